@@ -0,0 +1,155 @@
+//! Wizard interaktif `m4arch-cli calibrate`.
+//!
+//! Keyboard menampilkan warna uji dengan kalibrasi percobaan (lewat daemon)
+//! selagi pengguna menyesuaikan titik putih lalu gamma. Tidak ada yang
+//! disimpan sampai pengguna mengonfirmasi di akhir.
+use m4arch_core::config::calibration::GAMMA_RANGE;
+use m4arch_core::config::Calibration;
+use m4arch_core::error::M4ArchError;
+use m4arch_core::keyboard::Rgb;
+use m4arch_ipc::client::{preview_calibration, restore, set_calibration};
+use std::io::{self, BufRead, Write};
+
+const WHITE: Rgb = Rgb::new(255, 255, 255);
+
+/// Abu-abu 25%: paling mudah menunjukkan apakah nilai rendah terlalu gelap.
+const DARK_GREY: Rgb = Rgb::new(64, 64, 64);
+
+/// Faktor perubahan gamma untuk setiap `+`/`-`.
+const GAMMA_STEP: f32 = 1.1;
+
+/// Menjalankan wizard mulai dari kalibrasi `current`.
+///
+/// Mengembalikan kalibrasi yang disimpan, atau `None` jika dibatalkan.
+pub fn run(
+    current: Calibration,
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+) -> Result<Option<Calibration>, M4ArchError> {
+    let result = wizard(current, input, output);
+    // Apa pun hasilnya, keyboard kembali ke warna tersimpan
+    let restored = restore();
+    let saved = result?;
+    restored?;
+    Ok(saved)
+}
+
+fn wizard(
+    mut calibration: Calibration,
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+) -> Result<Option<Calibration>, M4ArchError> {
+    writeln!(
+        output,
+        "Colour calibration. The keyboard shows test colours while you answer;\n\
+         press Enter to keep the current value, or Ctrl-D to cancel.\n"
+    )?;
+
+    writeln!(
+        output,
+        "Step 1/2: white balance. The keyboard now shows white.\n\
+         If it looks tinted, lower the strongest channel, e.g. 'b -20' or 'g -10',\n\
+         or enter the white point as a colour (e.g. 255,230,200)."
+    )?;
+    loop {
+        preview_calibration(calibration, WHITE)?;
+        let Some(line) = prompt(
+            input,
+            output,
+            &format!("white point {}", calibration.white_point),
+        )?
+        else {
+            return Ok(None);
+        };
+        if line.is_empty() {
+            break;
+        }
+        match adjust_white_point(calibration.white_point, &line) {
+            Some(white) => calibration.white_point = white,
+            None => writeln!(output, "Expected 'r|g|b +N/-N' or a colour")?,
+        }
+    }
+
+    writeln!(
+        output,
+        "\nStep 2/2: brightness curve. The keyboard now shows a dark grey (25%).\n\
+         It should look clearly dimmer than white but not almost off.\n\
+         Enter '+' to brighten dark colours, '-' to darken them, or a gamma value\n\
+         ({} to {}).",
+        GAMMA_RANGE.start(),
+        GAMMA_RANGE.end()
+    )?;
+    loop {
+        preview_calibration(calibration, DARK_GREY)?;
+        let Some(line) = prompt(input, output, &format!("gamma {:.2}", calibration.gamma))? else {
+            return Ok(None);
+        };
+        if line.is_empty() {
+            break;
+        }
+        match adjust_gamma(calibration.gamma, &line) {
+            Some(gamma) => calibration.gamma = gamma,
+            None => writeln!(
+                output,
+                "Expected '+', '-' or a number from {} to {}",
+                GAMMA_RANGE.start(),
+                GAMMA_RANGE.end()
+            )?,
+        }
+    }
+
+    let answer = prompt(input, output, "Save this calibration? [Y/n]")?;
+    match answer.as_deref() {
+        Some("" | "y" | "yes") => {
+            set_calibration(calibration)?;
+            Ok(Some(calibration))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Menampilkan `label` dan membaca satu baris; `None` saat input berakhir.
+fn prompt(
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+    label: &str,
+) -> io::Result<Option<String>> {
+    write!(output, "{}> ", label)?;
+    output.flush()?;
+    let mut line = String::new();
+    if input.read_line(&mut line)? == 0 {
+        writeln!(output)?;
+        return Ok(None);
+    }
+    Ok(Some(line.trim().to_ascii_lowercase()))
+}
+
+/// `r -20`, `b +5` mengubah satu kanal; teks lain dibaca sebagai warna.
+fn adjust_white_point(white: Rgb, line: &str) -> Option<Rgb> {
+    let mut parts = line.split_whitespace();
+    if let (Some(channel), Some(delta), None) = (parts.next(), parts.next(), parts.next()) {
+        let delta: i16 = delta.parse().ok()?;
+        let shift = |value: u8| (i16::from(value) + delta).clamp(0, 255) as u8;
+        let mut white = white;
+        match channel {
+            "r" | "red" => white.r = shift(white.r),
+            "g" | "green" => white.g = shift(white.g),
+            "b" | "blue" => white.b = shift(white.b),
+            _ => return None,
+        }
+        return Some(white);
+    }
+    line.parse().ok()
+}
+
+fn adjust_gamma(gamma: f32, line: &str) -> Option<f32> {
+    let gamma = match line {
+        "+" => gamma / GAMMA_STEP,
+        "-" => gamma * GAMMA_STEP,
+        value => value
+            .parse()
+            .ok()
+            .filter(|gamma| GAMMA_RANGE.contains(gamma))?,
+    };
+    Some(gamma.clamp(*GAMMA_RANGE.start(), *GAMMA_RANGE.end()))
+}
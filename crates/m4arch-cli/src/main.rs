@@ -1,11 +1,35 @@
-use clap::{Parser, Subcommand};
-use m4arch_service::{
-    brightness_info, decrease_brightness, get_max_brightness, increase_brightness, set_brightness,
-    set_rgb_color,
+use clap::error::ErrorKind;
+use clap::{Args, CommandFactory, Parser, Subcommand};
+use m4arch_ipc::client::{
+    self, apply_profile, battery_info, brightness_info, cpu_status, decrease_brightness,
+    delete_profile, fade_brightness, fade_rgb_color, fade_to_profile, fade_zone_rgb_color,
+    get_calibration, get_charge_thresholds, get_effect, get_fan_settings, get_fans,
+    get_keyboard_mode, get_max_brightness, get_power_profile, get_schedule, get_zones,
+    increase_brightness, keyboard_modes, list_power_profiles, list_profiles, restore, save_profile,
+    set_brightness, set_brightness_percent, set_calibration, set_charge_thresholds, set_effect,
+    set_fan_settings, set_keyboard_mode, set_power_profile, set_rgb_color, set_schedule,
+    set_zone_rgb_color, step_brightness, stop_effect, tune_cpu, Mode,
 };
 
+use m4arch_core::battery::{BatteryInfo, BatteryState, ChargePreset, ChargeThresholds};
+use m4arch_core::config::{
+    Calibration, ChannelGain, FanMode, FanSettings, Location, Schedule, ScheduleAction,
+    ScheduleEntry, ScheduleTime,
+};
+use m4arch_core::cpu::{CpuStatus, CpuTuning};
+use m4arch_core::error::M4ArchError;
+use m4arch_core::fan::{CurvePoint, FanCurve, FanInfo};
+use m4arch_core::keyboard::{
+    brightness, Effect, EffectKind, KeyboardMode, Rgb, StepDirection, Transition,
+};
+use m4arch_core::power::PowerProfile;
 use serde_json::json;
+use std::fmt;
+use std::io;
 use std::process;
+use std::time::Duration;
+
+mod calibrate;
 
 #[derive(Parser, Debug)]
 #[command(
@@ -15,7 +39,10 @@ use std::process;
     long_about = r#"
         m4arch-cli allows you to control keyboard brightness and RGB lighting.
         
-        ⚠️  Some commands require write access to sysfs (may need sudo or udev rules).
+        Commands are sent to m4arch-daemon when it is running. Without the daemon
+        (or with --mode direct) they access sysfs directly.
+
+        ⚠️  Direct mode requires write access to sysfs (may need sudo or udev rules).
     "#,
     after_help = r#"
         EXAMPLES:
@@ -23,12 +50,52 @@ use std::process;
         m4arch-cli get-max-brightness
         m4arch-cli set-brightness 128
         m4arch-cli set-brightness 0 --off
+        m4arch-cli set-brightness 40%
+        m4arch-cli set-brightness 200 --fade 500
         m4arch-cli increase-brightness 10
-        m4arch-cli decrease-brightness 10
+        m4arch-cli decrease-brightness 5%
+        m4arch-cli increase-brightness --steps
+        m4arch-cli decrease-brightness --steps 8
         m4arch-cli set-rgb 255 0 0
+        m4arch-cli set-rgb '#ff8800'
+        m4arch-cli set-rgb orange --fade 500
+        m4arch-cli set-rgb 'hsv(200, 80%, 100%)' --zone 2
+        m4arch-cli list-zones
+        m4arch-cli restore
+        m4arch-cli effect breathe --speed 3 --color red --color '#0000ff'
+        m4arch-cli effect off
+        m4arch-cli mode list
+        m4arch-cli mode set wave
+        m4arch-cli profile save office
+        m4arch-cli profile apply office
+        m4arch-cli profile apply night --fade 1000
+        m4arch-cli profile list
+        m4arch-cli profile rm office
+        m4arch-cli schedule add 22:00 --profile night
+        m4arch-cli schedule add sunrise+15 --brightness 200
+        m4arch-cli schedule location -6.2 106.8
+        m4arch-cli schedule list
+        m4arch-cli schedule rm 0
+        m4arch-cli calibrate
+        m4arch-cli calibrate set --gamma 0.8 --white-point 255,230,200
+        m4arch-cli calibrate show
+        m4arch-cli fan
+        m4arch-cli fan curve --cpu 50:20,70:50,85:100 --hysteresis 5
+        m4arch-cli fan auto
+        m4arch-cli power list
+        m4arch-cli power set quiet
+        m4arch-cli power get
+        m4arch-cli cpu
+        m4arch-cli cpu set --epp balance_power --turbo off
+        m4arch-cli charge
+        m4arch-cli charge set stationary
+        m4arch-cli charge set 50-85
+        m4arch-cli battery --json
+
+        m4arch-cli --mode direct set-brightness 0
 
         NOTES:
-        • Commands that modify hardware may require root privileges
+        • Without m4arch-daemon, commands that modify hardware may require root privileges
         • Use with caution to avoid hardware misuse
     "#
 )]
@@ -37,6 +104,10 @@ struct Cli {
     #[arg(long, global = true)]
     json: bool,
 
+    /// How to reach the hardware: auto, daemon or direct
+    #[arg(long, global = true, default_value = "auto")]
+    mode: Mode,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -45,9 +116,13 @@ struct Cli {
 enum Commands {
     /// Set the keyboard backlight brightness (0 to turn off)
     SetBrightness {
-        /// Brightness level (0–255)
-        #[arg(value_parser = clap::value_parser!(u8).range(0..=255))]
-        level: u8,
+        /// Raw brightness level (0 to max), or a percentage such as 40%
+        #[arg(value_parser = parse_brightness)]
+        level: BrightnessValue,
+
+        /// Fade to the new level over this many milliseconds
+        #[arg(long, value_name = "MS")]
+        fade: Option<u64>,
     },
 
     /// Get the current keyboard backlight brightness
@@ -58,39 +133,652 @@ enum Commands {
 
     /// Increase keyboard backlight brightness
     IncreaseBrightness {
-        /// Step value
-        step: u8,
+        #[command(flatten)]
+        step: StepArgs,
     },
 
     /// Decrease keyboard backlight brightness
     DecreaseBrightness {
-        /// Step value
-        step: u8,
+        #[command(flatten)]
+        step: StepArgs,
     },
 
     /// Set the RGB color of the keyboard backlight
-    SetRgb { r: u8, g: u8, b: u8 },
+    SetRgb {
+        /// Color as R G B, #rrggbb, a CSS name, hsv(H,S%,V%) or hsl(H,S%,L%)
+        #[arg(num_args = 1..=3, required = true, value_name = "COLOR")]
+        color: Vec<String>,
+
+        /// Only change this zone (see `list-zones`); default is all zones
+        #[arg(long)]
+        zone: Option<usize>,
+
+        /// Fade to the new color over this many milliseconds
+        #[arg(long, value_name = "MS")]
+        fade: Option<u64>,
+    },
+
+    /// List the color zones of the keyboard
+    ListZones,
+
+    /// Reapply the last saved brightness and color
+    Restore,
+
+    /// Start a lighting effect (breathe, cycle, wave, pulse, strobe) or stop it (off).
+    /// Without an effect name, show the running effect. Requires m4arch-daemon.
+    Effect {
+        /// Effect name, or "off"
+        #[arg(value_parser = parse_effect_kind)]
+        kind: Option<EffectChoice>,
+
+        /// Animation speed (1 = slow, 10 = fast)
+        #[arg(long, default_value_t = Effect::DEFAULT_SPEED,
+              value_parser = clap::value_parser!(u8).range(Effect::MIN_SPEED as i64..=Effect::MAX_SPEED as i64))]
+        speed: u8,
+
+        /// Color as R,G,B, #rrggbb, a CSS name or hsv()/hsl(); repeat to alternate colors
+        #[arg(long = "color", value_parser = parse_rgb)]
+        colors: Vec<Rgb>,
+    },
+
+    /// Show or change the lighting mode built into the keyboard firmware
+    /// (only on keyboards that have one)
+    Mode {
+        #[command(subcommand)]
+        action: ModeCommand,
+    },
+
+    /// Manage named lighting profiles
+    Profile {
+        #[command(subcommand)]
+        action: ProfileAction,
+    },
+
+    /// Apply profiles or brightness levels at times of day. Requires m4arch-daemon.
+    Schedule {
+        #[command(subcommand)]
+        action: ScheduleCommand,
+    },
+
+    /// Calibrate LED colours; without a subcommand, start the interactive
+    /// wizard. Requires m4arch-daemon.
+    Calibrate {
+        #[command(subcommand)]
+        action: Option<CalibrateCommand>,
+    },
+
+    /// Show fan speeds and temperatures, or choose who controls the fans.
+    /// Fan curves require m4arch-daemon.
+    Fan {
+        #[command(subcommand)]
+        action: Option<FanCommand>,
+    },
+
+    /// Show or change the performance profile (quiet, balanced, performance)
+    Power {
+        #[command(subcommand)]
+        action: PowerCommand,
+    },
+
+    /// Show or change the CPU governor, energy preference (EPP) and turbo
+    /// boost. Changes last until the power profile is changed.
+    Cpu {
+        #[command(subcommand)]
+        action: Option<CpuCommand>,
+    },
+
+    /// Show or limit battery charging (full, balanced 60-90%, stationary 40-80%)
+    Charge {
+        #[command(subcommand)]
+        action: Option<ChargeCommand>,
+    },
+
+    /// Show battery status, health and estimated time remaining
+    Battery,
+}
+
+/// Besar langkah untuk `increase-brightness`/`decrease-brightness`.
+#[derive(Args, Debug)]
+struct StepArgs {
+    /// Raw step value, or a percentage of the maximum such as 10%
+    #[arg(
+        value_parser = parse_brightness,
+        required_unless_present = "steps",
+        conflicts_with = "steps"
+    )]
+    step: Option<BrightnessValue>,
+
+    /// Move along a perceptual scale of N steps instead, so every step looks
+    /// equally large on any hardware (default 10)
+    #[arg(long, value_name = "N", num_args = 0..=1, default_missing_value = "10")]
+    steps: Option<u8>,
+}
+
+/// Brightness mentah atau persen dari brightness maksimum.
+#[derive(Clone, Copy, Debug)]
+enum BrightnessValue {
+    Level(u8),
+    Percent(u8),
+}
+
+impl BrightnessValue {
+    /// Nilai mentah untuk keyboard dengan brightness maksimum `max`.
+    fn level(self, max: u8) -> u8 {
+        match self {
+            BrightnessValue::Level(level) => level.min(max),
+            BrightnessValue::Percent(percent) => brightness::level_for_percent(percent, max),
+        }
+    }
+}
+
+impl fmt::Display for BrightnessValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BrightnessValue::Level(level) => write!(f, "{}", level),
+            BrightnessValue::Percent(percent) => write!(f, "{}%", percent),
+        }
+    }
+}
+
+#[derive(Subcommand, Debug)]
+enum ProfileAction {
+    /// Save the current brightness and color as a profile
+    Save {
+        /// Profile name (letters, digits, '-' and '_')
+        name: String,
+    },
+
+    /// Apply a saved profile
+    Apply {
+        name: String,
+
+        /// Fade to the profile over this many milliseconds
+        #[arg(long, value_name = "MS")]
+        fade: Option<u64>,
+    },
+
+    /// List saved profiles
+    List,
+
+    /// Delete a saved profile
+    Rm { name: String },
+}
+
+#[derive(Subcommand, Debug)]
+enum ScheduleCommand {
+    /// List schedule entries and their index
+    List,
+
+    /// Add an entry that applies a profile or a brightness level every day
+    Add {
+        /// HH:MM, sunrise or sunset, optionally shifted in minutes (sunset+30)
+        #[arg(value_parser = parse_schedule_time)]
+        at: ScheduleTime,
+
+        /// Profile to apply
+        #[arg(
+            long,
+            conflicts_with = "brightness",
+            required_unless_present = "brightness"
+        )]
+        profile: Option<String>,
+
+        /// Brightness level (0–255) to set
+        #[arg(long)]
+        brightness: Option<u8>,
+    },
+
+    /// Remove the entry with this index (see `schedule list`)
+    Rm { index: usize },
+
+    /// Set the location used to compute sunrise and sunset, in degrees
+    Location {
+        /// Latitude, positive north
+        #[arg(allow_negative_numbers = true)]
+        latitude: f64,
+
+        /// Longitude, positive east
+        #[arg(allow_negative_numbers = true)]
+        longitude: f64,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum CalibrateCommand {
+    /// Show the current calibration
+    Show,
+
+    /// Change calibration values; values not given are kept
+    Set {
+        /// Brightness curve exponent; below 1 brightens dark colours
+        #[arg(long)]
+        gamma: Option<f32>,
+
+        /// Channel gains as R,G,B from 0 to 1
+        #[arg(long, value_parser = parse_gain)]
+        gain: Option<ChannelGain>,
+
+        /// Raw color that looks neutral white on this keyboard
+        #[arg(long, value_parser = parse_rgb)]
+        white_point: Option<Rgb>,
+    },
+
+    /// Remove the calibration
+    Reset,
+}
+
+#[derive(Subcommand, Debug)]
+enum FanCommand {
+    /// Show fan speeds, temperatures and the control mode (default)
+    Status,
+
+    /// Let the firmware control the fans
+    Auto,
+
+    /// Drive the fans from temperature curves; values not given are kept
+    Curve {
+        /// CPU fan curve as TEMP:DUTY points, e.g. 50:20,70:50,85:100; the
+        /// last point must be 100% at 90°C or below
+        #[arg(long, value_parser = parse_curve)]
+        cpu: Option<FanCurve>,
+
+        /// GPU fan curve as TEMP:DUTY points
+        #[arg(long, value_parser = parse_curve)]
+        gpu: Option<FanCurve>,
+
+        /// Degrees the temperature must fall before a fan slows down
+        #[arg(long)]
+        hysteresis: Option<u8>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum ModeCommand {
+    /// List the modes this keyboard supports
+    List,
+
+    /// Show the active mode
+    Get,
+
+    /// Switch to another mode; static colors and effects switch back to static
+    Set {
+        #[arg(value_parser = parse_keyboard_mode)]
+        mode: KeyboardMode,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum PowerCommand {
+    /// Show the active profile
+    Get,
+
+    /// Switch to another profile; the daemon reapplies it after boot and resume
+    Set {
+        #[arg(value_parser = parse_power_profile)]
+        profile: PowerProfile,
+    },
+
+    /// List the profiles this laptop supports
+    List,
+}
+
+#[derive(Subcommand, Debug)]
+enum CpuCommand {
+    /// Show governor, energy preference and turbo for every policy (default)
+    Status,
+
+    /// Change CPU tuning; values not given are kept
+    Set {
+        /// Scaling governor, e.g. powersave or performance
+        #[arg(long)]
+        governor: Option<String>,
+
+        /// Energy performance preference, e.g. balance_power
+        #[arg(long)]
+        epp: Option<String>,
+
+        /// Turbo boost: on or off
+        #[arg(long, value_parser = parse_on_off)]
+        turbo: Option<bool>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum ChargeCommand {
+    /// Show the charge thresholds (default)
+    Status,
+
+    /// Change the charge thresholds; the daemon reapplies them at boot
+    Set {
+        /// A preset (full, balanced, stationary) or START-END in percent, e.g. 50-85
+        #[arg(value_parser = parse_charge_thresholds)]
+        thresholds: ChargeThresholds,
+    },
+}
+
+#[derive(Clone, Copy, Debug)]
+enum EffectChoice {
+    Start(EffectKind),
+    Off,
+}
+
+fn parse_effect_kind(value: &str) -> Result<EffectChoice, String> {
+    if value.eq_ignore_ascii_case("off") {
+        return Ok(EffectChoice::Off);
+    }
+    value.parse().map(EffectChoice::Start).map_err(|_| {
+        let names: Vec<String> = EffectKind::ALL.iter().map(|k| k.to_string()).collect();
+        format!("expected one of: {}, off", names.join(", "))
+    })
+}
+
+fn parse_keyboard_mode(value: &str) -> Result<KeyboardMode, String> {
+    value.parse().map_err(|_| {
+        let names: Vec<String> = KeyboardMode::ALL.iter().map(|m| m.to_string()).collect();
+        format!("expected one of: {}", names.join(", "))
+    })
+}
+
+fn parse_power_profile(value: &str) -> Result<PowerProfile, String> {
+    value.parse().map_err(|_| {
+        let names: Vec<String> = PowerProfile::ALL.iter().map(|p| p.to_string()).collect();
+        format!("expected one of: {}", names.join(", "))
+    })
+}
+
+fn parse_charge_thresholds(value: &str) -> Result<ChargeThresholds, String> {
+    if let Ok(preset) = value.parse::<ChargePreset>() {
+        return Ok(preset.thresholds());
+    }
+    let invalid = || {
+        let names: Vec<String> = ChargePreset::ALL.iter().map(|p| p.to_string()).collect();
+        format!(
+            "expected one of: {}, or START-END such as 50-85",
+            names.join(", ")
+        )
+    };
+    let (start, end) = value.split_once('-').ok_or_else(invalid)?;
+    let thresholds = ChargeThresholds::new(
+        start.trim().parse().map_err(|_| invalid())?,
+        end.trim().parse().map_err(|_| invalid())?,
+    );
+    thresholds
+        .validate()
+        .map_err(|_| "START must be below END, and END at most 100".to_string())?;
+    Ok(thresholds)
+}
+
+fn describe_charge(thresholds: ChargeThresholds) -> String {
+    match ChargePreset::matching(thresholds) {
+        Some(preset) => format!("{} ({})", thresholds, preset),
+        None => thresholds.to_string(),
+    }
+}
+
+fn print_battery(battery: &BatteryInfo) {
+    let capacity = battery
+        .capacity
+        .map_or_else(|| "-".to_string(), |capacity| format!("{}%", capacity));
+    println!("{}: {}, {}", battery.name, capacity, battery.state);
+    if let Some(remaining) = battery.time_remaining() {
+        let until = match battery.state {
+            BatteryState::Charging => "until full",
+            _ => "until empty",
+        };
+        println!("  Remaining : {} {}", format_duration(remaining), until);
+    }
+    if let Some(power) = battery.power_mw {
+        println!("  Power     : {:.1} W", f64::from(power) / 1000.0);
+    }
+    if let Some(voltage) = battery.voltage_mv {
+        println!("  Voltage   : {:.2} V", f64::from(voltage) / 1000.0);
+    }
+    if let (Some(health), Some(full), Some(design)) = (
+        battery.health(),
+        battery.energy_full_mwh,
+        battery.energy_full_design_mwh,
+    ) {
+        println!(
+            "  Health    : {:.0}% ({:.1} of {:.1} Wh)",
+            health,
+            f64::from(full) / 1000.0,
+            f64::from(design) / 1000.0
+        );
+    }
+    if let Some(cycles) = battery.cycle_count {
+        println!("  Cycles    : {}", cycles);
+    }
+}
+
+/// `BatteryInfo` beserta nilai turunannya untuk keluaran JSON.
+fn battery_json(battery: &BatteryInfo) -> serde_json::Value {
+    let mut value = json!(battery);
+    value["health_percent"] = json!(battery.health().map(|health| health.round()));
+    value["time_remaining_secs"] = json!(battery.time_remaining().map(|t| t.as_secs()));
+    value
+}
+
+fn format_duration(duration: Duration) -> String {
+    let minutes = duration.as_secs() / 60;
+    format!("{}h {:02}m", minutes / 60, minutes % 60)
+}
+
+fn parse_on_off(value: &str) -> Result<bool, String> {
+    match value.to_ascii_lowercase().as_str() {
+        "on" => Ok(true),
+        "off" => Ok(false),
+        _ => Err("expected on or off".to_string()),
+    }
+}
+
+fn parse_schedule_time(value: &str) -> Result<ScheduleTime, String> {
+    value
+        .parse()
+        .map_err(|_| "expected HH:MM, sunrise or sunset, optionally with +MIN or -MIN".to_string())
+}
+
+fn parse_brightness(value: &str) -> Result<BrightnessValue, String> {
+    match value.trim().strip_suffix('%') {
+        Some(percent) => match percent.trim().parse::<u8>() {
+            Ok(percent) if percent <= 100 => Ok(BrightnessValue::Percent(percent)),
+            _ => Err("expected a percentage from 0% to 100%".to_string()),
+        },
+        None => value
+            .trim()
+            .parse()
+            .map(BrightnessValue::Level)
+            .map_err(|_| "expected a level from 0 to 255 or a percentage such as 40%".to_string()),
+    }
+}
+
+/// Menaikkan atau menurunkan brightness sesuai `step`.
+fn step_brightness_by(direction: StepDirection, step: &StepArgs) -> Result<(), M4ArchError> {
+    match (step.steps, step.step) {
+        (Some(steps), _) => step_brightness(direction, steps),
+        (None, Some(value)) => {
+            let amount = match value {
+                BrightnessValue::Level(level) => level,
+                percent => percent.level(get_max_brightness()?),
+            };
+            match direction {
+                StepDirection::Up => increase_brightness(amount),
+                StepDirection::Down => decrease_brightness(amount),
+            }
+        }
+        (None, None) => Err(M4ArchError::InvalidValue),
+    }
+}
+
+fn parse_gain(value: &str) -> Result<ChannelGain, String> {
+    let gains: Vec<f32> = value
+        .split(',')
+        .map(|gain| gain.trim().parse().ok())
+        .collect::<Option<_>>()
+        .ok_or("expected R,G,B gains from 0 to 1")?;
+    match gains[..] {
+        [red, green, blue] => Ok(ChannelGain { red, green, blue }),
+        _ => Err("expected R,G,B gains from 0 to 1".to_string()),
+    }
+}
+
+/// `50:20,70:50,85:100`: suhu °C dan duty persen.
+fn parse_curve(value: &str) -> Result<FanCurve, String> {
+    let invalid = || format!("invalid fan curve '{}' (try 50:20,70:50,85:100)", value);
+    let points = value
+        .split(',')
+        .map(|point| {
+            let (temperature, duty) = point.trim().split_once(':')?;
+            Some(CurvePoint::new(
+                temperature.trim().parse().ok()?,
+                duty.trim().trim_end_matches('%').parse().ok()?,
+            ))
+        })
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(invalid)?;
+    let curve = FanCurve::new(points);
+    curve.validate().map_err(|_| {
+        format!(
+            "{}: temperatures must rise, duty must not fall and end at 100",
+            invalid()
+        )
+    })?;
+    Ok(curve)
+}
+
+fn format_curve(curve: &FanCurve) -> String {
+    let points: Vec<_> = curve
+        .points
+        .iter()
+        .map(|point| format!("{}°C {}%", point.temperature, point.duty))
+        .collect();
+    points.join(", ")
+}
+
+fn print_cpu(status: &CpuStatus) {
+    println!("Driver: {}", status.driver);
+    for policy in &status.policies {
+        let preference = policy.energy_preference.as_deref().unwrap_or("-");
+        println!(
+            "policy{}: governor {}, energy preference {}",
+            policy.id, policy.governor, preference
+        );
+    }
+    match status.turbo {
+        Some(true) => println!("Turbo: on"),
+        Some(false) => println!("Turbo: off"),
+        None => println!("Turbo: not controllable"),
+    }
+    if let Some(policy) = status.policies.first() {
+        println!("Governors: {}", policy.governors.join(", "));
+        if !policy.energy_preferences.is_empty() {
+            println!(
+                "Energy preferences: {}",
+                policy.energy_preferences.join(", ")
+            );
+        }
+    }
+}
+
+fn print_fans(fans: &[FanInfo], settings: &FanSettings) {
+    for info in fans {
+        println!(
+            "{} fan: {}% at {}°C",
+            info.fan, info.speed, info.temperature
+        );
+    }
+    match settings.mode {
+        FanMode::Auto => println!("Control: firmware"),
+        FanMode::Curve => {
+            println!("Control: curve (hysteresis {}°C)", settings.hysteresis);
+            println!("  CPU : {}", format_curve(&settings.cpu));
+            println!("  GPU : {}", format_curve(&settings.gpu));
+        }
+    }
+}
+
+fn print_calibration(calibration: &Calibration) {
+    let gain = calibration.gain;
+    println!("Calibration:");
+    println!("  Gamma       : {}", calibration.gamma);
+    println!(
+        "  Gain        : {}, {}, {}",
+        gain.red, gain.green, gain.blue
+    );
+    println!("  White point : {}", calibration.white_point);
+}
+
+/// Menjalankan `change` pada jadwal milik daemon lalu menyimpannya.
+fn update_schedule(
+    change: impl FnOnce(&mut Schedule) -> Result<(), M4ArchError>,
+) -> Result<(), M4ArchError> {
+    let mut schedule = get_schedule()?;
+    change(&mut schedule)?;
+    set_schedule(schedule)
+}
+
+/// `--fade 0` sama dengan tanpa transisi.
+fn transition(fade: Option<u64>) -> Option<Transition> {
+    fade.filter(|&ms| ms > 0).map(Transition::from_millis)
+}
+
+fn parse_rgb(value: &str) -> Result<Rgb, String> {
+    value.parse().map_err(|_| {
+        format!(
+            "invalid color '{}' (try 255,136,0, #ff8800, orange or hsv(32,100%,100%))",
+            value
+        )
+    })
+}
+
+/// Warna `set-rgb`: satu argumen notasi warna, atau tiga kanal `R G B`.
+fn parse_color_args(args: &[String]) -> Result<Rgb, String> {
+    match args {
+        [color] => parse_rgb(color),
+        [r, g, b] => {
+            // Koma dibuang agar `hsv(30, 100%, 100%)` tanpa tanda kutip juga bisa
+            let parts = [r, g, b].map(|part| part.trim_end_matches(','));
+            parse_rgb(&parts.join(","))
+        }
+        _ => Err("expected one color or three channels R G B".to_string()),
+    }
 }
 
 fn main() {
     let cli = Cli::parse();
+    client::set_mode(cli.mode);
 
     let result = match cli.command {
-        Commands::SetBrightness { level } => {
+        Commands::SetBrightness { level, fade } => {
+            let enabled = !matches!(
+                level,
+                BrightnessValue::Level(0) | BrightnessValue::Percent(0)
+            );
             if !cli.json {
                 println!(
                     "Setting brightness to {} (backlight: {})",
                     level,
-                    if level > 0 { "ON" } else { "OFF" }
+                    if enabled { "ON" } else { "OFF" }
                 );
             }
 
-            set_brightness(level).map(|_| {
+            let result = match (transition(fade), level) {
+                (None, BrightnessValue::Level(level)) => set_brightness(level),
+                (None, BrightnessValue::Percent(percent)) => set_brightness_percent(percent),
+                // Transisi butuh nilai mentah
+                (Some(transition), level) => get_max_brightness()
+                    .and_then(|max| fade_brightness(level.level(max), transition)),
+            };
+            result.map(|_| {
                 if cli.json {
-                    println!(
-                        "{}",
-                        json!({ "status": "ok", "level": level, "enabled": level > 0 })
-                    );
+                    let reply = match level {
+                        BrightnessValue::Level(level) => {
+                            json!({ "status": "ok", "level": level, "enabled": enabled })
+                        }
+                        BrightnessValue::Percent(percent) => {
+                            json!({ "status": "ok", "percent": percent, "enabled": enabled })
+                        }
+                    };
+                    println!("{}", reply);
                 }
             })
         }
@@ -123,30 +811,462 @@ fn main() {
             Err(e) => Err(e),
         },
 
-        Commands::IncreaseBrightness { step } => increase_brightness(step).map(|_| {
+        Commands::IncreaseBrightness { step } => {
+            step_brightness_by(StepDirection::Up, &step).map(|_| {
+                if cli.json {
+                    println!(
+                        "{}",
+                        json!({
+                            "status": "ok",
+                            "action": "increase",
+                            "step": step.step.map(|value| value.to_string()),
+                            "steps": step.steps,
+                        })
+                    );
+                }
+            })
+        }
+
+        Commands::DecreaseBrightness { step } => step_brightness_by(StepDirection::Down, &step)
+            .map(|_| {
+                if cli.json {
+                    println!(
+                        "{}",
+                        json!({
+                            "status": "ok",
+                            "action": "decrease",
+                            "step": step.step.map(|value| value.to_string()),
+                            "steps": step.steps,
+                        })
+                    );
+                }
+            }),
+
+        Commands::SetRgb { color, zone, fade } => {
+            let Rgb { r, g, b } = parse_color_args(&color).unwrap_or_else(|message| {
+                Cli::command()
+                    .error(ErrorKind::ValueValidation, message)
+                    .exit()
+            });
+            let result = match (zone, transition(fade)) {
+                (Some(zone), Some(transition)) => fade_zone_rgb_color(zone, r, g, b, transition),
+                (Some(zone), None) => set_zone_rgb_color(zone, r, g, b),
+                (None, Some(transition)) => fade_rgb_color(r, g, b, transition),
+                (None, None) => set_rgb_color(r, g, b),
+            };
+            result.map(|_| {
+                if cli.json {
+                    println!(
+                        "{}",
+                        json!({ "status": "ok", "rgb": { "r": r, "g": g, "b": b }, "zone": zone })
+                    );
+                }
+            })
+        }
+
+        Commands::ListZones => get_zones().map(|zones| {
             if cli.json {
-                println!(
-                    "{}",
-                    json!({ "status": "ok", "action": "increase", "step": step })
-                );
+                println!("{}", json!(zones));
+            } else {
+                println!("Keyboard zones:");
+                for zone in zones {
+                    println!("  {} : {}", zone.index, zone.name);
+                }
             }
         }),
 
-        Commands::DecreaseBrightness { step } => decrease_brightness(step).map(|_| {
+        Commands::Restore => restore().map(|_| {
             if cli.json {
-                println!(
-                    "{}",
-                    json!({ "status": "ok", "action": "decrease", "step": step })
-                );
+                println!("{}", json!({ "status": "ok", "action": "restore" }));
+            } else {
+                println!("Restored saved keyboard state");
+            }
+        }),
+
+        Commands::Effect {
+            kind: Some(EffectChoice::Start(kind)),
+            speed,
+            colors,
+        } => {
+            let effect = Effect {
+                kind,
+                speed,
+                colors,
+            };
+            set_effect(effect.clone()).map(|_| {
+                if cli.json {
+                    println!("{}", json!({ "status": "ok", "effect": effect }));
+                } else {
+                    println!("Started effect '{}' (speed {})", effect.kind, effect.speed);
+                }
+            })
+        }
+
+        Commands::Effect {
+            kind: Some(EffectChoice::Off),
+            ..
+        } => stop_effect().map(|_| {
+            if cli.json {
+                println!("{}", json!({ "status": "ok", "effect": null }));
+            } else {
+                println!("Stopped lighting effect");
             }
         }),
 
-        Commands::SetRgb { r, g, b } => set_rgb_color(r, g, b).map(|_| {
+        Commands::Effect { kind: None, .. } => get_effect().map(|effect| {
             if cli.json {
-                println!(
-                    "{}",
-                    json!({ "status": "ok", "rgb": { "r": r, "g": g, "b": b } })
-                );
+                println!("{}", json!(effect));
+            } else {
+                match effect {
+                    Some(effect) => println!("Effect: {} (speed {})", effect.kind, effect.speed),
+                    None => println!("No effect running"),
+                }
+            }
+        }),
+
+        Commands::Profile { action } => match action {
+            ProfileAction::Save { name } => save_profile(&name).map(|_| {
+                if cli.json {
+                    println!(
+                        "{}",
+                        json!({ "status": "ok", "action": "save", "profile": name })
+                    );
+                } else {
+                    println!("Saved profile '{}'", name);
+                }
+            }),
+            ProfileAction::Apply { name, fade } => match transition(fade) {
+                Some(transition) => fade_to_profile(&name, transition),
+                None => apply_profile(&name),
+            }
+            .map(|_| {
+                if cli.json {
+                    println!(
+                        "{}",
+                        json!({ "status": "ok", "action": "apply", "profile": name })
+                    );
+                } else {
+                    println!("Applied profile '{}'", name);
+                }
+            }),
+            ProfileAction::List => list_profiles().map(|names| {
+                if cli.json {
+                    println!("{}", json!(names));
+                } else if names.is_empty() {
+                    println!("No saved profiles");
+                } else {
+                    println!("Profiles:");
+                    for name in names {
+                        println!("  {}", name);
+                    }
+                }
+            }),
+            ProfileAction::Rm { name } => delete_profile(&name).map(|_| {
+                if cli.json {
+                    println!(
+                        "{}",
+                        json!({ "status": "ok", "action": "rm", "profile": name })
+                    );
+                } else {
+                    println!("Deleted profile '{}'", name);
+                }
+            }),
+        },
+
+        Commands::Schedule { action } => match action {
+            ScheduleCommand::List => get_schedule().map(|schedule| {
+                if cli.json {
+                    println!("{}", json!(schedule));
+                    return;
+                }
+                if schedule.entries.is_empty() {
+                    println!("No schedule entries");
+                } else {
+                    println!("Schedule:");
+                    for (index, entry) in schedule.entries.iter().enumerate() {
+                        println!("  {} : {}", index, entry);
+                    }
+                }
+                if let Some(location) = schedule.location {
+                    println!("Location: {}, {}", location.latitude, location.longitude);
+                }
+            }),
+            ScheduleCommand::Add {
+                at,
+                profile,
+                brightness,
+            } => {
+                let action = match (profile, brightness) {
+                    (Some(name), _) => ScheduleAction::Profile(name),
+                    (None, level) => ScheduleAction::Brightness(level.unwrap_or_default()),
+                };
+                let entry = ScheduleEntry { at, action };
+                update_schedule(|schedule| {
+                    schedule.entries.push(entry.clone());
+                    Ok(())
+                })
+                .map(|_| {
+                    if cli.json {
+                        println!(
+                            "{}",
+                            json!({ "status": "ok", "action": "add", "entry": entry })
+                        );
+                    } else {
+                        println!("Added schedule entry: {}", entry);
+                    }
+                })
+            }
+            ScheduleCommand::Rm { index } => update_schedule(|schedule| {
+                if index >= schedule.entries.len() {
+                    return Err(M4ArchError::InvalidValue);
+                }
+                schedule.entries.remove(index);
+                Ok(())
+            })
+            .map(|_| {
+                if cli.json {
+                    println!(
+                        "{}",
+                        json!({ "status": "ok", "action": "rm", "index": index })
+                    );
+                } else {
+                    println!("Removed schedule entry {}", index);
+                }
+            }),
+            ScheduleCommand::Location {
+                latitude,
+                longitude,
+            } => {
+                let location = Location {
+                    latitude,
+                    longitude,
+                };
+                update_schedule(|schedule| {
+                    location.validate()?;
+                    schedule.location = Some(location);
+                    Ok(())
+                })
+                .map(|_| {
+                    if cli.json {
+                        println!(
+                            "{}",
+                            json!({ "status": "ok", "action": "location", "location": location })
+                        );
+                    } else {
+                        println!("Schedule location set to {}, {}", latitude, longitude);
+                    }
+                })
+            }
+        },
+
+        Commands::Calibrate { action } => match action {
+            None => get_calibration()
+                .and_then(|current| {
+                    calibrate::run(current, &mut io::stdin().lock(), &mut io::stdout())
+                })
+                .map(|saved| match saved {
+                    Some(calibration) => {
+                        if cli.json {
+                            println!("{}", json!({ "status": "ok", "calibration": calibration }));
+                        } else {
+                            println!("Saved calibration");
+                        }
+                    }
+                    None => {
+                        if cli.json {
+                            println!("{}", json!({ "status": "cancelled" }));
+                        } else {
+                            println!("Calibration not changed");
+                        }
+                    }
+                }),
+            Some(CalibrateCommand::Show) => get_calibration().map(|calibration| {
+                if cli.json {
+                    println!("{}", json!(calibration));
+                } else {
+                    print_calibration(&calibration);
+                }
+            }),
+            Some(CalibrateCommand::Set {
+                gamma,
+                gain,
+                white_point,
+            }) => get_calibration()
+                .and_then(|mut calibration| {
+                    calibration.gamma = gamma.unwrap_or(calibration.gamma);
+                    calibration.gain = gain.unwrap_or(calibration.gain);
+                    calibration.white_point = white_point.unwrap_or(calibration.white_point);
+                    set_calibration(calibration).map(|_| calibration)
+                })
+                .map(|calibration| {
+                    if cli.json {
+                        println!("{}", json!({ "status": "ok", "calibration": calibration }));
+                    } else {
+                        print_calibration(&calibration);
+                    }
+                }),
+            Some(CalibrateCommand::Reset) => set_calibration(Calibration::default()).map(|_| {
+                if cli.json {
+                    println!("{}", json!({ "status": "ok", "action": "reset" }));
+                } else {
+                    println!("Calibration removed");
+                }
+            }),
+        },
+
+        Commands::Fan { action } => match action.unwrap_or(FanCommand::Status) {
+            FanCommand::Status => get_fans().and_then(|fans| {
+                let settings = get_fan_settings()?;
+                if cli.json {
+                    println!("{}", json!({ "fans": fans, "settings": settings }));
+                } else {
+                    print_fans(&fans, &settings);
+                }
+                Ok(())
+            }),
+            FanCommand::Auto => get_fan_settings()
+                .and_then(|mut settings| {
+                    settings.mode = FanMode::Auto;
+                    set_fan_settings(settings)
+                })
+                .map(|_| {
+                    if cli.json {
+                        println!("{}", json!({ "status": "ok", "mode": FanMode::Auto }));
+                    } else {
+                        println!("Fans are controlled by the firmware");
+                    }
+                }),
+            FanCommand::Curve {
+                cpu,
+                gpu,
+                hysteresis,
+            } => get_fan_settings()
+                .and_then(|mut settings| {
+                    settings.mode = FanMode::Curve;
+                    settings.cpu = cpu.unwrap_or(settings.cpu);
+                    settings.gpu = gpu.unwrap_or(settings.gpu);
+                    settings.hysteresis = hysteresis.unwrap_or(settings.hysteresis);
+                    set_fan_settings(settings.clone()).map(|_| settings)
+                })
+                .map(|settings| {
+                    if cli.json {
+                        println!("{}", json!({ "status": "ok", "settings": settings }));
+                    } else {
+                        println!("Fans follow the temperature curves");
+                        println!("  CPU : {}", format_curve(&settings.cpu));
+                        println!("  GPU : {}", format_curve(&settings.gpu));
+                    }
+                }),
+        },
+
+        Commands::Mode { action } => match action {
+            ModeCommand::List => keyboard_modes().map(|modes| {
+                if cli.json {
+                    println!("{}", json!(modes));
+                } else if modes.is_empty() {
+                    println!("This keyboard has no firmware modes");
+                } else {
+                    for mode in modes {
+                        println!("{}", mode);
+                    }
+                }
+            }),
+            ModeCommand::Get => get_keyboard_mode().map(|mode| {
+                if cli.json {
+                    println!("{}", json!({ "mode": mode }));
+                } else {
+                    println!("Keyboard mode: {}", mode);
+                }
+            }),
+            ModeCommand::Set { mode } => set_keyboard_mode(mode).map(|_| {
+                if cli.json {
+                    println!("{}", json!({ "status": "ok", "mode": mode }));
+                } else {
+                    println!("Keyboard mode set to {}", mode);
+                }
+            }),
+        },
+
+        Commands::Power { action } => match action {
+            PowerCommand::Get => get_power_profile().map(|profile| {
+                if cli.json {
+                    println!("{}", json!({ "profile": profile }));
+                } else {
+                    println!("Power profile: {}", profile);
+                }
+            }),
+            PowerCommand::Set { profile } => set_power_profile(profile).map(|_| {
+                if cli.json {
+                    println!("{}", json!({ "status": "ok", "profile": profile }));
+                } else {
+                    println!("Power profile set to {}", profile);
+                }
+            }),
+            PowerCommand::List => list_power_profiles().map(|profiles| {
+                if cli.json {
+                    println!("{}", json!(profiles));
+                } else {
+                    for profile in profiles {
+                        println!("{}", profile);
+                    }
+                }
+            }),
+        },
+
+        Commands::Cpu { action } => match action.unwrap_or(CpuCommand::Status) {
+            CpuCommand::Status => cpu_status().map(|status| {
+                if cli.json {
+                    println!("{}", json!(status));
+                } else {
+                    print_cpu(&status);
+                }
+            }),
+            CpuCommand::Set {
+                governor,
+                epp,
+                turbo,
+            } => {
+                let tuning = CpuTuning {
+                    governor,
+                    energy_preference: epp,
+                    turbo,
+                };
+                tune_cpu(tuning.clone()).map(|_| {
+                    if cli.json {
+                        println!("{}", json!({ "status": "ok", "tuning": tuning }));
+                    } else {
+                        println!("CPU tuning updated");
+                    }
+                })
+            }
+        },
+
+        Commands::Charge { action } => match action.unwrap_or(ChargeCommand::Status) {
+            ChargeCommand::Status => get_charge_thresholds().map(|thresholds| {
+                if cli.json {
+                    let preset = ChargePreset::matching(thresholds);
+                    println!("{}", json!({ "thresholds": thresholds, "preset": preset }));
+                } else {
+                    println!("Charging: {}", describe_charge(thresholds));
+                }
+            }),
+            ChargeCommand::Set { thresholds } => set_charge_thresholds(thresholds).map(|_| {
+                if cli.json {
+                    println!("{}", json!({ "status": "ok", "thresholds": thresholds }));
+                } else {
+                    println!("Charging limited to {}", describe_charge(thresholds));
+                }
+            }),
+        },
+
+        Commands::Battery => battery_info().map(|batteries| {
+            if cli.json {
+                let batteries: Vec<_> = batteries.iter().map(battery_json).collect();
+                println!("{}", json!({ "batteries": batteries }));
+            } else if batteries.is_empty() {
+                println!("No battery found");
+            } else {
+                batteries.iter().for_each(print_battery);
             }
         }),
     };
@@ -156,7 +1276,14 @@ fn main() {
             eprintln!("{}", json!({ "status": "error", "message": e.to_string() }));
         } else {
             eprintln!("Error: {}", e);
-            eprintln!("Hint: run as root or fix udev permission");
+            // Hint hanya relevan untuk masalah akses ke daemon/sysfs
+            if matches!(e, M4ArchError::Io(_) | M4ArchError::PermissionDenied) {
+                if cli.mode == Mode::Daemon {
+                    eprintln!("Hint: start m4arch-daemon (systemctl start m4arch-daemon)");
+                } else {
+                    eprintln!("Hint: start m4arch-daemon, or run as root / fix udev permission");
+                }
+            }
         }
         process::exit(1);
     }
@@ -0,0 +1,237 @@
+//! Batas pengisian baterai untuk memperpanjang umur baterai.
+//!
+//! Baterai mulai diisi saat kapasitasnya turun di bawah `start` dan berhenti
+//! di `end`. Baterai yang selalu tersambung ke AC lebih awet bila tidak
+//! ditahan di 100%.
+//!
+//! `BatteryInfo` melaporkan keadaan dan kesehatan baterai itu sendiri.
+use crate::error::{M4ArchError, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Ambang pengisian dalam persen kapasitas.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChargeThresholds {
+    pub start: u8,
+    pub end: u8,
+}
+
+impl ChargeThresholds {
+    pub const fn new(start: u8, end: u8) -> Self {
+        Self { start, end }
+    }
+
+    /// `start` harus di bawah `end`, dan `end` paling tinggi 100.
+    pub fn validate(&self) -> Result<()> {
+        if self.start >= self.end || self.end > 100 {
+            return Err(M4ArchError::InvalidValue);
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for ChargeThresholds {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}–{}%", self.start, self.end)
+    }
+}
+
+/// Ambang siap pakai.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChargePreset {
+    /// Tanpa batas (bawaan firmware)
+    Full,
+    /// Untuk pemakaian campuran AC dan baterai
+    Balanced,
+    /// Untuk laptop yang hampir selalu tersambung ke AC
+    Stationary,
+}
+
+impl ChargePreset {
+    pub const ALL: [ChargePreset; 3] = [
+        ChargePreset::Full,
+        ChargePreset::Balanced,
+        ChargePreset::Stationary,
+    ];
+
+    pub const fn thresholds(self) -> ChargeThresholds {
+        match self {
+            ChargePreset::Full => ChargeThresholds::new(0, 100),
+            ChargePreset::Balanced => ChargeThresholds::new(60, 90),
+            ChargePreset::Stationary => ChargeThresholds::new(40, 80),
+        }
+    }
+
+    /// Preset dengan ambang persis `thresholds`, jika ada.
+    pub fn matching(thresholds: ChargeThresholds) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|preset| preset.thresholds() == thresholds)
+    }
+}
+
+impl fmt::Display for ChargePreset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ChargePreset::Full => "full",
+            ChargePreset::Balanced => "balanced",
+            ChargePreset::Stationary => "stationary",
+        };
+        f.write_str(name)
+    }
+}
+
+impl FromStr for ChargePreset {
+    type Err = M4ArchError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|preset| preset.to_string().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| M4ArchError::Unsupported(format!("charge preset '{}'", s.trim())))
+    }
+}
+
+/// Trait untuk membaca dan mengubah ambang pengisian.
+pub trait ChargeControl {
+    fn get_thresholds(&self) -> Result<ChargeThresholds>;
+
+    fn set_thresholds(&self, thresholds: ChargeThresholds) -> Result<()>;
+}
+
+/// Status pengisian menurut atribut `status` kernel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BatteryState {
+    Charging,
+    Discharging,
+    /// Tersambung ke AC tetapi tidak mengisi, misalnya karena ambang akhir
+    NotCharging,
+    Full,
+    Unknown,
+}
+
+impl BatteryState {
+    /// Mengurai nilai `status` kernel; nilai lain menjadi `Unknown`.
+    pub fn from_kernel(status: &str) -> Self {
+        match status.trim() {
+            "Charging" => BatteryState::Charging,
+            "Discharging" => BatteryState::Discharging,
+            "Not charging" => BatteryState::NotCharging,
+            "Full" => BatteryState::Full,
+            _ => BatteryState::Unknown,
+        }
+    }
+}
+
+impl fmt::Display for BatteryState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            BatteryState::Charging => "charging",
+            BatteryState::Discharging => "discharging",
+            BatteryState::NotCharging => "not charging",
+            BatteryState::Full => "full",
+            BatteryState::Unknown => "unknown",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Keadaan dan kesehatan satu baterai. Nilai yang tidak dilaporkan driver
+/// bernilai `None`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BatteryInfo {
+    /// Nama di kelas `power_supply`, misalnya `BAT0`
+    pub name: String,
+    pub state: BatteryState,
+    /// Sisa kapasitas dalam persen
+    pub capacity: Option<u8>,
+    pub energy_now_mwh: Option<u32>,
+    /// Energi saat penuh; menyusut seiring umur baterai
+    pub energy_full_mwh: Option<u32>,
+    /// Energi saat penuh ketika baterai masih baru
+    pub energy_full_design_mwh: Option<u32>,
+    pub cycle_count: Option<u32>,
+    /// Daya yang masuk atau keluar
+    pub power_mw: Option<u32>,
+    pub voltage_mv: Option<u32>,
+}
+
+impl BatteryInfo {
+    /// Kapasitas penuh terhadap kapasitas desain, dalam persen.
+    pub fn health(&self) -> Option<f64> {
+        let (full, design) = (self.energy_full_mwh?, self.energy_full_design_mwh?);
+        (design > 0).then(|| f64::from(full) / f64::from(design) * 100.0)
+    }
+
+    /// Perkiraan waktu sampai kosong saat `Discharging`, atau sampai penuh
+    /// saat `Charging`, dengan daya sekarang.
+    pub fn time_remaining(&self) -> Option<Duration> {
+        let (now, power) = (self.energy_now_mwh?, self.power_mw?);
+        if power == 0 {
+            return None;
+        }
+        let energy = match self.state {
+            BatteryState::Discharging => now,
+            BatteryState::Charging => self.energy_full_mwh?.saturating_sub(now),
+            _ => return None,
+        };
+        Some(Duration::from_secs(
+            u64::from(energy) * 3600 / u64::from(power),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_presets() {
+        for preset in ChargePreset::ALL {
+            preset.thresholds().validate().unwrap();
+            assert_eq!(preset.to_string().parse::<ChargePreset>().unwrap(), preset);
+            assert_eq!(ChargePreset::matching(preset.thresholds()), Some(preset));
+        }
+        assert_eq!(ChargePreset::matching(ChargeThresholds::new(50, 80)), None);
+        assert!(ChargeThresholds::new(80, 80).validate().is_err());
+        assert!(ChargeThresholds::new(0, 101).validate().is_err());
+    }
+
+    #[test]
+    fn test_battery_estimates() {
+        let mut battery = BatteryInfo {
+            name: "BAT0".to_string(),
+            state: BatteryState::Discharging,
+            capacity: Some(50),
+            energy_now_mwh: Some(20_000),
+            energy_full_mwh: Some(40_000),
+            energy_full_design_mwh: Some(50_000),
+            cycle_count: Some(120),
+            power_mw: Some(10_000),
+            voltage_mv: Some(11_400),
+        };
+        assert_eq!(battery.health(), Some(80.0));
+        assert_eq!(
+            battery.time_remaining(),
+            Some(Duration::from_secs(2 * 3600))
+        );
+
+        battery.state = BatteryState::Charging;
+        battery.power_mw = Some(40_000);
+        assert_eq!(battery.time_remaining(), Some(Duration::from_secs(1800)));
+
+        battery.state = BatteryState::NotCharging;
+        assert_eq!(battery.time_remaining(), None);
+        battery.state = BatteryState::Discharging;
+        battery.power_mw = Some(0);
+        assert_eq!(battery.time_remaining(), None);
+        assert_eq!(
+            BatteryState::from_kernel("Not charging\n"),
+            BatteryState::NotCharging
+        );
+    }
+}
@@ -0,0 +1,193 @@
+//! Kalibrasi warna LED RGB keyboard.
+//!
+//! LED tidak menampilkan warna sRGB apa adanya: `255 255 255` sering tampak
+//! kebiruan dan nilai rendah tampak terlalu gelap. Kalibrasi mengubah warna
+//! pilihan pengguna menjadi nilai mentah yang ditulis ke driver, per kanal:
+//!
+//! 1. kurva gamma: `(c / 255) ^ gamma`
+//! 2. dikalikan gain kanal
+//! 3. diskalakan ke titik putih, yaitu nilai mentah yang tampak putih netral
+use crate::error::{M4ArchError, Result};
+use crate::keyboard::Rgb;
+use serde::{Deserialize, Serialize};
+
+/// Rentang gamma yang diterima.
+pub const GAMMA_RANGE: std::ops::RangeInclusive<f32> = 0.2..=5.0;
+
+/// Pengali per kanal (0–1) yang diterapkan setelah kurva gamma.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChannelGain {
+    pub red: f32,
+    pub green: f32,
+    pub blue: f32,
+}
+
+/// Kalibrasi warna, disimpan di bagian `[calibration]` pengaturan. Default
+/// tidak mengubah warna apa pun.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Calibration {
+    /// Eksponen kurva; nilai di bawah 1 mencerahkan warna gelap
+    pub gamma: f32,
+
+    pub gain: ChannelGain,
+
+    /// Nilai mentah yang tampak putih netral di keyboard ini
+    pub white_point: Rgb,
+}
+
+// Nilai float selalu tervalidasi (tidak NaN), jadi perbandingannya total
+impl Eq for ChannelGain {}
+impl Eq for Calibration {}
+
+impl Default for ChannelGain {
+    fn default() -> Self {
+        Self {
+            red: 1.0,
+            green: 1.0,
+            blue: 1.0,
+        }
+    }
+}
+
+impl Default for Calibration {
+    fn default() -> Self {
+        Self {
+            gamma: 1.0,
+            gain: ChannelGain::default(),
+            white_point: Rgb::new(255, 255, 255),
+        }
+    }
+}
+
+impl Calibration {
+    /// Apakah kalibrasi ini tidak mengubah warna.
+    pub fn is_identity(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        let ChannelGain { red, green, blue } = self.gain;
+        let gains_valid = [red, green, blue]
+            .iter()
+            .all(|gain| (0.0..=1.0).contains(gain));
+        if !GAMMA_RANGE.contains(&self.gamma) || !gains_valid {
+            return Err(M4ArchError::InvalidValue);
+        }
+        Ok(())
+    }
+
+    /// Skala akhir tiap kanal: gain dikali titik putih, dalam 0–255.
+    fn scales(&self) -> [f32; 3] {
+        let white = self.white_point;
+        [
+            self.gain.red * f32::from(white.r),
+            self.gain.green * f32::from(white.g),
+            self.gain.blue * f32::from(white.b),
+        ]
+    }
+
+    /// Warna pilihan pengguna menjadi nilai mentah untuk driver.
+    pub fn apply(&self, rgb: Rgb) -> Rgb {
+        let [r, g, b] = self.scales();
+        let channel = |value: u8, scale: f32| {
+            let level = (f32::from(value) / 255.0).powf(self.gamma);
+            (level * scale).round().clamp(0.0, 255.0) as u8
+        };
+        Rgb::new(channel(rgb.r, r), channel(rgb.g, g), channel(rgb.b, b))
+    }
+
+    /// Kebalikan `apply()`: nilai mentah dari driver menjadi warna pilihan.
+    ///
+    /// Tidak selalu persis, karena beberapa warna bisa menghasilkan nilai
+    /// mentah yang sama. Kanal dengan skala 0 selalu dibaca sebagai 0.
+    pub fn invert(&self, raw: Rgb) -> Rgb {
+        let [r, g, b] = self.scales();
+        let channel = |value: u8, scale: f32| {
+            if scale <= 0.0 {
+                return 0;
+            }
+            let level = (f32::from(value) / scale).min(1.0);
+            (level.powf(self.gamma.recip()) * 255.0).round() as u8
+        };
+        Rgb::new(channel(raw.r, r), channel(raw.g, g), channel(raw.b, b))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identity_keeps_colors() {
+        let calibration = Calibration::default();
+        assert!(calibration.is_identity());
+        for rgb in [Rgb::new(0, 0, 0), Rgb::new(1, 128, 255)] {
+            assert_eq!(calibration.apply(rgb), rgb);
+            assert_eq!(calibration.invert(rgb), rgb);
+        }
+    }
+
+    #[test]
+    fn test_apply_and_invert() {
+        let calibration = Calibration {
+            gamma: 0.5,
+            gain: ChannelGain {
+                red: 0.5,
+                ..Default::default()
+            },
+            white_point: Rgb::new(255, 240, 200),
+        };
+        assert!(calibration.validate().is_ok());
+        // Putih menjadi titik putih; gain hanya mengurangi merah
+        assert_eq!(
+            calibration.apply(Rgb::new(255, 255, 255)),
+            Rgb::new(128, 240, 200)
+        );
+        // Gamma di bawah 1 mencerahkan nilai rendah
+        assert_eq!(calibration.apply(Rgb::new(0, 0, 64)).b, 100);
+
+        let rgb = Rgb::new(200, 100, 50);
+        let back = calibration.invert(calibration.apply(rgb));
+        for (a, b) in [(rgb.r, back.r), (rgb.g, back.g), (rgb.b, back.b)] {
+            assert!(a.abs_diff(b) <= 2, "{rgb:?} -> {back:?}");
+        }
+    }
+
+    #[test]
+    fn test_parse_and_validate() {
+        let calibration: Calibration = toml::from_str(
+            r##"
+            gamma = 0.8
+            white_point = "#ffd0b0"
+            gain = { blue = 0.9 }
+            "##,
+        )
+        .unwrap();
+        assert_eq!(calibration.white_point, Rgb::new(255, 208, 176));
+        assert_eq!(calibration.gain.red, 1.0);
+        assert_eq!(calibration.gain.blue, 0.9);
+        assert!(calibration.validate().is_ok());
+
+        for invalid in [
+            Calibration {
+                gamma: 0.0,
+                ..Default::default()
+            },
+            Calibration {
+                gamma: f32::NAN,
+                ..Default::default()
+            },
+            Calibration {
+                gain: ChannelGain {
+                    green: 1.5,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        ] {
+            assert!(invalid.validate().is_err());
+        }
+    }
+}
@@ -0,0 +1,68 @@
+//! Pengaturan kurva kipas yang dijalankan daemon.
+use crate::error::{M4ArchError, Result};
+use crate::fan::{Fan, FanCurve};
+use serde::{Deserialize, Serialize};
+
+/// Penurunan suhu maksimum yang boleh ditunggu sebelum kipas melambat.
+pub const MAX_HYSTERESIS: u8 = 20;
+
+/// Siapa yang mengatur kecepatan kipas.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FanMode {
+    /// Firmware (bawaan)
+    #[default]
+    Auto,
+    /// Daemon, mengikuti kurva suhu
+    Curve,
+}
+
+/// Bagian `[fan]` pengaturan. Default menyerahkan kipas ke firmware.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FanSettings {
+    pub mode: FanMode,
+
+    /// Kurva kipas CPU terhadap suhu CPU
+    pub cpu: FanCurve,
+
+    /// Kurva kipas GPU terhadap suhu GPU
+    pub gpu: FanCurve,
+
+    /// Suhu harus turun sekian °C di bawah suhu saat duty terakhir dipilih
+    /// sebelum kipas melambat, agar kipas tidak naik-turun terus
+    pub hysteresis: u8,
+}
+
+impl Default for FanSettings {
+    fn default() -> Self {
+        Self {
+            mode: FanMode::Auto,
+            cpu: FanCurve::default(),
+            gpu: FanCurve::default(),
+            hysteresis: 4,
+        }
+    }
+}
+
+impl FanSettings {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        self.cpu.validate()?;
+        self.gpu.validate()?;
+        if self.hysteresis > MAX_HYSTERESIS {
+            return Err(M4ArchError::InvalidValue);
+        }
+        Ok(())
+    }
+
+    pub fn curve(&self, fan: Fan) -> &FanCurve {
+        match fan {
+            Fan::Cpu => &self.cpu,
+            Fan::Gpu => &self.gpu,
+        }
+    }
+}
@@ -0,0 +1,118 @@
+//! Pengaturan timeout backlight saat pengguna tidak aktif.
+use crate::error::{M4ArchError, Result};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Kapan backlight diredupkan dan dimatikan. Nonaktif secara default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IdleSettings {
+    /// Detik tanpa aktivitas sebelum backlight diredupkan; `None` berarti tidak pernah
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dim_after: Option<u64>,
+
+    /// Detik tanpa aktivitas sebelum backlight dimatikan; `None` berarti tidak pernah
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub off_after: Option<u64>,
+
+    /// Brightness saat redup, dalam persen dari brightness sebelumnya
+    pub dim_percent: u8,
+}
+
+/// Tahap idle berdasarkan lamanya pengguna tidak aktif.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum IdleLevel {
+    #[default]
+    Active,
+    Dimmed,
+    Off,
+}
+
+impl Default for IdleSettings {
+    fn default() -> Self {
+        Self {
+            dim_after: None,
+            off_after: None,
+            dim_percent: 30,
+        }
+    }
+}
+
+impl IdleSettings {
+    pub fn is_enabled(&self) -> bool {
+        self.dim_after.is_some() || self.off_after.is_some()
+    }
+
+    /// Persentase harus 0–100 dan backlight harus redup sebelum mati.
+    pub fn validate(&self) -> Result<()> {
+        let ordered = match (self.dim_after, self.off_after) {
+            (Some(dim), Some(off)) => dim < off,
+            _ => true,
+        };
+        if self.dim_percent > 100 || !ordered || self.dim_after == Some(0) {
+            return Err(M4ArchError::InvalidValue);
+        }
+        Ok(())
+    }
+
+    /// Tahap yang seharusnya berlaku setelah tidak aktif selama `idle_for`.
+    pub fn level_after(&self, idle_for: Duration) -> IdleLevel {
+        let reached = |secs: Option<u64>| secs.is_some_and(|secs| idle_for.as_secs() >= secs);
+        if reached(self.off_after) {
+            IdleLevel::Off
+        } else if reached(self.dim_after) {
+            IdleLevel::Dimmed
+        } else {
+            IdleLevel::Active
+        }
+    }
+
+    /// Brightness redup untuk brightness awal `level`; minimal 1 agar
+    /// backlight tidak terlihat mati.
+    pub fn dimmed(&self, level: u8) -> u8 {
+        let dimmed = u16::from(level) * u16::from(self.dim_percent.min(100)) / 100;
+        (dimmed as u8).max(1).min(level)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_levels() {
+        let idle = IdleSettings {
+            dim_after: Some(60),
+            off_after: Some(300),
+            ..Default::default()
+        };
+        let secs = Duration::from_secs;
+        assert_eq!(idle.level_after(secs(59)), IdleLevel::Active);
+        assert_eq!(idle.level_after(secs(60)), IdleLevel::Dimmed);
+        assert_eq!(idle.level_after(secs(300)), IdleLevel::Off);
+        assert_eq!(
+            IdleSettings::default().level_after(secs(9999)),
+            IdleLevel::Active
+        );
+
+        assert_eq!(idle.dimmed(200), 60);
+        assert_eq!(idle.dimmed(2), 1);
+        assert_eq!(idle.dimmed(0), 0);
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(IdleSettings::default().validate().is_ok());
+        let backwards = IdleSettings {
+            dim_after: Some(300),
+            off_after: Some(60),
+            ..Default::default()
+        };
+        assert!(backwards.validate().is_err());
+        let too_bright = IdleSettings {
+            dim_percent: 101,
+            ..Default::default()
+        };
+        assert!(too_bright.validate().is_err());
+    }
+}
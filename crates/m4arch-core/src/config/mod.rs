@@ -0,0 +1,16 @@
+//! Konfigurasi dan state yang disimpan di disk.
+pub mod calibration;
+pub mod fan;
+pub mod idle;
+pub mod power;
+pub mod profile;
+pub mod schedule;
+pub mod settings;
+
+pub use calibration::{Calibration, ChannelGain};
+pub use fan::{FanMode, FanSettings};
+pub use idle::{IdleLevel, IdleSettings};
+pub use power::{LowBattery, PowerPolicy, PowerRule, PowerSource, PowerStatus};
+pub use profile::Profile;
+pub use schedule::{Location, Schedule, ScheduleAction, ScheduleEntry, ScheduleTime};
+pub use settings::{KeyboardState, Settings, SettingsStore};
@@ -0,0 +1,193 @@
+//! Aturan pencahayaan berdasarkan sumber daya (AC atau baterai).
+use crate::error::{M4ArchError, Result};
+use crate::keyboard::Rgb;
+use serde::{Deserialize, Serialize};
+
+/// Sumber daya laptop saat ini.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PowerSource {
+    Ac,
+    Battery,
+}
+
+/// Keadaan daya yang dibaca dari `/sys/class/power_supply`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PowerStatus {
+    pub source: PowerSource,
+    /// Sisa baterai dalam persen; `None` jika tidak ada baterai
+    pub capacity: Option<u8>,
+}
+
+/// Batasan pencahayaan untuk satu sumber daya. Default tidak membatasi apa pun.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PowerRule {
+    /// Brightness tertinggi dalam persen dari brightness maksimum perangkat
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_brightness: Option<u8>,
+
+    /// Warna yang diterapkan saat aturan mulai berlaku, menggantikan warna
+    /// dan efek tersimpan sampai pengguna memilih warna lain
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<Rgb>,
+
+    /// Apakah efek animasi boleh berjalan
+    pub effects: bool,
+}
+
+/// Aturan yang menggantikan aturan baterai saat sisa baterai rendah.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LowBattery {
+    /// Berlaku jika sisa baterai di bawah persentase ini
+    pub below: u8,
+    #[serde(flatten)]
+    pub rule: PowerRule,
+}
+
+/// Aturan per sumber daya, disimpan di bagian `[power]` pengaturan.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PowerPolicy {
+    #[serde(skip_serializing_if = "PowerRule::is_empty")]
+    pub ac: PowerRule,
+
+    #[serde(skip_serializing_if = "PowerRule::is_empty")]
+    pub battery: PowerRule,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub low_battery: Option<LowBattery>,
+}
+
+impl Default for PowerRule {
+    fn default() -> Self {
+        Self {
+            max_brightness: None,
+            color: None,
+            effects: true,
+        }
+    }
+}
+
+impl PowerRule {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Brightness tertinggi yang diizinkan untuk perangkat dengan
+    /// brightness maksimum `max`.
+    pub fn brightness_cap(&self, max: u8) -> u8 {
+        match self.max_brightness {
+            Some(percent) => {
+                let cap = (u16::from(max) * u16::from(percent.min(100)) + 50) / 100;
+                cap as u8
+            }
+            None => max,
+        }
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.max_brightness.is_some_and(|percent| percent > 100) {
+            return Err(M4ArchError::InvalidValue);
+        }
+        Ok(())
+    }
+}
+
+impl PowerPolicy {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Aturan yang berlaku untuk keadaan daya `status`.
+    ///
+    /// Aturan baterai lemah hanya berlaku saat berjalan dengan baterai.
+    pub fn rule_for(&self, status: &PowerStatus) -> PowerRule {
+        match status.source {
+            PowerSource::Ac => self.ac,
+            PowerSource::Battery => match (self.low_battery, status.capacity) {
+                (Some(low), Some(capacity)) if capacity < low.below => low.rule,
+                _ => self.battery,
+            },
+        }
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        self.ac.validate()?;
+        self.battery.validate()?;
+        if let Some(low) = &self.low_battery {
+            if low.below > 100 {
+                return Err(M4ArchError::InvalidValue);
+            }
+            low.rule.validate()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn on_battery(capacity: u8) -> PowerStatus {
+        PowerStatus {
+            source: PowerSource::Battery,
+            capacity: Some(capacity),
+        }
+    }
+
+    #[test]
+    fn test_rule_for_power_source() {
+        let policy = PowerPolicy {
+            battery: PowerRule {
+                max_brightness: Some(50),
+                ..Default::default()
+            },
+            low_battery: Some(LowBattery {
+                below: 20,
+                rule: PowerRule {
+                    max_brightness: Some(0),
+                    effects: false,
+                    ..Default::default()
+                },
+            }),
+            ..Default::default()
+        };
+        let ac = PowerStatus {
+            source: PowerSource::Ac,
+            capacity: Some(5),
+        };
+        assert!(policy.rule_for(&ac).is_empty());
+        assert_eq!(policy.rule_for(&on_battery(20)), policy.battery);
+        assert_eq!(policy.rule_for(&on_battery(19)).max_brightness, Some(0));
+
+        assert_eq!(policy.battery.brightness_cap(255), 128);
+        assert_eq!(PowerRule::default().brightness_cap(4), 4);
+    }
+
+    #[test]
+    fn test_parse_config() {
+        let policy: PowerPolicy = toml::from_str(
+            r#"
+            [battery]
+            max_brightness = 40
+            effects = false
+
+            [low_battery]
+            below = 15
+            max_brightness = 0
+            color = { r = 255, g = 0, b = 0 }
+            "#,
+        )
+        .unwrap();
+        assert!(policy.ac.is_empty());
+        assert!(!policy.battery.effects);
+        let low = policy.low_battery.unwrap();
+        assert_eq!(low.below, 15);
+        assert_eq!(low.rule.color, Some(Rgb { r: 255, g: 0, b: 0 }));
+        assert!(low.rule.effects);
+        assert!(policy.validate().is_ok());
+
+        let text = toml::to_string(&policy).unwrap();
+        assert_eq!(toml::from_str::<PowerPolicy>(&text).unwrap(), policy);
+    }
+}
@@ -0,0 +1,87 @@
+//! Profil pencahayaan bernama, misalnya "office", "night" atau "gaming".
+use super::settings::{KeyboardState, Settings};
+use crate::error::{M4ArchError, Result};
+use serde::{Deserialize, Serialize};
+
+/// Kumpulan pengaturan yang diterapkan sekaligus.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Profile {
+    pub keyboard: KeyboardState,
+}
+
+/// Nama profil harus berisi huruf, angka, `-` atau `_`, agar aman dipakai
+/// sebagai argumen CLI dan kunci TOML.
+pub fn validate_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && name.len() <= 64
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(M4ArchError::InvalidValue)
+    }
+}
+
+impl Settings {
+    /// Menyimpan (atau menimpa) profil bernama `name`.
+    pub fn save_profile(&mut self, name: &str, profile: Profile) -> Result<()> {
+        validate_name(name)?;
+        self.profiles.insert(name.to_string(), profile);
+        Ok(())
+    }
+
+    pub fn profile(&self, name: &str) -> Result<&Profile> {
+        self.profiles
+            .get(name)
+            .ok_or_else(|| M4ArchError::UnknownProfile(name.to_string()))
+    }
+
+    pub fn remove_profile(&mut self, name: &str) -> Result<Profile> {
+        self.profiles
+            .remove(name)
+            .ok_or_else(|| M4ArchError::UnknownProfile(name.to_string()))
+    }
+
+    /// Nama semua profil, terurut.
+    pub fn profile_names(&self) -> Vec<String> {
+        self.profiles.keys().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keyboard::Rgb;
+
+    #[test]
+    fn test_validate_name() {
+        for name in ["office", "night-2", "gaming_red"] {
+            assert!(validate_name(name).is_ok(), "{name}");
+        }
+        for name in ["", "two words", "a/b", "x\n"] {
+            assert!(validate_name(name).is_err(), "{name:?}");
+        }
+    }
+
+    #[test]
+    fn test_profile_crud() {
+        let mut settings = Settings::default();
+        let mut profile = Profile::default();
+        profile.keyboard.record_rgb(Rgb { r: 255, g: 0, b: 0 });
+
+        settings.save_profile("gaming", profile.clone()).unwrap();
+        settings.save_profile("night", Profile::default()).unwrap();
+        assert_eq!(settings.profile_names(), ["gaming", "night"]);
+        assert_eq!(settings.profile("gaming").unwrap(), &profile);
+
+        settings.remove_profile("gaming").unwrap();
+        assert!(matches!(
+            settings.profile("gaming"),
+            Err(M4ArchError::UnknownProfile(name)) if name == "gaming"
+        ));
+        assert!(settings.remove_profile("gaming").is_err());
+    }
+}
@@ -0,0 +1,408 @@
+//! Jadwal pencahayaan harian berdasarkan jam atau matahari terbit/terbenam.
+//!
+//! Waktu matahari dihitung secara offline dari lintang/bujur dengan
+//! persamaan matahari terbit (akurat sekitar satu menit), jadi tidak perlu
+//! layanan lokasi atau koneksi internet.
+use crate::error::{M4ArchError, Result};
+use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Pergeseran terbesar dari matahari terbit/terbenam, dalam menit.
+pub const MAX_SUN_OFFSET: i16 = 720;
+
+/// Kapan sebuah entry jadwal dijalankan setiap hari.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum ScheduleTime {
+    /// Jam lokal tetap, misalnya `22:00`
+    At(NaiveTime),
+    /// Matahari terbit, digeser sekian menit (`sunrise-15`)
+    Sunrise(i16),
+    /// Matahari terbenam, digeser sekian menit (`sunset+30`)
+    Sunset(i16),
+}
+
+/// Yang dilakukan saat entry jatuh tempo.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScheduleAction {
+    /// Menerapkan profil bernama
+    Profile(String),
+    /// Mengatur brightness mentah, seperti `set-brightness`
+    Brightness(u8),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScheduleEntry {
+    pub at: ScheduleTime,
+    #[serde(flatten)]
+    pub action: ScheduleAction,
+}
+
+/// Lokasi untuk menghitung matahari terbit/terbenam, dalam derajat
+/// (lintang utara dan bujur timur positif).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Location {
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+// Koordinat divalidasi sehingga tidak pernah NaN
+impl Eq for Location {}
+
+/// Jadwal yang dijalankan daemon, disimpan di bagian `[schedule]` pengaturan.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Schedule {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<Location>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub entries: Vec<ScheduleEntry>,
+}
+
+impl ScheduleTime {
+    pub fn uses_sun(&self) -> bool {
+        !matches!(self, ScheduleTime::At(_))
+    }
+
+    /// Waktu lokal entry untuk tanggal `date`, dengan `utc_offset` detik
+    /// selisih zona waktu lokal terhadap UTC.
+    ///
+    /// `None` jika butuh lokasi yang belum diatur, atau matahari tidak
+    /// terbit/terbenam hari itu (siang/malam kutub).
+    pub fn on(
+        &self,
+        date: NaiveDate,
+        location: Option<&Location>,
+        utc_offset: i32,
+    ) -> Option<NaiveDateTime> {
+        let (sun, offset) = match *self {
+            ScheduleTime::At(time) => return Some(date.and_time(time)),
+            ScheduleTime::Sunrise(offset) => (location?.sun_times(date)?.0, offset),
+            ScheduleTime::Sunset(offset) => (location?.sun_times(date)?.1, offset),
+        };
+        Some(sun + Duration::seconds(i64::from(utc_offset)) + Duration::minutes(offset.into()))
+    }
+}
+
+impl fmt::Display for ScheduleTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (name, offset) = match self {
+            ScheduleTime::At(time) => return write!(f, "{}", time.format("%H:%M")),
+            ScheduleTime::Sunrise(offset) => ("sunrise", *offset),
+            ScheduleTime::Sunset(offset) => ("sunset", *offset),
+        };
+        match offset {
+            0 => write!(f, "{name}"),
+            _ => write!(f, "{name}{offset:+}"),
+        }
+    }
+}
+
+impl FromStr for ScheduleTime {
+    type Err = M4ArchError;
+
+    /// Menerima `HH:MM`, `sunrise`, `sunset`, atau keduanya dengan
+    /// pergeseran menit seperti `sunset+30` dan `sunrise-15`.
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || {
+            M4ArchError::Config(format!(
+                "invalid schedule time '{s}' (expected HH:MM, sunrise or sunset[+-MIN])"
+            ))
+        };
+        let s = s.trim();
+        let sun = [("sunrise", true), ("sunset", false)]
+            .into_iter()
+            .find_map(|(name, rise)| Some((s.strip_prefix(name)?, rise)));
+        let Some((offset, rise)) = sun else {
+            let time = NaiveTime::parse_from_str(s, "%H:%M").map_err(|_| invalid())?;
+            return Ok(ScheduleTime::At(time));
+        };
+
+        let offset = match offset {
+            "" => 0,
+            _ if offset.starts_with(['+', '-']) => offset.parse().map_err(|_| invalid())?,
+            _ => return Err(invalid()),
+        };
+        if !(-MAX_SUN_OFFSET..=MAX_SUN_OFFSET).contains(&offset) {
+            return Err(invalid());
+        }
+        Ok(if rise {
+            ScheduleTime::Sunrise(offset)
+        } else {
+            ScheduleTime::Sunset(offset)
+        })
+    }
+}
+
+impl TryFrom<String> for ScheduleTime {
+    type Error = M4ArchError;
+
+    fn try_from(s: String) -> Result<Self> {
+        s.parse()
+    }
+}
+
+impl From<ScheduleTime> for String {
+    fn from(time: ScheduleTime) -> Self {
+        time.to_string()
+    }
+}
+
+impl fmt::Display for ScheduleAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScheduleAction::Profile(name) => write!(f, "profile {name}"),
+            ScheduleAction::Brightness(level) => write!(f, "brightness {level}"),
+        }
+    }
+}
+
+impl fmt::Display for ScheduleEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.at, self.action)
+    }
+}
+
+impl Location {
+    pub fn validate(&self) -> Result<()> {
+        if !(-90.0..=90.0).contains(&self.latitude) || !(-180.0..=180.0).contains(&self.longitude) {
+            return Err(M4ArchError::InvalidValue);
+        }
+        Ok(())
+    }
+
+    /// Matahari terbit dan terbenam (UTC) pada `date`.
+    ///
+    /// `None` saat siang atau malam kutub.
+    pub fn sun_times(&self, date: NaiveDate) -> Option<(NaiveDateTime, NaiveDateTime)> {
+        // Hari sejak J2000 (1 Januari 2000 pukul 12:00 UTC)
+        let j2000 = NaiveDate::from_ymd_opt(2000, 1, 1)?.and_hms_opt(12, 0, 0)?;
+        let days = (date - j2000.date()).num_days() as f64;
+
+        let mean_noon = days - self.longitude / 360.0;
+        let anomaly = (357.5291 + 0.985_600_28 * mean_noon)
+            .rem_euclid(360.0)
+            .to_radians();
+        let center =
+            1.9148 * anomaly.sin() + 0.02 * (2.0 * anomaly).sin() + 0.0003 * (3.0 * anomaly).sin();
+        let ecliptic = (anomaly.to_degrees() + center + 180.0 + 102.9372)
+            .rem_euclid(360.0)
+            .to_radians();
+        let transit = mean_noon + 0.0053 * anomaly.sin() - 0.0069 * (2.0 * ecliptic).sin();
+        let declination = (ecliptic.sin() * 23.4397_f64.to_radians().sin()).asin();
+
+        // -0.833° memperhitungkan refraksi dan jari-jari piringan matahari
+        let latitude = self.latitude.to_radians();
+        let cos_hour_angle = ((-0.833_f64).to_radians().sin() - latitude.sin() * declination.sin())
+            / (latitude.cos() * declination.cos());
+        if !(-1.0..=1.0).contains(&cos_hour_angle) {
+            return None;
+        }
+        let half_day = cos_hour_angle.acos().to_degrees() / 360.0;
+        let at = |days: f64| j2000 + Duration::seconds((days * 86_400.0).round() as i64);
+        Some((at(transit - half_day), at(transit + half_day)))
+    }
+}
+
+impl Schedule {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Entry matahari terbit/terbenam butuh lokasi yang valid.
+    pub fn validate(&self) -> Result<()> {
+        if let Some(location) = &self.location {
+            location.validate()?;
+        }
+        if self.location.is_none() && self.entries.iter().any(|entry| entry.at.uses_sun()) {
+            return Err(M4ArchError::Config(
+                "sunrise and sunset entries need a location".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Entry terakhir yang jatuh tempo setelah `since` sampai `now` (waktu
+    /// lokal).
+    ///
+    /// Tanpa `since`, hasilnya entry yang sedang berlaku, yaitu yang terakhir
+    /// jatuh tempo dalam 24 jam terakhir. Entry yang lebih lama dari itu
+    /// sudah digantikan oleh kemunculan berikutnya.
+    pub fn latest_due(
+        &self,
+        since: Option<NaiveDateTime>,
+        now: NaiveDateTime,
+        utc_offset: i32,
+    ) -> Option<&ScheduleEntry> {
+        let day_ago = now - Duration::days(1);
+        let since = since.map_or(day_ago, |since| since.max(day_ago));
+        // Pergeseran matahari bisa memindahkan kemunculan ke hari lain
+        let dates = (-2..=1).filter_map(|days| now.date().checked_add_signed(Duration::days(days)));
+        dates
+            .flat_map(|date| {
+                self.entries.iter().filter_map(move |entry| {
+                    let at = entry.at.on(date, self.location.as_ref(), utc_offset)?;
+                    Some((at, entry))
+                })
+            })
+            .filter(|(at, _)| since < *at && *at <= now)
+            .max_by_key(|(at, _)| *at)
+            .map(|(_, entry)| entry)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn clock(s: &str) -> NaiveTime {
+        NaiveTime::parse_from_str(s, "%H:%M").unwrap()
+    }
+
+    /// Selisih dua waktu dalam menit.
+    fn minutes_between(a: NaiveDateTime, b: NaiveDateTime) -> i64 {
+        (a - b).num_minutes().abs()
+    }
+
+    #[test]
+    fn test_parse_times() {
+        assert_eq!(
+            "22:00".parse::<ScheduleTime>().unwrap(),
+            ScheduleTime::At(clock("22:00"))
+        );
+        assert_eq!(
+            "sunset".parse::<ScheduleTime>().unwrap(),
+            ScheduleTime::Sunset(0)
+        );
+        assert_eq!(
+            "sunrise-15".parse::<ScheduleTime>().unwrap(),
+            ScheduleTime::Sunrise(-15)
+        );
+        for time in ["07:05", "sunset+30", "sunrise-15", "sunrise"] {
+            assert_eq!(time.parse::<ScheduleTime>().unwrap().to_string(), time);
+        }
+        for invalid in ["25:00", "sunset30", "sunset+999", "noon"] {
+            assert!(invalid.parse::<ScheduleTime>().is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_sun_times() {
+        // Jakarta (WIB, UTC+7) saat ekuinoks: terbit ~05:52, terbenam ~17:59
+        let jakarta = Location {
+            latitude: -6.2,
+            longitude: 106.8,
+        };
+        let day = date(2024, 3, 20);
+        let wib = 7 * 3600;
+        let rise = ScheduleTime::Sunrise(0)
+            .on(day, Some(&jakarta), wib)
+            .unwrap();
+        let set = ScheduleTime::Sunset(0)
+            .on(day, Some(&jakarta), wib)
+            .unwrap();
+        assert!(
+            minutes_between(rise, day.and_time(clock("05:52"))) <= 5,
+            "{rise}"
+        );
+        assert!(
+            minutes_between(set, day.and_time(clock("17:59"))) <= 5,
+            "{set}"
+        );
+
+        // London di titik balik matahari musim panas (UTC): 03:43 dan 20:21
+        let london = Location {
+            latitude: 51.5,
+            longitude: -0.13,
+        };
+        let day = date(2024, 6, 21);
+        let (rise, set) = london.sun_times(day).unwrap();
+        assert!(
+            minutes_between(rise, day.and_time(clock("03:43"))) <= 5,
+            "{rise}"
+        );
+        assert!(
+            minutes_between(set, day.and_time(clock("20:21"))) <= 5,
+            "{set}"
+        );
+
+        // Tromsø tidak mengalami matahari terbenam di bulan Juni
+        let tromso = Location {
+            latitude: 69.6,
+            longitude: 18.9,
+        };
+        assert_eq!(tromso.sun_times(day), None);
+        assert_eq!(ScheduleTime::Sunset(0).on(day, None, 0), None);
+    }
+
+    #[test]
+    fn test_latest_due() {
+        let schedule = Schedule {
+            location: None,
+            entries: vec![
+                ScheduleEntry {
+                    at: ScheduleTime::At(clock("22:00")),
+                    action: ScheduleAction::Profile("night".to_string()),
+                },
+                ScheduleEntry {
+                    at: ScheduleTime::At(clock("07:00")),
+                    action: ScheduleAction::Brightness(200),
+                },
+            ],
+        };
+        let day = date(2024, 5, 1);
+        let at = |time: &str| day.and_time(clock(time));
+        let night = Some(&schedule.entries[0]);
+        let morning = Some(&schedule.entries[1]);
+
+        // Tanpa pemeriksaan sebelumnya: entry yang sedang berlaku
+        assert_eq!(schedule.latest_due(None, at("03:00"), 0), night);
+        assert_eq!(schedule.latest_due(None, at("12:00"), 0), morning);
+        // Hanya entry yang jatuh tempo sejak pemeriksaan terakhir
+        assert_eq!(schedule.latest_due(Some(at("12:00")), at("21:59"), 0), None);
+        assert_eq!(
+            schedule.latest_due(Some(at("21:59")), at("22:00"), 0),
+            night
+        );
+        assert_eq!(schedule.latest_due(Some(at("22:00")), at("22:01"), 0), None);
+        assert_eq!(Schedule::default().latest_due(None, at("12:00"), 0), None);
+    }
+
+    #[test]
+    fn test_parse_config() {
+        let schedule: Schedule = toml::from_str(
+            r#"
+            location = { latitude = -6.2, longitude = 106.8 }
+
+            [[entries]]
+            at = "sunset+30"
+            profile = "warm"
+
+            [[entries]]
+            at = "07:00"
+            brightness = 255
+            "#,
+        )
+        .unwrap();
+        assert_eq!(schedule.entries[0].to_string(), "sunset+30 profile warm");
+        assert_eq!(schedule.entries[1].action, ScheduleAction::Brightness(255));
+        assert!(schedule.validate().is_ok());
+
+        let text = toml::to_string(&schedule).unwrap();
+        assert_eq!(toml::from_str::<Schedule>(&text).unwrap(), schedule);
+
+        let without_location = Schedule {
+            location: None,
+            ..schedule
+        };
+        assert!(without_location.validate().is_err());
+    }
+}
@@ -0,0 +1,305 @@
+//! Pengaturan m4arch yang bertahan melewati reboot, disimpan sebagai TOML.
+//!
+//! Daemon menyimpan state di `/var/lib/m4arch/settings.toml`; front-end yang
+//! berjalan tanpa daemon (mode `direct`) memakai
+//! `$XDG_CONFIG_HOME/m4arch/settings.toml`.
+use super::calibration::Calibration;
+use super::fan::FanSettings;
+use super::idle::IdleSettings;
+use super::power::PowerPolicy;
+use super::profile::Profile;
+use super::schedule::Schedule;
+use crate::battery::ChargeThresholds;
+use crate::cpu::CpuTuning;
+use crate::error::{M4ArchError, Result};
+use crate::keyboard::{BrightnessInfo, Effect, KeyboardMode, Rgb};
+use crate::power::PowerProfile;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Semua pengaturan yang disimpan.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub keyboard: KeyboardState,
+
+    /// Profil bernama, lihat `config::profile`
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, Profile>,
+
+    /// Timeout backlight saat tidak aktif (hanya dijalankan oleh daemon)
+    pub idle: IdleSettings,
+
+    /// Aturan pencahayaan saat memakai AC atau baterai (hanya dijalankan
+    /// oleh daemon)
+    #[serde(skip_serializing_if = "PowerPolicy::is_empty")]
+    pub power: PowerPolicy,
+
+    /// Jadwal harian profil/brightness (hanya dijalankan oleh daemon)
+    #[serde(skip_serializing_if = "Schedule::is_empty")]
+    pub schedule: Schedule,
+
+    /// Kalibrasi warna LED (hanya diterapkan oleh daemon)
+    #[serde(skip_serializing_if = "Calibration::is_identity")]
+    pub calibration: Calibration,
+
+    /// Kurva kipas (hanya dijalankan oleh daemon)
+    #[serde(skip_serializing_if = "FanSettings::is_default")]
+    pub fan: FanSettings,
+
+    /// Profil performa pilihan terakhir, diterapkan ulang oleh daemon saat
+    /// start dan resume
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub power_profile: Option<PowerProfile>,
+
+    /// Tuning CPU manual yang menimpa bawaan profil performa; dikosongkan
+    /// saat profil diganti
+    #[serde(skip_serializing_if = "CpuTuning::is_empty")]
+    pub cpu: CpuTuning,
+
+    /// Ambang pengisian baterai, diterapkan ulang oleh daemon saat start
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub charge: Option<ChargeThresholds>,
+}
+
+/// State keyboard terakhir yang diterapkan pengguna.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KeyboardState {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub brightness: Option<BrightnessInfo>,
+
+    /// Warna untuk semua zona
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rgb: Option<Rgb>,
+
+    /// Warna per zona yang menimpa `rgb`; kunci adalah indeks zona
+    /// (TOML hanya mengenal kunci string)
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub zones: BTreeMap<String, Rgb>,
+
+    /// Efek animasi yang berjalan di atas warna statis
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub effect: Option<Effect>,
+
+    /// Mode bawaan firmware; hanya dicatat pada keyboard yang memilikinya
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<KeyboardMode>,
+}
+
+impl KeyboardState {
+    pub fn record_brightness(&mut self, info: BrightnessInfo) {
+        self.brightness = Some(info);
+    }
+
+    /// Warna untuk semua zona; warna per zona sebelumnya tidak berlaku lagi.
+    /// Memilih warna statis juga menghentikan efek.
+    pub fn record_rgb(&mut self, rgb: Rgb) {
+        self.rgb = Some(rgb);
+        self.zones.clear();
+        self.effect = None;
+        self.leave_firmware_mode();
+    }
+
+    pub fn record_zone_rgb(&mut self, zone: usize, rgb: Rgb) {
+        self.zones.insert(zone.to_string(), rgb);
+        self.effect = None;
+        self.leave_firmware_mode();
+    }
+
+    /// Efek perangkat lunak hanya terlihat pada mode `Static`.
+    pub fn record_effect(&mut self, effect: Effect) {
+        self.effect = Some(effect);
+        self.leave_firmware_mode();
+    }
+
+    /// Animasi firmware menggantikan efek perangkat lunak.
+    pub fn record_mode(&mut self, mode: KeyboardMode) {
+        self.mode = Some(mode);
+        if mode != KeyboardMode::Static {
+            self.effect = None;
+        }
+    }
+
+    /// Warna statis dan efek menuntut mode `Static`; keyboard tanpa mode
+    /// firmware tidak mencatat mode sama sekali.
+    fn leave_firmware_mode(&mut self) {
+        if self.mode.is_some() {
+            self.mode = Some(KeyboardMode::Static);
+        }
+    }
+
+    /// Warna per zona yang tersimpan, diurutkan berdasarkan indeks.
+    pub fn zone_colors(&self) -> Vec<(usize, Rgb)> {
+        let mut colors: Vec<(usize, Rgb)> = self
+            .zones
+            .iter()
+            .filter_map(|(zone, rgb)| Some((zone.parse().ok()?, *rgb)))
+            .collect();
+        colors.sort_by_key(|(zone, _)| *zone);
+        colors
+    }
+
+    /// Brightness tersimpan, diskalakan ke `max` perangkat saat ini
+    /// (misalnya jika driver diganti dan rentangnya berubah).
+    pub fn brightness_for(&self, max: u8) -> Option<u8> {
+        let saved = self.brightness.as_ref()?;
+        if saved.max == max || saved.max == 0 {
+            return Some(saved.current.min(max));
+        }
+        let scaled = (u32::from(saved.current) * u32::from(max) + u32::from(saved.max) / 2)
+            / u32::from(saved.max);
+        Some(scaled.min(u32::from(max)) as u8)
+    }
+}
+
+/// Lokasi file pengaturan.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SettingsStore {
+    path: PathBuf,
+}
+
+impl SettingsStore {
+    /// Direktori state milik daemon.
+    pub const SYSTEM_DIR: &'static str = "/var/lib/m4arch";
+    pub const FILE_NAME: &'static str = "settings.toml";
+
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Store milik daemon (`/var/lib/m4arch/settings.toml`).
+    pub fn system() -> Self {
+        Self::new(Path::new(Self::SYSTEM_DIR).join(Self::FILE_NAME))
+    }
+
+    /// Store milik pengguna (`$XDG_CONFIG_HOME/m4arch/settings.toml`, atau
+    /// `~/.config/m4arch/settings.toml`); `None` jika `HOME` tidak diketahui.
+    pub fn user() -> Option<Self> {
+        let config = match std::env::var_os("XDG_CONFIG_HOME").filter(|dir| !dir.is_empty()) {
+            Some(dir) => PathBuf::from(dir),
+            None => PathBuf::from(std::env::var_os("HOME")?).join(".config"),
+        };
+        Some(Self::new(config.join("m4arch").join(Self::FILE_NAME)))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Membaca pengaturan; file yang belum ada berarti pengaturan default.
+    pub fn load(&self) -> Result<Settings> {
+        let content = match fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Settings::default()),
+            Err(e) => return Err(e.into()),
+        };
+        toml::from_str(&content).map_err(|e| M4ArchError::Config(e.to_string()))
+    }
+
+    /// Menyimpan pengaturan secara atomik (tulis ke file sementara, lalu rename)
+    /// agar file tidak rusak jika daya mati di tengah penulisan.
+    pub fn save(&self, settings: &Settings) -> Result<()> {
+        let content =
+            toml::to_string_pretty(settings).map_err(|e| M4ArchError::Config(e.to_string()))?;
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let temp = self.path.with_extension("toml.tmp");
+        fs::write(&temp, content)?;
+        fs::rename(&temp, &self.path)?;
+        Ok(())
+    }
+
+    /// Membaca, mengubah lalu menyimpan pengaturan.
+    pub fn update(&self, change: impl FnOnce(&mut Settings)) -> Result<()> {
+        let mut settings = self.load()?;
+        change(&mut settings);
+        self.save(&settings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(current: u8, max: u8) -> BrightnessInfo {
+        BrightnessInfo {
+            current,
+            max,
+            percent: 0,
+            is_on: current > 0,
+        }
+    }
+
+    #[test]
+    fn test_missing_file_is_default() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SettingsStore::new(dir.path().join("settings.toml"));
+        assert_eq!(store.load().unwrap(), Settings::default());
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SettingsStore::new(dir.path().join("m4arch/settings.toml"));
+        store
+            .update(|settings| {
+                settings.keyboard.record_brightness(info(3, 4));
+                settings.keyboard.record_rgb(Rgb { r: 1, g: 2, b: 3 });
+                settings
+                    .keyboard
+                    .record_zone_rgb(2, Rgb { r: 9, g: 9, b: 9 });
+            })
+            .unwrap();
+
+        let loaded = store.load().unwrap();
+        assert_eq!(loaded.keyboard.brightness, Some(info(3, 4)));
+        assert_eq!(loaded.keyboard.rgb, Some(Rgb { r: 1, g: 2, b: 3 }));
+        assert_eq!(
+            loaded.keyboard.zone_colors(),
+            [(2, Rgb { r: 9, g: 9, b: 9 })]
+        );
+    }
+
+    #[test]
+    fn test_rgb_clears_zone_colors() {
+        let mut state = KeyboardState::default();
+        state.record_zone_rgb(1, Rgb { r: 1, g: 1, b: 1 });
+        state.record_rgb(Rgb { r: 2, g: 2, b: 2 });
+        assert!(state.zone_colors().is_empty());
+    }
+
+    #[test]
+    fn test_static_color_leaves_firmware_mode() {
+        let mut state = KeyboardState::default();
+        state.record_rgb(Rgb { r: 1, g: 1, b: 1 });
+        assert_eq!(state.mode, None);
+
+        state.record_effect(Effect::new(crate::keyboard::EffectKind::Wave));
+        state.record_mode(KeyboardMode::Wave);
+        assert_eq!(state.effect, None);
+        state.record_zone_rgb(0, Rgb { r: 2, g: 2, b: 2 });
+        assert_eq!(state.mode, Some(KeyboardMode::Static));
+    }
+
+    #[test]
+    fn test_brightness_is_scaled_to_new_max() {
+        let mut state = KeyboardState::default();
+        assert_eq!(state.brightness_for(255), None);
+        state.record_brightness(info(2, 4));
+        assert_eq!(state.brightness_for(4), Some(2));
+        assert_eq!(state.brightness_for(255), Some(128));
+    }
+
+    #[test]
+    fn test_invalid_file_is_config_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SettingsStore::new(dir.path().join("settings.toml"));
+        fs::write(store.path(), "keyboard = 3").unwrap();
+        assert!(matches!(store.load(), Err(M4ArchError::Config(_))));
+    }
+}
@@ -0,0 +1,109 @@
+//! Tuning CPU lewat cpufreq: governor, energy performance preference (EPP)
+//! dan turbo boost.
+//!
+//! Kernel mengelompokkan CPU ke dalam policy (`cpufreq/policyN`); governor
+//! dan EPP diatur per policy, sedangkan turbo berlaku untuk semua CPU.
+use crate::error::Result;
+use crate::power::PowerProfile;
+use serde::{Deserialize, Serialize};
+
+/// Perubahan tuning CPU; nilai `None` dibiarkan seperti sekarang.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CpuTuning {
+    /// Nama governor, misalnya `powersave` atau `performance`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub governor: Option<String>,
+
+    /// Nama EPP, misalnya `balance_power`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub energy_preference: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub turbo: Option<bool>,
+}
+
+impl CpuTuning {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Tuning bawaan yang menyertai setiap profil performa.
+    ///
+    /// Governor `performance` pada `intel_pstate` memaksa EPP `performance`
+    /// (kernel menolak EPP lain), jadi EPP-nya tidak ikut ditulis.
+    pub fn for_profile(profile: PowerProfile) -> Self {
+        let (governor, energy_preference, turbo) = match profile {
+            PowerProfile::Quiet => ("powersave", Some("power"), false),
+            PowerProfile::Balanced => ("powersave", Some("balance_performance"), true),
+            PowerProfile::Performance => ("performance", None, true),
+        };
+        Self {
+            governor: Some(governor.to_string()),
+            energy_preference: energy_preference.map(str::to_string),
+            turbo: Some(turbo),
+        }
+    }
+
+    /// Menimpa nilai yang disebut `other`.
+    pub fn merge(&mut self, other: CpuTuning) {
+        if other.governor.is_some() {
+            self.governor = other.governor;
+        }
+        if other.energy_preference.is_some() {
+            self.energy_preference = other.energy_preference;
+        }
+        if other.turbo.is_some() {
+            self.turbo = other.turbo;
+        }
+    }
+}
+
+/// Keadaan satu policy cpufreq.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CpuPolicy {
+    /// Nomor `policyN`
+    pub id: u32,
+    pub governor: String,
+    pub governors: Vec<String>,
+    /// `None` jika driver tidak mendukung EPP
+    pub energy_preference: Option<String>,
+    pub energy_preferences: Vec<String>,
+}
+
+/// Keadaan semua policy dan turbo boost.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CpuStatus {
+    /// Driver cpufreq, misalnya `intel_pstate`
+    pub driver: String,
+    pub policies: Vec<CpuPolicy>,
+    /// `None` jika turbo tidak bisa diatur
+    pub turbo: Option<bool>,
+}
+
+/// Trait untuk membaca dan mengubah tuning CPU.
+pub trait CpuControl {
+    fn status(&self) -> Result<CpuStatus>;
+
+    /// Menerapkan `tuning` ke semua policy. Nama yang tidak didukung policy
+    /// mana pun ditolak sebelum ada yang ditulis.
+    fn apply(&self, tuning: &CpuTuning) -> Result<()>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge() {
+        let mut tuning = CpuTuning::for_profile(PowerProfile::Quiet);
+        tuning.merge(CpuTuning {
+            turbo: Some(true),
+            ..Default::default()
+        });
+        assert_eq!(tuning.governor.as_deref(), Some("powersave"));
+        assert_eq!(tuning.energy_preference.as_deref(), Some("power"));
+        assert_eq!(tuning.turbo, Some(true));
+        assert!(CpuTuning::default().is_empty());
+    }
+}
@@ -11,6 +11,21 @@ pub enum M4ArchError {
 
     #[error("Permission denied (are you root?)")]
     PermissionDenied,
+
+    #[error("Not supported by this device: {0}")]
+    Unsupported(String),
+
+    #[error("Invalid keyboard zone: {0}")]
+    InvalidZone(usize),
+
+    #[error("Unexpected LED color channels in multi_index: {0:?} (expected red, green and blue)")]
+    UnexpectedChannels(String),
+
+    #[error("Invalid settings file: {0}")]
+    Config(String),
+
+    #[error("Unknown profile: {0}")]
+    UnknownProfile(String),
 }
 
 pub type Result<T> = std::result::Result<T, M4ArchError>;
@@ -0,0 +1,63 @@
+//! Trait dan struktur data untuk kipas laptop.
+//!
+//! Driver `tuxedo_io` melaporkan kecepatan kipas sebagai duty cycle (bukan
+//! RPM), jadi semua kecepatan di sini dalam persen.
+use crate::error::Result;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Kipas yang bisa dikontrol, masing-masing dengan sensor suhunya sendiri.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Fan {
+    Cpu,
+    Gpu,
+}
+
+impl fmt::Display for Fan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Fan::Cpu => write!(f, "CPU"),
+            Fan::Gpu => write!(f, "GPU"),
+        }
+    }
+}
+
+/// Keadaan satu kipas saat dibaca.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FanInfo {
+    pub fan: Fan,
+    /// Duty cycle dalam persen (0–100)
+    pub speed: u8,
+    /// Suhu sensor yang dipasangkan dengan kipas, dalam °C
+    pub temperature: u8,
+}
+
+/// Trait untuk membaca kipas.
+pub trait FanSpeed {
+    /// Kipas yang tersedia di perangkat ini.
+    fn fans(&self) -> Vec<Fan>;
+
+    /// Duty cycle kipas saat ini dalam persen.
+    fn get_fan_speed(&self, fan: Fan) -> Result<u8>;
+
+    /// Suhu sensor milik kipas dalam °C.
+    fn get_temperature(&self, fan: Fan) -> Result<u8>;
+
+    fn fan_info(&self, fan: Fan) -> Result<FanInfo> {
+        Ok(FanInfo {
+            fan,
+            speed: self.get_fan_speed(fan)?,
+            temperature: self.get_temperature(fan)?,
+        })
+    }
+}
+
+/// Trait untuk mengatur kipas secara manual.
+pub trait FanDuty {
+    /// Mengunci kipas pada duty cycle `percent` (0–100) sampai
+    /// `set_fans_auto()` dipanggil.
+    fn set_fan_duty(&self, fan: Fan, percent: u8) -> Result<()>;
+
+    /// Mengembalikan semua kipas ke kendali otomatis firmware.
+    fn set_fans_auto(&self) -> Result<()>;
+}
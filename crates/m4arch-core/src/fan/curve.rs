@@ -0,0 +1,128 @@
+//! Kurva kipas: duty cycle sebagai fungsi suhu.
+//!
+//! Kurva berupa titik-titik `(suhu, duty)`. Di antara dua titik duty
+//! diinterpolasi linear; di bawah titik pertama dan di atas titik terakhir
+//! duty mengikuti titik terdekat.
+use crate::error::{M4ArchError, Result};
+use serde::{Deserialize, Serialize};
+
+/// Satu titik kurva.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CurvePoint {
+    /// Suhu dalam °C
+    pub temperature: u8,
+    /// Duty cycle dalam persen (0–100)
+    pub duty: u8,
+}
+
+impl CurvePoint {
+    pub const fn new(temperature: u8, duty: u8) -> Self {
+        Self { temperature, duty }
+    }
+}
+
+/// Titik-titik kurva, terurut menurut suhu.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct FanCurve {
+    pub points: Vec<CurvePoint>,
+}
+
+impl Default for FanCurve {
+    fn default() -> Self {
+        Self {
+            points: vec![
+                CurvePoint::new(45, 20),
+                CurvePoint::new(60, 35),
+                CurvePoint::new(70, 50),
+                CurvePoint::new(80, 75),
+                CurvePoint::new(88, 100),
+            ],
+        }
+    }
+}
+
+impl FanCurve {
+    /// Suhu tertinggi (°C) untuk titik 100%. Di atas ini kurva bisa menahan
+    /// kipas pelan pada suhu yang benar-benar tercapai.
+    pub const MAX_FULL_SPEED_TEMPERATURE: u8 = 90;
+
+    pub fn new(points: Vec<CurvePoint>) -> Self {
+        Self { points }
+    }
+
+    /// Suhu harus naik, duty tidak boleh turun, dan titik terakhir harus
+    /// 100% paling tinggi pada `MAX_FULL_SPEED_TEMPERATURE` agar kipas tidak
+    /// pernah tertahan pelan saat panas.
+    pub fn validate(&self) -> Result<()> {
+        let Some(last) = self.points.last() else {
+            return Err(M4ArchError::InvalidValue);
+        };
+        let ordered = self
+            .points
+            .windows(2)
+            .all(|pair| pair[0].temperature < pair[1].temperature && pair[0].duty <= pair[1].duty);
+        if !ordered || last.duty != 100 || last.temperature > Self::MAX_FULL_SPEED_TEMPERATURE {
+            return Err(M4ArchError::InvalidValue);
+        }
+        Ok(())
+    }
+
+    /// Duty cycle untuk suhu `temperature`, dibulatkan ke persen terdekat.
+    pub fn duty_at(&self, temperature: u8) -> u8 {
+        let (Some(first), Some(last)) = (self.points.first(), self.points.last()) else {
+            return 100;
+        };
+        if temperature <= first.temperature {
+            return first.duty;
+        }
+        if temperature >= last.temperature {
+            return last.duty;
+        }
+        let upper = self
+            .points
+            .iter()
+            .position(|point| point.temperature >= temperature)
+            .unwrap_or(self.points.len() - 1);
+        let (low, high) = (self.points[upper - 1], self.points[upper]);
+        let span = u32::from(high.temperature - low.temperature);
+        let offset = u32::from(temperature - low.temperature);
+        let rise = u32::from(high.duty - low.duty);
+        low.duty + ((rise * offset + span / 2) / span) as u8
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interpolates_between_points() {
+        let curve = FanCurve::new(vec![CurvePoint::new(40, 20), CurvePoint::new(80, 100)]);
+        assert_eq!(curve.duty_at(0), 20);
+        assert_eq!(curve.duty_at(40), 20);
+        assert_eq!(curve.duty_at(50), 40);
+        assert_eq!(curve.duty_at(61), 62);
+        assert_eq!(curve.duty_at(80), 100);
+        assert_eq!(curve.duty_at(95), 100);
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(FanCurve::default().validate().is_ok());
+        assert!(FanCurve::new(Vec::new()).validate().is_err());
+        let unordered = FanCurve::new(vec![CurvePoint::new(60, 50), CurvePoint::new(50, 100)]);
+        assert!(unordered.validate().is_err());
+        let falling = FanCurve::new(vec![CurvePoint::new(50, 60), CurvePoint::new(60, 40)]);
+        assert!(falling.validate().is_err());
+        let never_full = FanCurve::new(vec![CurvePoint::new(50, 20), CurvePoint::new(90, 80)]);
+        assert!(never_full.validate().is_err());
+        let full_too_late = FanCurve::new(vec![CurvePoint::new(254, 0), CurvePoint::new(255, 100)]);
+        assert!(full_too_late.validate().is_err());
+        let full_at_ceiling = FanCurve::new(vec![
+            CurvePoint::new(50, 20),
+            CurvePoint::new(FanCurve::MAX_FULL_SPEED_TEMPERATURE, 100),
+        ]);
+        assert!(full_at_ceiling.validate().is_ok());
+    }
+}
@@ -0,0 +1,10 @@
+//! Kontrol kipas: membaca putaran dan suhu, mengatur duty manual, dan
+//! mengembalikan kipas ke kendali otomatis firmware.
+pub mod control;
+pub mod curve;
+
+// === Fan API ===
+pub use control::{Fan, FanDuty, FanInfo, FanSpeed};
+
+// === Curve API ===
+pub use curve::{CurvePoint, FanCurve};
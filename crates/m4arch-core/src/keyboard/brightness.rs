@@ -2,7 +2,7 @@
 //!
 //! Modul ini menyediakan trait dan struktur data untuk kecerahan keyboard.
 use crate::error::Result;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 /// Trait untuk mengontrol kecerahan keyboard.
 /// Dapat diimplementasikan oleh berbagai driver hardware (Tuxedo, Generic LED, dll).
@@ -25,21 +25,79 @@ pub trait KeyboardBrightness {
     }
 
     fn brightness_info(&self) -> Result<BrightnessInfo> {
+        Ok(BrightnessInfo::new(
+            self.get_brightness()?,
+            self.get_max_brightness()?,
+        ))
+    }
+
+    /// Mengatur brightness dalam persen dari brightness maksimum.
+    fn set_brightness_percent(&self, percent: u8) -> Result<()> {
+        let max = self.get_max_brightness()?;
+        self.set_brightness(level_for_percent(percent, max))
+    }
+
+    /// Pindah satu langkah pada tangga brightness perseptual dengan `steps`
+    /// langkah, lihat `perceptual_levels()`.
+    fn step_brightness(&self, direction: StepDirection, steps: u8) -> Result<()> {
         let current = self.get_brightness()?;
         let max = self.get_max_brightness()?;
-        let percent = calculate_percent(current, max);
-        let is_on = current > 0;
-        Ok(BrightnessInfo {
-            current,
-            max,
-            percent,
-            is_on,
-        })
+        self.set_brightness(next_level(current, max, steps, direction))
+    }
+}
+
+/// Arah perubahan brightness.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StepDirection {
+    Up,
+    Down,
+}
+
+/// Nilai mentah untuk `percent` persen dari `max`, dibulatkan ke terdekat.
+pub fn level_for_percent(percent: u8, max: u8) -> u8 {
+    let percent = u16::from(percent.min(100));
+    ((u16::from(max) * percent + 50) / 100) as u8
+}
+
+/// Tangga brightness dengan `steps` langkah dari mati sampai maksimum.
+///
+/// Mata menilai terang secara logaritmik, jadi langkah di atas 0 membentuk
+/// deret geometri `max^(i/steps)`: rapat di nilai rendah, renggang di nilai
+/// tinggi. Hardware dengan sedikit level menghasilkan tangga yang lebih
+/// pendek karena level yang sama digabung.
+pub fn perceptual_levels(steps: u8, max: u8) -> Vec<u8> {
+    let steps = steps.max(1);
+    let mut levels: Vec<u8> = vec![0];
+    for i in 1..=steps {
+        let level = f32::from(max).powf(f32::from(i) / f32::from(steps)).round() as u8;
+        // Selalu naik minimal satu level agar tidak ada langkah yang diam
+        let previous = *levels.last().unwrap_or(&0);
+        let level = level.max(previous.saturating_add(1)).min(max);
+        if level > previous {
+            levels.push(level);
+        }
+    }
+    levels
+}
+
+/// Level berikutnya dari `current` pada tangga `perceptual_levels()`.
+pub fn next_level(current: u8, max: u8, steps: u8, direction: StepDirection) -> u8 {
+    let levels = perceptual_levels(steps, max);
+    match direction {
+        StepDirection::Up => levels
+            .into_iter()
+            .find(|&level| level > current)
+            .unwrap_or(max),
+        StepDirection::Down => levels
+            .into_iter()
+            .rev()
+            .find(|&level| level < current)
+            .unwrap_or(0),
     }
 }
 
 /// Struktur yang menyimpan informasi lengkap tentang status kecerahan.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct BrightnessInfo {
     /// Brightness saat ini (raw value)
     pub current: u8,
@@ -54,6 +112,18 @@ pub struct BrightnessInfo {
     pub is_on: bool,
 }
 
+impl BrightnessInfo {
+    /// Melengkapi persentase dan status nyala dari nilai mentah.
+    pub fn new(current: u8, max: u8) -> Self {
+        Self {
+            current,
+            max,
+            percent: calculate_percent(current, max),
+            is_on: current > 0,
+        }
+    }
+}
+
 fn calculate_percent(current: u8, max: u8) -> u8 {
     if max == 0 {
         0
@@ -74,6 +144,30 @@ mod tests {
         assert_eq!(calculate_percent(64, 255), 25);
     }
 
+    #[test]
+    fn test_level_for_percent() {
+        assert_eq!(level_for_percent(40, 255), 102);
+        assert_eq!(level_for_percent(50, 3), 2);
+        assert_eq!(level_for_percent(150, 200), 200);
+        assert_eq!(level_for_percent(100, 0), 0);
+    }
+
+    #[test]
+    fn test_perceptual_levels() {
+        assert_eq!(
+            perceptual_levels(10, 255),
+            [0, 2, 3, 5, 9, 16, 28, 48, 84, 147, 255]
+        );
+        // Keyboard dengan sedikit level: tidak ada langkah ganda
+        assert_eq!(perceptual_levels(10, 2), [0, 1, 2]);
+        assert_eq!(perceptual_levels(0, 4), [0, 4]);
+
+        assert_eq!(next_level(20, 255, 10, StepDirection::Up), 28);
+        assert_eq!(next_level(20, 255, 10, StepDirection::Down), 16);
+        assert_eq!(next_level(255, 255, 10, StepDirection::Up), 255);
+        assert_eq!(next_level(1, 255, 10, StepDirection::Down), 0);
+    }
+
     #[test]
     fn test_calculate_percent_edge_cases() {
         assert_eq!(calculate_percent(0, 0), 0); // Max 0 should return 0%
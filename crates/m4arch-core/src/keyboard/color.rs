@@ -0,0 +1,474 @@
+//! Notasi warna yang ramah pengguna untuk `Rgb`.
+//!
+//! `Rgb` bisa di-parse dari hex (`#ff8800`, `ff8800`, `#f80`), nama warna CSS
+//! (`orange`), desimal (`255,136,0` atau `rgb(255, 136, 0)`), `hsv(...)` dan
+//! `hsl(...)`, dan ditampilkan sebagai hex. File pengaturan menerima notasi
+//! yang sama selain bentuk tabel `{ r = .., g = .., b = .. }`.
+use super::rgb::Rgb;
+use crate::error::{M4ArchError, Result};
+use serde::Deserialize;
+use std::fmt;
+use std::str::FromStr;
+
+/// Warna dalam HSV. `hue` dalam derajat (0–360), `saturation` dan `value`
+/// dalam rentang 0–1.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hsv {
+    pub hue: f32,
+    pub saturation: f32,
+    pub value: f32,
+}
+
+/// Warna dalam HSL. `hue` dalam derajat (0–360), `saturation` dan
+/// `lightness` dalam rentang 0–1.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hsl {
+    pub hue: f32,
+    pub saturation: f32,
+    pub lightness: f32,
+}
+
+impl Rgb {
+    pub const fn new(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b }
+    }
+
+    /// Warna dari nilai `0xRRGGBB`.
+    pub const fn from_hex(hex: u32) -> Self {
+        Self::new((hex >> 16) as u8, (hex >> 8) as u8, hex as u8)
+    }
+
+    pub const fn to_hex(self) -> u32 {
+        (self.r as u32) << 16 | (self.g as u32) << 8 | self.b as u32
+    }
+
+    /// Warna dari nama warna CSS, tanpa membedakan huruf besar/kecil.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.to_ascii_lowercase();
+        CSS_COLORS
+            .binary_search_by_key(&name.as_str(), |(name, _)| name)
+            .ok()
+            .map(|index| Self::from_hex(CSS_COLORS[index].1))
+    }
+
+    pub fn from_hsv(hsv: Hsv) -> Self {
+        let saturation = hsv.saturation.clamp(0.0, 1.0);
+        let value = hsv.value.clamp(0.0, 1.0);
+        let chroma = value * saturation;
+        from_hue(hsv.hue, chroma, value - chroma)
+    }
+
+    pub fn to_hsv(self) -> Hsv {
+        let (max, min, hue) = hue_of(self);
+        Hsv {
+            hue,
+            saturation: if max > 0.0 { (max - min) / max } else { 0.0 },
+            value: max,
+        }
+    }
+
+    pub fn from_hsl(hsl: Hsl) -> Self {
+        let saturation = hsl.saturation.clamp(0.0, 1.0);
+        let lightness = hsl.lightness.clamp(0.0, 1.0);
+        let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+        from_hue(hsl.hue, chroma, lightness - chroma / 2.0)
+    }
+
+    pub fn to_hsl(self) -> Hsl {
+        let (max, min, hue) = hue_of(self);
+        let lightness = (max + min) / 2.0;
+        let saturation = if max == min {
+            0.0
+        } else {
+            (max - min) / (1.0 - (2.0 * lightness - 1.0).abs())
+        };
+        Hsl {
+            hue,
+            saturation,
+            lightness,
+        }
+    }
+}
+
+/// Kanal RGB dari hue, chroma dan komponen abu-abu `m` (rumus bersama HSV/HSL).
+fn from_hue(hue: f32, chroma: f32, m: f32) -> Rgb {
+    let sector = hue.rem_euclid(360.0) / 60.0;
+    let x = chroma * (1.0 - (sector % 2.0 - 1.0).abs());
+    let (r, g, b) = match sector as u8 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    let channel = |c: f32| ((c + m) * 255.0).round().clamp(0.0, 255.0) as u8;
+    Rgb::new(channel(r), channel(g), channel(b))
+}
+
+/// Kanal terbesar, terkecil (0–1) dan hue dalam derajat; hue abu-abu adalah 0.
+fn hue_of(rgb: Rgb) -> (f32, f32, f32) {
+    let [r, g, b] = [rgb.r, rgb.g, rgb.b].map(|c| f32::from(c) / 255.0);
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let delta = max - min;
+    let hue = if delta == 0.0 {
+        0.0
+    } else if max == r {
+        60.0 * ((g - b) / delta).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / delta + 2.0)
+    } else {
+        60.0 * ((r - g) / delta + 4.0)
+    };
+    (max, min, hue)
+}
+
+impl fmt::Display for Rgb {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+    }
+}
+
+impl FromStr for Rgb {
+    type Err = M4ArchError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        parse(s).ok_or(M4ArchError::InvalidValue)
+    }
+}
+
+fn parse(s: &str) -> Option<Rgb> {
+    let s = s.trim().to_ascii_lowercase();
+    if let Some(hex) = s.strip_prefix('#') {
+        return parse_hex(hex);
+    }
+    if let Some(rgb) = Rgb::from_name(&s) {
+        return Some(rgb);
+    }
+    if let Some(args) = function_args(&s, "rgb") {
+        let [r, g, b] = numbers(args)?;
+        return Some(Rgb::new(channel(r)?, channel(g)?, channel(b)?));
+    }
+    if let Some(args) = function_args(&s, "hsv") {
+        let [hue, saturation, value] = numbers(args)?;
+        return Some(Rgb::from_hsv(Hsv {
+            hue,
+            saturation: percent(saturation)?,
+            value: percent(value)?,
+        }));
+    }
+    if let Some(args) = function_args(&s, "hsl") {
+        let [hue, saturation, lightness] = numbers(args)?;
+        return Some(Rgb::from_hsl(Hsl {
+            hue,
+            saturation: percent(saturation)?,
+            lightness: percent(lightness)?,
+        }));
+    }
+    if s.contains(',') {
+        let [r, g, b] = numbers(&s)?;
+        return Some(Rgb::new(channel(r)?, channel(g)?, channel(b)?));
+    }
+    // Hex tanpa `#` hanya bentuk enam digit, agar tidak tertukar dengan angka
+    (s.len() == 6).then(|| parse_hex(&s)).flatten()
+}
+
+/// `rrggbb` atau bentuk pendek `rgb` seperti di CSS.
+fn parse_hex(hex: &str) -> Option<Rgb> {
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let value = u32::from_str_radix(hex, 16).ok()?;
+    match hex.len() {
+        6 => Some(Rgb::from_hex(value)),
+        3 => {
+            let digit = |shift: u32| ((value >> shift) & 0xf) as u8 * 17;
+            Some(Rgb::new(digit(8), digit(4), digit(0)))
+        }
+        _ => None,
+    }
+}
+
+/// Isi `name(...)`, misalnya `30, 100%, 50%` dari `hsl(30, 100%, 50%)`.
+fn function_args<'a>(s: &'a str, name: &str) -> Option<&'a str> {
+    s.strip_prefix(name)?
+        .trim_start()
+        .strip_prefix('(')?
+        .strip_suffix(')')
+}
+
+/// Tiga angka dipisah koma; tanda `%` opsional dan dibuang.
+fn numbers(args: &str) -> Option<[f32; 3]> {
+    let values: Vec<f32> = args
+        .split(',')
+        .map(|value| value.trim().trim_end_matches('%').parse().ok())
+        .collect::<Option<_>>()?;
+    values.try_into().ok()
+}
+
+fn channel(value: f32) -> Option<u8> {
+    (value.fract() == 0.0 && (0.0..=255.0).contains(&value)).then_some(value as u8)
+}
+
+/// Persentase 0–100 menjadi 0–1.
+fn percent(value: f32) -> Option<f32> {
+    (0.0..=100.0).contains(&value).then_some(value / 100.0)
+}
+
+/// Bentuk `Rgb` yang diterima di file pengaturan.
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub(crate) enum RgbRepr {
+    Text(String),
+    Channels { r: u8, g: u8, b: u8 },
+}
+
+impl TryFrom<RgbRepr> for Rgb {
+    type Error = String;
+
+    fn try_from(repr: RgbRepr) -> std::result::Result<Self, String> {
+        match repr {
+            RgbRepr::Text(text) => text
+                .parse()
+                .map_err(|_| format!("invalid colour '{}'", text)),
+            RgbRepr::Channels { r, g, b } => Ok(Rgb::new(r, g, b)),
+        }
+    }
+}
+
+/// Warna bernama CSS Color Module Level 4, terurut untuk pencarian biner.
+const CSS_COLORS: [(&str, u32); 148] = [
+    ("aliceblue", 0xf0f8ff),
+    ("antiquewhite", 0xfaebd7),
+    ("aqua", 0x00ffff),
+    ("aquamarine", 0x7fffd4),
+    ("azure", 0xf0ffff),
+    ("beige", 0xf5f5dc),
+    ("bisque", 0xffe4c4),
+    ("black", 0x000000),
+    ("blanchedalmond", 0xffebcd),
+    ("blue", 0x0000ff),
+    ("blueviolet", 0x8a2be2),
+    ("brown", 0xa52a2a),
+    ("burlywood", 0xdeb887),
+    ("cadetblue", 0x5f9ea0),
+    ("chartreuse", 0x7fff00),
+    ("chocolate", 0xd2691e),
+    ("coral", 0xff7f50),
+    ("cornflowerblue", 0x6495ed),
+    ("cornsilk", 0xfff8dc),
+    ("crimson", 0xdc143c),
+    ("cyan", 0x00ffff),
+    ("darkblue", 0x00008b),
+    ("darkcyan", 0x008b8b),
+    ("darkgoldenrod", 0xb8860b),
+    ("darkgray", 0xa9a9a9),
+    ("darkgreen", 0x006400),
+    ("darkgrey", 0xa9a9a9),
+    ("darkkhaki", 0xbdb76b),
+    ("darkmagenta", 0x8b008b),
+    ("darkolivegreen", 0x556b2f),
+    ("darkorange", 0xff8c00),
+    ("darkorchid", 0x9932cc),
+    ("darkred", 0x8b0000),
+    ("darksalmon", 0xe9967a),
+    ("darkseagreen", 0x8fbc8f),
+    ("darkslateblue", 0x483d8b),
+    ("darkslategray", 0x2f4f4f),
+    ("darkslategrey", 0x2f4f4f),
+    ("darkturquoise", 0x00ced1),
+    ("darkviolet", 0x9400d3),
+    ("deeppink", 0xff1493),
+    ("deepskyblue", 0x00bfff),
+    ("dimgray", 0x696969),
+    ("dimgrey", 0x696969),
+    ("dodgerblue", 0x1e90ff),
+    ("firebrick", 0xb22222),
+    ("floralwhite", 0xfffaf0),
+    ("forestgreen", 0x228b22),
+    ("fuchsia", 0xff00ff),
+    ("gainsboro", 0xdcdcdc),
+    ("ghostwhite", 0xf8f8ff),
+    ("gold", 0xffd700),
+    ("goldenrod", 0xdaa520),
+    ("gray", 0x808080),
+    ("green", 0x008000),
+    ("greenyellow", 0xadff2f),
+    ("grey", 0x808080),
+    ("honeydew", 0xf0fff0),
+    ("hotpink", 0xff69b4),
+    ("indianred", 0xcd5c5c),
+    ("indigo", 0x4b0082),
+    ("ivory", 0xfffff0),
+    ("khaki", 0xf0e68c),
+    ("lavender", 0xe6e6fa),
+    ("lavenderblush", 0xfff0f5),
+    ("lawngreen", 0x7cfc00),
+    ("lemonchiffon", 0xfffacd),
+    ("lightblue", 0xadd8e6),
+    ("lightcoral", 0xf08080),
+    ("lightcyan", 0xe0ffff),
+    ("lightgoldenrodyellow", 0xfafad2),
+    ("lightgray", 0xd3d3d3),
+    ("lightgreen", 0x90ee90),
+    ("lightgrey", 0xd3d3d3),
+    ("lightpink", 0xffb6c1),
+    ("lightsalmon", 0xffa07a),
+    ("lightseagreen", 0x20b2aa),
+    ("lightskyblue", 0x87cefa),
+    ("lightslategray", 0x778899),
+    ("lightslategrey", 0x778899),
+    ("lightsteelblue", 0xb0c4de),
+    ("lightyellow", 0xffffe0),
+    ("lime", 0x00ff00),
+    ("limegreen", 0x32cd32),
+    ("linen", 0xfaf0e6),
+    ("magenta", 0xff00ff),
+    ("maroon", 0x800000),
+    ("mediumaquamarine", 0x66cdaa),
+    ("mediumblue", 0x0000cd),
+    ("mediumorchid", 0xba55d3),
+    ("mediumpurple", 0x9370db),
+    ("mediumseagreen", 0x3cb371),
+    ("mediumslateblue", 0x7b68ee),
+    ("mediumspringgreen", 0x00fa9a),
+    ("mediumturquoise", 0x48d1cc),
+    ("mediumvioletred", 0xc71585),
+    ("midnightblue", 0x191970),
+    ("mintcream", 0xf5fffa),
+    ("mistyrose", 0xffe4e1),
+    ("moccasin", 0xffe4b5),
+    ("navajowhite", 0xffdead),
+    ("navy", 0x000080),
+    ("oldlace", 0xfdf5e6),
+    ("olive", 0x808000),
+    ("olivedrab", 0x6b8e23),
+    ("orange", 0xffa500),
+    ("orangered", 0xff4500),
+    ("orchid", 0xda70d6),
+    ("palegoldenrod", 0xeee8aa),
+    ("palegreen", 0x98fb98),
+    ("paleturquoise", 0xafeeee),
+    ("palevioletred", 0xdb7093),
+    ("papayawhip", 0xffefd5),
+    ("peachpuff", 0xffdab9),
+    ("peru", 0xcd853f),
+    ("pink", 0xffc0cb),
+    ("plum", 0xdda0dd),
+    ("powderblue", 0xb0e0e6),
+    ("purple", 0x800080),
+    ("rebeccapurple", 0x663399),
+    ("red", 0xff0000),
+    ("rosybrown", 0xbc8f8f),
+    ("royalblue", 0x4169e1),
+    ("saddlebrown", 0x8b4513),
+    ("salmon", 0xfa8072),
+    ("sandybrown", 0xf4a460),
+    ("seagreen", 0x2e8b57),
+    ("seashell", 0xfff5ee),
+    ("sienna", 0xa0522d),
+    ("silver", 0xc0c0c0),
+    ("skyblue", 0x87ceeb),
+    ("slateblue", 0x6a5acd),
+    ("slategray", 0x708090),
+    ("slategrey", 0x708090),
+    ("snow", 0xfffafa),
+    ("springgreen", 0x00ff7f),
+    ("steelblue", 0x4682b4),
+    ("tan", 0xd2b48c),
+    ("teal", 0x008080),
+    ("thistle", 0xd8bfd8),
+    ("tomato", 0xff6347),
+    ("turquoise", 0x40e0d0),
+    ("violet", 0xee82ee),
+    ("wheat", 0xf5deb3),
+    ("white", 0xffffff),
+    ("whitesmoke", 0xf5f5f5),
+    ("yellow", 0xffff00),
+    ("yellowgreen", 0x9acd32),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> Rgb {
+        s.parse().unwrap_or_else(|_| panic!("cannot parse '{s}'"))
+    }
+
+    #[test]
+    fn test_parse_notations() {
+        let orange = Rgb::new(255, 136, 0);
+        for s in [
+            "#ff8800",
+            "FF8800",
+            "#f80",
+            "255,136,0",
+            "rgb(255, 136, 0)",
+            "hsv(32, 100%, 100%)",
+            "hsl(32, 100%, 50%)",
+        ] {
+            assert_eq!(parse(s), orange, "{s}");
+        }
+        assert_eq!(parse("Orange"), Rgb::new(255, 165, 0));
+        assert_eq!(parse("rebeccapurple"), Rgb::new(0x66, 0x33, 0x99));
+        for invalid in [
+            "",
+            "#ff88",
+            "f80",
+            "notacolor",
+            "256,0,0",
+            "hsv(0, 200%, 0)",
+        ] {
+            assert!(invalid.parse::<Rgb>().is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        let rgb = Rgb::new(1, 171, 255);
+        assert_eq!(rgb.to_string(), "#01abff");
+        assert_eq!(parse(&rgb.to_string()), rgb);
+    }
+
+    #[test]
+    fn test_hsv_hsl_conversions() {
+        let hsv = Rgb::new(255, 0, 0).to_hsv();
+        assert_eq!((hsv.hue, hsv.saturation, hsv.value), (0.0, 1.0, 1.0));
+        let hsl = Rgb::new(0, 0, 255).to_hsl();
+        assert_eq!((hsl.hue, hsl.saturation, hsl.lightness), (240.0, 1.0, 0.5));
+        assert_eq!(Rgb::new(128, 128, 128).to_hsv().saturation, 0.0);
+
+        // Konversi bolak-balik tidak mengubah warna
+        for hex in [0x000000, 0xffffff, 0x123456, 0xff8800, 0x00fa9a, 0x9400d3] {
+            let rgb = Rgb::from_hex(hex);
+            assert_eq!(Rgb::from_hsv(rgb.to_hsv()), rgb);
+            assert_eq!(Rgb::from_hsl(rgb.to_hsl()), rgb);
+        }
+    }
+
+    #[test]
+    fn test_deserialize_text_or_table() {
+        #[derive(Deserialize)]
+        struct Config {
+            a: Rgb,
+            b: Rgb,
+        }
+        let config: Config = toml::from_str(
+            r#"
+            a = "hsl(120, 100%, 25%)"
+            b = { r = 1, g = 2, b = 3 }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.a, Rgb::new(0, 128, 0));
+        assert_eq!(config.b, Rgb::new(1, 2, 3));
+        assert!(toml::from_str::<Config>("a = \"bogus\"\nb = \"red\"").is_err());
+    }
+
+    #[test]
+    fn test_css_table_is_sorted() {
+        assert!(CSS_COLORS.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    }
+}
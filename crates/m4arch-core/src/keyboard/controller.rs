@@ -0,0 +1,21 @@
+//! Informasi kemampuan (capability) sebuah perangkat backlight keyboard.
+use serde::{Deserialize, Serialize};
+
+/// Kemampuan yang dipublikasikan oleh perangkat LED keyboard.
+///
+/// Diisi berdasarkan atribut sysfs yang tersedia, sehingga front-end bisa
+/// menyembunyikan kontrol yang tidak didukung (misalnya warna pada LED monokrom).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyboardCapabilities {
+    /// Kecerahan bisa dibaca dan diubah (`brightness`)
+    pub brightness: bool,
+
+    /// Batas kecerahan diketahui (`max_brightness`)
+    pub max_brightness: bool,
+
+    /// Warna RGB bisa diatur (`multi_intensity`)
+    pub rgb: bool,
+
+    /// Kernel memublikasikan urutan channel warna (`multi_index`)
+    pub channel_index: bool,
+}
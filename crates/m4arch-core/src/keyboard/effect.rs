@@ -0,0 +1,313 @@
+//! Efek pencahayaan animasi yang dirender oleh software.
+//!
+//! Hardware hanya menerima warna statis, jadi daemon memanggil
+//! `Effect::render()` secara berkala dan menulis hasilnya ke sysfs.
+//! Fungsi ini murni (hanya bergantung pada waktu yang sudah berlalu),
+//! sehingga mudah diuji dan tidak menyimpan state.
+use crate::error::{M4ArchError, Result};
+use crate::keyboard::Rgb;
+use serde::{Deserialize, Serialize};
+use std::f32::consts::TAU;
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Batas frame per detik agar sysfs tidak ditulis terlalu sering.
+pub const MAX_FPS: u32 = 30;
+
+/// Jeda minimum antar frame sesuai `MAX_FPS`.
+pub const FRAME_INTERVAL: Duration = Duration::from_millis(1000 / MAX_FPS as u64);
+
+/// Jumlah warna maksimum dalam satu efek (sesuai spesifikasi hardware).
+pub const MAX_COLORS: usize = 15;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EffectKind {
+    /// Warna meredup dan menyala perlahan
+    Breathe,
+    /// Semua zona berputar melalui spektrum warna
+    Cycle,
+    /// Spektrum warna bergeser dari zona ke zona
+    Wave,
+    /// Menyala cepat lalu memudar, seperti detak jantung
+    Pulse,
+    /// Berkedip nyala/mati
+    Strobe,
+}
+
+impl EffectKind {
+    pub const ALL: [EffectKind; 5] = [
+        EffectKind::Breathe,
+        EffectKind::Cycle,
+        EffectKind::Wave,
+        EffectKind::Pulse,
+        EffectKind::Strobe,
+    ];
+
+    /// Durasi satu siklus pada kecepatan default.
+    fn base_period(self) -> Duration {
+        match self {
+            EffectKind::Breathe => Duration::from_secs(4),
+            EffectKind::Cycle => Duration::from_secs(10),
+            EffectKind::Wave => Duration::from_secs(5),
+            EffectKind::Pulse => Duration::from_secs(2),
+            EffectKind::Strobe => Duration::from_millis(500),
+        }
+    }
+}
+
+impl fmt::Display for EffectKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            EffectKind::Breathe => "breathe",
+            EffectKind::Cycle => "cycle",
+            EffectKind::Wave => "wave",
+            EffectKind::Pulse => "pulse",
+            EffectKind::Strobe => "strobe",
+        };
+        f.write_str(name)
+    }
+}
+
+impl FromStr for EffectKind {
+    type Err = M4ArchError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|kind| kind.to_string().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| M4ArchError::Unsupported(format!("effect '{}'", s.trim())))
+    }
+}
+
+/// Efek beserta parameternya.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Effect {
+    pub kind: EffectKind,
+
+    /// Kecepatan, `MIN_SPEED` (lambat) sampai `MAX_SPEED` (cepat)
+    pub speed: u8,
+
+    /// Warna yang dipakai bergantian; kosong berarti putih.
+    /// Diabaikan oleh `Cycle` dan `Wave` yang memakai spektrum penuh.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub colors: Vec<Rgb>,
+}
+
+/// Satu frame hasil render.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    /// Warna per zona
+    pub colors: Vec<Rgb>,
+
+    /// Intensitas 0–255; dipakai sebagai brightness pada keyboard monokrom
+    pub intensity: u8,
+}
+
+impl Frame {
+    /// Warna per zona yang sudah dikalikan dengan intensitas.
+    pub fn scaled_colors(&self) -> Vec<Rgb> {
+        let scale = |value: u8| ((u16::from(value) * u16::from(self.intensity) + 127) / 255) as u8;
+        self.colors
+            .iter()
+            .map(|rgb| Rgb {
+                r: scale(rgb.r),
+                g: scale(rgb.g),
+                b: scale(rgb.b),
+            })
+            .collect()
+    }
+}
+
+impl Effect {
+    pub const MIN_SPEED: u8 = 1;
+    pub const MAX_SPEED: u8 = 10;
+    pub const DEFAULT_SPEED: u8 = 5;
+
+    pub fn new(kind: EffectKind) -> Self {
+        Self {
+            kind,
+            speed: Self::DEFAULT_SPEED,
+            colors: Vec::new(),
+        }
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if !(Self::MIN_SPEED..=Self::MAX_SPEED).contains(&self.speed)
+            || self.colors.len() > MAX_COLORS
+        {
+            return Err(M4ArchError::InvalidValue);
+        }
+        Ok(())
+    }
+
+    /// Durasi satu siklus efek sesuai kecepatan.
+    pub fn period(&self) -> Duration {
+        let speed = u32::from(self.speed.clamp(Self::MIN_SPEED, Self::MAX_SPEED));
+        self.kind.base_period() * u32::from(Self::DEFAULT_SPEED) / speed
+    }
+
+    /// Merender frame pada waktu `elapsed` sejak efek dimulai.
+    pub fn render(&self, elapsed: Duration, zones: usize) -> Frame {
+        let zones = zones.max(1);
+        let cycles = elapsed.as_secs_f32() / self.period().as_secs_f32();
+        let phase = cycles.fract();
+        // Warna berganti di setiap siklus
+        let color = self.color(cycles as usize);
+
+        let (colors, intensity) = match self.kind {
+            EffectKind::Breathe => {
+                let level = (1.0 - (TAU * phase).cos()) / 2.0;
+                (vec![color; zones], level)
+            }
+            EffectKind::Cycle => (vec![hue_to_rgb(phase); zones], 1.0),
+            EffectKind::Wave => {
+                let colors = (0..zones)
+                    .map(|zone| hue_to_rgb((phase + zone as f32 / zones as f32).fract()))
+                    .collect();
+                (colors, 1.0)
+            }
+            EffectKind::Pulse => {
+                // Naik cepat di 10% pertama, lalu memudar
+                let level = if phase < 0.1 {
+                    phase / 0.1
+                } else {
+                    (1.0 - (phase - 0.1) / 0.9).powi(2)
+                };
+                (vec![color; zones], level)
+            }
+            EffectKind::Strobe => {
+                let level = if phase < 0.5 { 1.0 } else { 0.0 };
+                (vec![color; zones], level)
+            }
+        };
+
+        Frame {
+            colors,
+            intensity: (intensity.clamp(0.0, 1.0) * 255.0).round() as u8,
+        }
+    }
+
+    fn color(&self, cycle: usize) -> Rgb {
+        const WHITE: Rgb = Rgb {
+            r: 255,
+            g: 255,
+            b: 255,
+        };
+        if self.colors.is_empty() {
+            WHITE
+        } else {
+            self.colors[cycle % self.colors.len()]
+        }
+    }
+}
+
+/// Warna spektrum penuh (saturasi dan value maksimum) untuk `hue` 0.0–1.0.
+fn hue_to_rgb(hue: f32) -> Rgb {
+    let h = hue.rem_euclid(1.0) * 6.0;
+    let x = 1.0 - (h % 2.0 - 1.0).abs();
+    let (r, g, b) = match h as u32 {
+        0 => (1.0, x, 0.0),
+        1 => (x, 1.0, 0.0),
+        2 => (0.0, 1.0, x),
+        3 => (0.0, x, 1.0),
+        4 => (x, 0.0, 1.0),
+        _ => (1.0, 0.0, x),
+    };
+    let channel = |value: f32| (value * 255.0).round() as u8;
+    Rgb {
+        r: channel(r),
+        g: channel(g),
+        b: channel(b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RED: Rgb = Rgb { r: 255, g: 0, b: 0 };
+    const BLUE: Rgb = Rgb { r: 0, g: 0, b: 255 };
+
+    fn at(effect: &Effect, fraction: f32) -> Frame {
+        effect.render(effect.period().mul_f32(fraction), 3)
+    }
+
+    #[test]
+    fn test_speed_scales_period() {
+        let mut effect = Effect::new(EffectKind::Breathe);
+        assert_eq!(effect.period(), Duration::from_secs(4));
+        effect.speed = 10;
+        assert_eq!(effect.period(), Duration::from_secs(2));
+        effect.speed = 1;
+        assert_eq!(effect.period(), Duration::from_secs(20));
+    }
+
+    #[test]
+    fn test_breathe_alternates_colors() {
+        let effect = Effect {
+            colors: vec![RED, BLUE],
+            ..Effect::new(EffectKind::Breathe)
+        };
+        assert_eq!(at(&effect, 0.0).intensity, 0);
+        let peak = at(&effect, 0.5);
+        assert_eq!(peak.intensity, 255);
+        assert_eq!(peak.colors, [RED; 3]);
+        assert_eq!(at(&effect, 1.5).colors, [BLUE; 3]);
+    }
+
+    #[test]
+    fn test_wave_offsets_zones() {
+        let frame = at(&Effect::new(EffectKind::Wave), 0.0);
+        assert_eq!(frame.colors[0], RED);
+        assert_eq!(frame.colors[1], Rgb { r: 0, g: 255, b: 0 });
+        assert_eq!(frame.colors[2], BLUE);
+    }
+
+    #[test]
+    fn test_strobe_and_pulse_levels() {
+        let strobe = Effect::new(EffectKind::Strobe);
+        assert_eq!(at(&strobe, 0.25).intensity, 255);
+        assert_eq!(at(&strobe, 0.75).intensity, 0);
+
+        let pulse = Effect::new(EffectKind::Pulse);
+        assert_eq!(at(&pulse, 0.1).intensity, 255);
+        assert!(at(&pulse, 0.5).intensity < at(&pulse, 0.2).intensity);
+    }
+
+    #[test]
+    fn test_scaled_colors() {
+        let frame = Frame {
+            colors: vec![Rgb {
+                r: 255,
+                g: 100,
+                b: 0,
+            }],
+            intensity: 128,
+        };
+        assert_eq!(
+            frame.scaled_colors(),
+            [Rgb {
+                r: 128,
+                g: 50,
+                b: 0
+            }]
+        );
+    }
+
+    #[test]
+    fn test_validate_and_parse() {
+        assert!(Effect::new(EffectKind::Cycle).validate().is_ok());
+        let too_fast = Effect {
+            speed: 11,
+            ..Effect::new(EffectKind::Cycle)
+        };
+        assert!(too_fast.validate().is_err());
+        assert_eq!(
+            "Breathe".parse::<EffectKind>().unwrap(),
+            EffectKind::Breathe
+        );
+        assert!("rainbow".parse::<EffectKind>().is_err());
+    }
+}
@@ -1,8 +1,26 @@
 pub mod brightness;
+pub mod color;
+pub mod controller;
+pub mod effect;
+pub mod mode;
 pub mod rgb;
+pub mod transition;
 
 // === Brightness API ===
-pub use brightness::{BrightnessInfo, KeyboardBrightness};
+pub use brightness::{BrightnessInfo, KeyboardBrightness, StepDirection};
+
+// === Capability API ===
+pub use controller::KeyboardCapabilities;
+
+// === Effect API ===
+pub use effect::{Effect, EffectKind, Frame};
+
+// === Hardware Mode API ===
+pub use mode::KeyboardMode;
 
 // === RGB API ===
-pub use rgb::{KeyboardRgb, Rgb};
+pub use color::{Hsl, Hsv};
+pub use rgb::{KeyboardRgb, KeyboardZone, Rgb};
+
+// === Transition API ===
+pub use transition::{Easing, Fade, FadeFrame, Transition};
@@ -0,0 +1,96 @@
+//! Mode pencahayaan bawaan firmware.
+//!
+//! Berbeda dengan `effect`, mode ini dijalankan oleh controller keyboard
+//! sendiri sehingga tidak memakan CPU. Hanya tersedia pada driver yang
+//! memublikasikan atribut `mode` (misalnya `tuxedo_keyboard` versi lama).
+use crate::error::{M4ArchError, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyboardMode {
+    /// Warna statis per zona (mode `custom` di firmware)
+    Static,
+    Breathe,
+    Cycle,
+    Dance,
+    Flash,
+    /// Warna acak yang berganti-ganti
+    Random,
+    Tempo,
+    Wave,
+}
+
+impl KeyboardMode {
+    /// Semua mode, terurut sesuai nilai atribut `mode` di firmware.
+    pub const ALL: [KeyboardMode; 8] = [
+        KeyboardMode::Static,
+        KeyboardMode::Breathe,
+        KeyboardMode::Cycle,
+        KeyboardMode::Dance,
+        KeyboardMode::Flash,
+        KeyboardMode::Random,
+        KeyboardMode::Tempo,
+        KeyboardMode::Wave,
+    ];
+
+    /// Nilai numerik yang ditulis ke atribut `mode`.
+    pub fn index(self) -> u8 {
+        self as u8
+    }
+
+    pub fn from_index(index: u8) -> Result<Self> {
+        Self::ALL
+            .get(usize::from(index))
+            .copied()
+            .ok_or(M4ArchError::InvalidValue)
+    }
+}
+
+impl fmt::Display for KeyboardMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            KeyboardMode::Static => "static",
+            KeyboardMode::Breathe => "breathe",
+            KeyboardMode::Cycle => "cycle",
+            KeyboardMode::Dance => "dance",
+            KeyboardMode::Flash => "flash",
+            KeyboardMode::Random => "random",
+            KeyboardMode::Tempo => "tempo",
+            KeyboardMode::Wave => "wave",
+        };
+        f.write_str(name)
+    }
+}
+
+impl FromStr for KeyboardMode {
+    type Err = M4ArchError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|mode| mode.to_string().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| M4ArchError::Unsupported(format!("mode '{}'", s.trim())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_index_round_trip() {
+        for mode in KeyboardMode::ALL {
+            assert_eq!(KeyboardMode::from_index(mode.index()).unwrap(), mode);
+        }
+        assert_eq!(KeyboardMode::Wave.index(), 7);
+        assert!(KeyboardMode::from_index(8).is_err());
+        assert_eq!(
+            "Dance".parse::<KeyboardMode>().unwrap(),
+            KeyboardMode::Dance
+        );
+        assert!("disco".parse::<KeyboardMode>().is_err());
+    }
+}
@@ -1,13 +1,80 @@
-use crate::error::Result;
+use crate::error::{M4ArchError, Result};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Warna RGB 8-bit per kanal.
+///
+/// Selain tabel `{ r, g, b }`, file pengaturan juga menerima notasi teks
+/// seperti `"#ff8800"` atau `"orange"`; lihat modul [`color`](super::color).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "super::color::RgbRepr")]
 pub struct Rgb {
     pub r: u8,
     pub g: u8,
     pub b: u8,
 }
 
+/// Satu zona warna pada keyboard (misalnya kiri/tengah/kanan).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyboardZone {
+    /// Indeks zona, dimulai dari 0
+    pub index: usize,
+
+    /// Nama zona yang ramah pengguna
+    pub name: String,
+}
+
+impl KeyboardZone {
+    /// Membuat zona dengan nama standar berdasarkan jumlah total zona.
+    ///
+    /// Keyboard tiga zona (Clevo) diberi nama `left`, `center`, `right`;
+    /// keyboard satu zona diberi nama `main`.
+    pub fn standard(index: usize, count: usize) -> Self {
+        const CLEVO_ZONES: [&str; 4] = ["left", "center", "right", "extra"];
+        let name = match count {
+            1 => "main".to_string(),
+            2..=4 => CLEVO_ZONES[index].to_string(),
+            _ => format!("zone {}", index),
+        };
+        Self { index, name }
+    }
+}
+
+/// Trait untuk mengontrol warna keyboard.
+///
+/// `get_rgb`/`set_rgb` adalah shortcut untuk "semua zona": `get_rgb` membaca
+/// zona pertama dan `set_rgb` menulis ke semua zona. Implementasi default
+/// untuk API per-zona menganggap keyboard hanya memiliki satu zona.
 pub trait KeyboardRgb {
     fn get_rgb(&self) -> Result<Rgb>;
     fn set_rgb(&self, rgb: Rgb) -> Result<()>;
+
+    fn zones(&self) -> Vec<KeyboardZone> {
+        vec![KeyboardZone::standard(0, 1)]
+    }
+
+    fn get_zone_rgb(&self, zone: usize) -> Result<Rgb> {
+        match zone {
+            0 => self.get_rgb(),
+            _ => Err(M4ArchError::InvalidZone(zone)),
+        }
+    }
+
+    fn set_zone_rgb(&self, zone: usize, rgb: Rgb) -> Result<()> {
+        match zone {
+            0 => self.set_rgb(rgb),
+            _ => Err(M4ArchError::InvalidZone(zone)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_standard_zone_names() {
+        assert_eq!(KeyboardZone::standard(0, 1).name, "main");
+        assert_eq!(KeyboardZone::standard(2, 3).name, "right");
+        assert_eq!(KeyboardZone::standard(5, 6).name, "zone 5");
+    }
 }
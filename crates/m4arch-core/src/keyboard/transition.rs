@@ -0,0 +1,289 @@
+//! Transisi halus (fade) antara dua nilai brightness atau warna.
+//!
+//! Interpolasi dilakukan di ruang perseptual: brightness lewat lightness
+//! CIE L*, warna lewat OKLab. Dengan begitu fade terlihat rata di mata,
+//! bukan cepat di awal lalu lambat di akhir seperti interpolasi nilai mentah.
+//! `Fade` hanya menghitung frame; penulisan ke hardware dan pembatalan
+//! dilakukan oleh pemanggil (daemon atau `m4arch_service`).
+use crate::keyboard::Rgb;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Kurva percepatan transisi.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Easing {
+    Linear,
+    /// Mulai pelan, berakhir cepat
+    EaseIn,
+    /// Mulai cepat, berakhir pelan
+    EaseOut,
+    /// Pelan di awal dan di akhir
+    #[default]
+    EaseInOut,
+}
+
+impl Easing {
+    /// Memetakan progres waktu `t` (0.0–1.0) ke progres nilai.
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t,
+            Easing::EaseOut => 1.0 - (1.0 - t) * (1.0 - t),
+            Easing::EaseInOut => t * t * (3.0 - 2.0 * t),
+        }
+    }
+}
+
+/// Durasi dan kurva sebuah transisi.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Transition {
+    pub duration: Duration,
+    #[serde(default)]
+    pub easing: Easing,
+}
+
+impl Transition {
+    pub const fn new(duration: Duration) -> Self {
+        Self {
+            duration,
+            easing: Easing::EaseInOut,
+        }
+    }
+
+    pub const fn from_millis(millis: u64) -> Self {
+        Self::new(Duration::from_millis(millis))
+    }
+
+    /// Progres yang sudah di-easing pada waktu `elapsed`, 0.0–1.0.
+    pub fn progress(&self, elapsed: Duration) -> f32 {
+        if self.duration.is_zero() {
+            return 1.0;
+        }
+        self.easing
+            .apply(elapsed.as_secs_f32() / self.duration.as_secs_f32())
+    }
+}
+
+/// Transisi dari state hardware saat ini menuju state tujuan.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fade {
+    transition: Transition,
+    max_brightness: u8,
+    brightness: Option<(u8, u8)>,
+    /// `(zona, dari, ke)`
+    colors: Vec<(usize, Rgb, Rgb)>,
+}
+
+/// Nilai yang harus ditulis ke hardware pada satu titik waktu.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FadeFrame {
+    pub brightness: Option<u8>,
+    /// Warna per zona
+    pub colors: Vec<(usize, Rgb)>,
+}
+
+impl Fade {
+    pub fn new(transition: Transition, max_brightness: u8) -> Self {
+        Self {
+            transition,
+            max_brightness,
+            brightness: None,
+            colors: Vec::new(),
+        }
+    }
+
+    /// Menambahkan perubahan brightness; diabaikan jika nilainya sama.
+    pub fn brightness(mut self, from: u8, to: u8) -> Self {
+        if from != to {
+            self.brightness = Some((from, to));
+        }
+        self
+    }
+
+    /// Menambahkan perubahan warna satu zona; diabaikan jika warnanya sama.
+    pub fn color(mut self, zone: usize, from: Rgb, to: Rgb) -> Self {
+        if from != to {
+            self.colors.push((zone, from, to));
+        }
+        self
+    }
+
+    /// Tidak ada yang berubah, jadi tidak perlu dianimasikan.
+    pub fn is_empty(&self) -> bool {
+        self.brightness.is_none() && self.colors.is_empty()
+    }
+
+    pub fn is_finished(&self, elapsed: Duration) -> bool {
+        self.is_empty() || elapsed >= self.transition.duration
+    }
+
+    /// Menghitung frame pada waktu `elapsed` sejak transisi dimulai.
+    pub fn frame(&self, elapsed: Duration) -> FadeFrame {
+        let t = self.transition.progress(elapsed);
+        FadeFrame {
+            brightness: self
+                .brightness
+                .map(|(from, to)| lerp_brightness(from, to, self.max_brightness, t)),
+            colors: self
+                .colors
+                .iter()
+                .map(|&(zone, from, to)| (zone, lerp_rgb(from, to, t)))
+                .collect(),
+        }
+    }
+}
+
+/// Interpolasi brightness `0..=max` dalam lightness CIE L*.
+pub fn lerp_brightness(from: u8, to: u8, max: u8, t: f32) -> u8 {
+    if max == 0 {
+        return to;
+    }
+    let max_f = f32::from(max);
+    let from_l = lightness(f32::from(from) / max_f);
+    let to_l = lightness(f32::from(to) / max_f);
+    let level = luminance(from_l + (to_l - from_l) * t) * max_f;
+    level.round().clamp(0.0, max_f) as u8
+}
+
+/// Interpolasi warna dalam ruang OKLab.
+pub fn lerp_rgb(from: Rgb, to: Rgb, t: f32) -> Rgb {
+    let a = rgb_to_oklab(from);
+    let b = rgb_to_oklab(to);
+    let mix = |i: usize| a[i] + (b[i] - a[i]) * t;
+    oklab_to_rgb([mix(0), mix(1), mix(2)])
+}
+
+/// Luminance relatif (0.0–1.0) ke lightness CIE L* (0–100).
+fn lightness(y: f32) -> f32 {
+    if y > 0.008856 {
+        116.0 * y.cbrt() - 16.0
+    } else {
+        903.3 * y
+    }
+}
+
+fn luminance(l: f32) -> f32 {
+    if l > 8.0 {
+        ((l + 16.0) / 116.0).powi(3)
+    } else {
+        l / 903.3
+    }
+}
+
+fn srgb_to_linear(c: u8) -> f32 {
+    let c = f32::from(c) / 255.0;
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(c: f32) -> u8 {
+    let c = c.clamp(0.0, 1.0);
+    let c = if c <= 0.003_130_8 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    };
+    (c * 255.0).round() as u8
+}
+
+// Matriks konversi dari spesifikasi OKLab (Björn Ottosson)
+fn rgb_to_oklab(rgb: Rgb) -> [f32; 3] {
+    let (r, g, b) = (
+        srgb_to_linear(rgb.r),
+        srgb_to_linear(rgb.g),
+        srgb_to_linear(rgb.b),
+    );
+    let l = (0.412_221_47 * r + 0.536_332_55 * g + 0.051_445_99 * b).cbrt();
+    let m = (0.211_903_5 * r + 0.680_699_5 * g + 0.107_396_96 * b).cbrt();
+    let s = (0.088_302_46 * r + 0.281_718_85 * g + 0.629_978_7 * b).cbrt();
+    [
+        0.210_454_26 * l + 0.793_617_8 * m - 0.004_072_047 * s,
+        1.977_998_5 * l - 2.428_592_2 * m + 0.450_593_7 * s,
+        0.025_904_037 * l + 0.782_771_77 * m - 0.808_675_77 * s,
+    ]
+}
+
+fn oklab_to_rgb([l, a, b]: [f32; 3]) -> Rgb {
+    let l_ = (l + 0.396_337_78 * a + 0.215_803_76 * b).powi(3);
+    let m_ = (l - 0.105_561_35 * a - 0.063_854_17 * b).powi(3);
+    let s_ = (l - 0.089_484_18 * a - 1.291_485_5 * b).powi(3);
+    Rgb {
+        r: linear_to_srgb(4.076_741_7 * l_ - 3.307_711_6 * m_ + 0.230_969_94 * s_),
+        g: linear_to_srgb(-1.268_438 * l_ + 2.609_757_4 * m_ - 0.341_319_38 * s_),
+        b: linear_to_srgb(-0.004_196_086_3 * l_ - 0.703_418_6 * m_ + 1.707_614_7 * s_),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RED: Rgb = Rgb { r: 255, g: 0, b: 0 };
+    const BLUE: Rgb = Rgb { r: 0, g: 0, b: 255 };
+
+    #[test]
+    fn test_easing_endpoints() {
+        for easing in [
+            Easing::Linear,
+            Easing::EaseIn,
+            Easing::EaseOut,
+            Easing::EaseInOut,
+        ] {
+            assert_eq!(easing.apply(0.0), 0.0);
+            assert_eq!(easing.apply(1.0), 1.0);
+            assert_eq!(easing.apply(2.0), 1.0);
+        }
+        assert!(Easing::EaseIn.apply(0.5) < 0.5);
+        assert!(Easing::EaseOut.apply(0.5) > 0.5);
+    }
+
+    #[test]
+    fn test_brightness_is_perceptual() {
+        assert_eq!(lerp_brightness(0, 255, 255, 0.0), 0);
+        assert_eq!(lerp_brightness(0, 255, 255, 1.0), 255);
+        // Setengah lightness jauh lebih gelap dari setengah nilai mentah
+        assert_eq!(lerp_brightness(0, 255, 255, 0.5), 47);
+        assert_eq!(lerp_brightness(3, 1, 3, 1.0), 1);
+    }
+
+    #[test]
+    fn test_color_round_trip() {
+        for rgb in [
+            RED,
+            BLUE,
+            Rgb {
+                r: 12,
+                g: 200,
+                b: 99,
+            },
+        ] {
+            assert_eq!(lerp_rgb(rgb, rgb, 0.5), rgb);
+            assert_eq!(lerp_rgb(RED, rgb, 1.0), rgb);
+        }
+    }
+
+    #[test]
+    fn test_fade_frames() {
+        let fade = Fade::new(Transition::from_millis(100), 255)
+            .brightness(0, 255)
+            .color(1, RED, BLUE)
+            .color(2, RED, RED);
+        assert!(!fade.is_finished(Duration::from_millis(50)));
+        assert!(fade.is_finished(Duration::from_millis(100)));
+
+        let start = fade.frame(Duration::ZERO);
+        assert_eq!(start.brightness, Some(0));
+        assert_eq!(start.colors, [(1, RED)]);
+        let end = fade.frame(Duration::from_millis(100));
+        assert_eq!(end.brightness, Some(255));
+        assert_eq!(end.colors, [(1, BLUE)]);
+
+        let empty = Fade::new(Transition::from_millis(100), 255).brightness(5, 5);
+        assert!(empty.is_empty() && empty.is_finished(Duration::ZERO));
+    }
+}
@@ -1,4 +1,12 @@
+pub mod battery;
+pub mod config;
+pub mod cpu;
 pub mod error;
+pub mod fan;
 pub mod keyboard;
+pub mod power;
 
-pub use keyboard::{BrightnessInfo, KeyboardBrightness, KeyboardRgb, Rgb};
+pub use keyboard::{
+    BrightnessInfo, Effect, EffectKind, KeyboardBrightness, KeyboardCapabilities, KeyboardMode,
+    KeyboardRgb, KeyboardZone, Rgb,
+};
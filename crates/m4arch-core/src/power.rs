@@ -0,0 +1,80 @@
+//! Profil performa laptop: batas daya (TDP) dan perilaku kipas yang diatur
+//! firmware.
+//!
+//! Setiap backend punya nama profil sendiri (`low-power`, `entertainment`,
+//! `overboost`, ...); m4arch menyederhanakannya menjadi tiga tingkat.
+use crate::error::{M4ArchError, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PowerProfile {
+    /// Daya rendah dan kipas pelan
+    Quiet,
+    Balanced,
+    /// Daya maksimum; kipas lebih bising
+    Performance,
+}
+
+impl PowerProfile {
+    /// Semua profil, dari yang paling hemat.
+    pub const ALL: [PowerProfile; 3] = [
+        PowerProfile::Quiet,
+        PowerProfile::Balanced,
+        PowerProfile::Performance,
+    ];
+}
+
+impl fmt::Display for PowerProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            PowerProfile::Quiet => "quiet",
+            PowerProfile::Balanced => "balanced",
+            PowerProfile::Performance => "performance",
+        };
+        f.write_str(name)
+    }
+}
+
+impl FromStr for PowerProfile {
+    type Err = M4ArchError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|profile| profile.to_string().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| M4ArchError::Unsupported(format!("power profile '{}'", s.trim())))
+    }
+}
+
+/// Trait untuk membaca dan mengganti profil performa.
+pub trait PowerProfileControl {
+    /// Profil yang didukung perangkat ini, dari yang paling hemat.
+    fn profiles(&self) -> Vec<PowerProfile>;
+
+    fn get_profile(&self) -> Result<PowerProfile>;
+
+    fn set_profile(&self, profile: PowerProfile) -> Result<()>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_display() {
+        for profile in PowerProfile::ALL {
+            assert_eq!(
+                profile.to_string().parse::<PowerProfile>().unwrap(),
+                profile
+            );
+        }
+        assert_eq!(
+            " Performance ".parse::<PowerProfile>().unwrap(),
+            PowerProfile::Performance
+        );
+        assert!("turbo".parse::<PowerProfile>().is_err());
+    }
+}
@@ -0,0 +1,39 @@
+//! Thread yang merender transisi dan efek pencahayaan.
+//!
+//! Laju frame dibatasi oleh `m4arch_core::keyboard::effect::MAX_FPS`; saat
+//! tidak ada yang dirender, thread tidur sampai dibangunkan oleh daemon.
+use crate::daemon::Daemon;
+use crate::server::lock;
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Jeda sebelum mencoba lagi setelah gagal menulis ke hardware.
+const RETRY_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Merender transisi dan efek selamanya.
+pub fn run(daemon: &Mutex<Daemon>) {
+    lock(daemon).set_animator(thread::current());
+    let mut failing = false;
+    loop {
+        // Kunci dilepas sebelum tidur agar perintah lain tidak tertahan
+        let result = lock(daemon).render(Instant::now());
+        match result {
+            Ok(delay) => {
+                failing = false;
+                match delay {
+                    Some(delay) => thread::sleep(delay),
+                    // Token `unpark()` yang datang sebelum `park()` tidak hilang
+                    None => thread::park(),
+                }
+            }
+            Err(e) => {
+                if !failing {
+                    eprintln!("m4arch-daemon: cannot render lighting: {}", e);
+                    failing = true;
+                }
+                thread::sleep(RETRY_INTERVAL);
+            }
+        }
+    }
+}
@@ -0,0 +1,2208 @@
+//! State daemon: pemilik tunggal controller keyboard.
+use crate::events::EventBus;
+use crate::fan::FanCurveController;
+use chrono::NaiveDateTime;
+use m4arch_core::battery::ChargeThresholds;
+use m4arch_core::config::{
+    Calibration, FanSettings, IdleLevel, IdleSettings, KeyboardState, PowerRule, PowerStatus,
+    Profile, Schedule, ScheduleAction, Settings, SettingsStore,
+};
+use m4arch_core::cpu::CpuTuning;
+use m4arch_core::error::{M4ArchError, Result};
+use m4arch_core::keyboard::effect::FRAME_INTERVAL;
+use m4arch_core::keyboard::{
+    brightness, BrightnessInfo, Effect, Fade, Frame, KeyboardMode, Rgb, Transition,
+};
+use m4arch_core::power::PowerProfile;
+use m4arch_ipc::protocol::{
+    ChargeCommand, CpuCommand, FadeTarget, FanCommand, IpcCommand, IpcError, IpcEvent, IpcReply,
+    PowerProfileCommand, PROTOCOL_VERSION,
+};
+use m4arch_service::calibration::CalibratedKeyboard;
+use m4arch_service::{
+    ChargeController, CpuController, FanController, KeyboardController, PowerProfileController,
+};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::Receiver;
+use std::thread::Thread;
+use std::time::{Duration, Instant};
+
+pub struct Daemon {
+    /// Root sysfs tempat keyboard dicari ulang saat driver dimuat/dilepas
+    root: PathBuf,
+    keyboard: Option<Box<dyn KeyboardController>>,
+    /// Brightness terakhir yang menyala, dipakai saat `Power(true)`
+    last_brightness: Option<u8>,
+    /// Brightness terakhir yang diketahui daemon, untuk mendeteksi
+    /// perubahan yang tidak lewat daemon (hotkey Fn)
+    known_brightness: Option<u8>,
+    /// Nilai `brightness_hw_changed` terakhir yang sudah diproses
+    known_hw_changed: Option<u8>,
+    events: EventBus,
+    /// State yang diterapkan ulang saat startup, driver dimuat dan resume
+    store: SettingsStore,
+    settings: Settings,
+    effect: Option<RunningEffect>,
+    fade: Option<RunningFade>,
+    /// Brightness sebelum transisi yang sedang diselesaikan
+    fade_origin: Option<u8>,
+    /// Thread animasi yang tidur selama tidak ada yang dirender
+    animator: Option<Thread>,
+    idle: IdleState,
+    /// Keadaan daya terakhir; `None` jika tidak ada adaptor maupun baterai
+    power: Option<PowerStatus>,
+    /// Waktu lokal pemeriksaan jadwal terakhir
+    schedule_checked: Option<NaiveDateTime>,
+    /// Kurva kipas; `None` jika tidak ada controller kipas
+    fans: Option<FanCurveController>,
+    /// Backend profil performa; `None` jika tidak ada
+    power_profiles: Option<Box<dyn PowerProfileController>>,
+    /// Tuning CPU; `None` jika tidak ada cpufreq
+    cpu: Option<Box<dyn CpuController>>,
+    /// Ambang pengisian baterai; `None` jika tidak didukung
+    charge: Option<Box<dyn ChargeController>>,
+}
+
+/// Transisi saat backlight diredupkan atau dimatikan karena idle.
+const IDLE_FADE: Transition = Transition::from_millis(1000);
+
+/// Transisi saat backlight dikembalikan karena ada aktivitas.
+const WAKE_FADE: Transition = Transition::from_millis(150);
+
+/// Transisi saat entry jadwal dijalankan.
+const SCHEDULE_FADE: Transition = Transition::from_millis(2000);
+
+/// Keadaan timeout idle saat ini.
+#[derive(Debug, Default)]
+struct IdleState {
+    level: IdleLevel,
+    /// Brightness sebelum diredupkan, dikembalikan saat ada aktivitas
+    saved: Option<BrightnessInfo>,
+}
+
+/// Efek yang sedang dirender oleh daemon.
+struct RunningEffect {
+    effect: Effect,
+    started: Instant,
+    /// Frame terakhir yang ditulis, agar frame yang sama tidak ditulis ulang
+    last_frame: Option<Frame>,
+}
+
+/// Transisi yang sedang berjalan.
+struct RunningFade {
+    fade: Fade,
+    started: Instant,
+    /// Brightness saat transisi dimulai
+    origin: u8,
+    /// Perintah instan yang dijalankan saat transisi selesai; `None` untuk
+    /// transisi sementara yang tidak disimpan (idle)
+    then: Option<IpcCommand>,
+}
+
+impl Daemon {
+    /// Membuat daemon, memuat pengaturan dari `store` dan langsung mencari
+    /// keyboard di bawah `root`.
+    pub fn new(root: impl Into<PathBuf>, store: SettingsStore) -> Self {
+        let root = root.into();
+        // File rusak tidak boleh membuat daemon gagal start
+        let mut settings = store.load().unwrap_or_else(|e| {
+            eprintln!("m4arch-daemon: ignoring {}: {}", store.path().display(), e);
+            Settings::default()
+        });
+        if settings.calibration.validate().is_err() {
+            eprintln!("m4arch-daemon: ignoring invalid colour calibration");
+            settings.calibration = Calibration::default();
+        }
+        if settings.fan.validate().is_err() {
+            eprintln!("m4arch-daemon: ignoring invalid fan curve");
+            settings.fan = FanSettings::default();
+        }
+        if settings
+            .charge
+            .is_some_and(|charge| charge.validate().is_err())
+        {
+            eprintln!("m4arch-daemon: ignoring invalid charge thresholds");
+            settings.charge = None;
+        }
+        let keyboard = detect_keyboard(&root, settings.calibration);
+        let power = m4arch_service::power_supply::read_status(&root)
+            .ok()
+            .flatten();
+        let mut daemon = Self {
+            keyboard,
+            root,
+            last_brightness: None,
+            known_brightness: None,
+            known_hw_changed: None,
+            events: EventBus::default(),
+            store,
+            settings,
+            effect: None,
+            fade: None,
+            fade_origin: None,
+            animator: None,
+            idle: IdleState::default(),
+            power,
+            schedule_checked: None,
+            fans: None,
+            power_profiles: None,
+            cpu: None,
+            charge: None,
+        };
+        daemon.reset_known_state();
+        daemon
+    }
+
+    pub fn has_keyboard(&self) -> bool {
+        self.keyboard.is_some()
+    }
+
+    /// Memasang controller kipas; kurvanya dijalankan oleh `update_fans()`.
+    pub fn set_fans(&mut self, fans: Box<dyn FanController>) {
+        self.fans = Some(FanCurveController::new(fans));
+    }
+
+    /// Menyesuaikan kipas dengan suhu saat ini, atau mengembalikannya ke
+    /// firmware jika kurva tidak aktif.
+    pub fn update_fans(&mut self) -> Result<()> {
+        match &mut self.fans {
+            Some(fans) => fans.update(&self.settings.fan),
+            None => Ok(()),
+        }
+    }
+
+    /// Menulis ulang duty kurva pada pembaruan berikutnya, misalnya setelah
+    /// resume ketika EC kembali mengatur kipas sendiri.
+    pub fn reset_fans(&mut self) {
+        if let Some(fans) = &mut self.fans {
+            fans.reset();
+        }
+    }
+
+    /// Mengembalikan kipas ke firmware sebelum daemon berhenti.
+    pub fn release_fans(&mut self) -> Result<()> {
+        match &mut self.fans {
+            Some(fans) => fans.release(),
+            None => Ok(()),
+        }
+    }
+
+    /// Memasang backend profil performa lalu menerapkan profil tersimpan.
+    pub fn set_power_profiles(&mut self, profiles: Box<dyn PowerProfileController>) -> Result<()> {
+        self.power_profiles = Some(profiles);
+        self.restore_power_profile()
+    }
+
+    /// Memasang tuning CPU; pasang sebelum `set_power_profiles()` agar
+    /// tuning manual tersimpan ikut diterapkan.
+    pub fn set_cpu(&mut self, cpu: Box<dyn CpuController>) {
+        self.cpu = Some(cpu);
+    }
+
+    /// Menerapkan ulang profil performa tersimpan beserta tuning CPU manual,
+    /// misalnya setelah resume ketika firmware kembali ke profil bawaannya.
+    pub fn restore_power_profile(&mut self) -> Result<()> {
+        if let (Some(profiles), Some(profile)) = (&self.power_profiles, self.settings.power_profile)
+        {
+            profiles.set_profile(profile)?;
+        }
+        match &self.cpu {
+            Some(cpu) if !self.settings.cpu.is_empty() => cpu.apply(&self.settings.cpu),
+            _ => Ok(()),
+        }
+    }
+
+    /// Memasang mekanisme ambang pengisian lalu menerapkan ambang tersimpan;
+    /// banyak firmware kembali mengisi penuh setelah reboot.
+    pub fn set_charge(&mut self, charge: Box<dyn ChargeController>) -> Result<()> {
+        let result = match self.settings.charge {
+            Some(thresholds) => charge.set_thresholds(thresholds),
+            None => Ok(()),
+        };
+        self.charge = Some(charge);
+        result
+    }
+
+    /// Berlangganan perubahan state hardware.
+    pub fn subscribe(&self) -> Receiver<IpcEvent> {
+        self.events.subscribe()
+    }
+
+    /// Mencari ulang keyboard dan menyiarkan `DriverAdded`/`DriverRemoved`
+    /// jika ketersediaannya berubah.
+    pub fn rescan(&mut self) {
+        let found = detect_keyboard(&self.root, self.settings.calibration);
+        match (&self.keyboard, found) {
+            (None, Some(keyboard)) => {
+                let capabilities = keyboard.capabilities();
+                self.keyboard = Some(keyboard);
+                self.reset_known_state();
+                self.events.publish(IpcEvent::DriverAdded(capabilities));
+                if let Err(e) = self.restore() {
+                    eprintln!("m4arch-daemon: cannot restore keyboard state: {}", e);
+                }
+            }
+            (Some(_), None) => {
+                self.keyboard = None;
+                self.fade = None;
+                self.last_brightness = None;
+                self.reset_known_state();
+                self.events.publish(IpcEvent::DriverRemoved);
+            }
+            _ => {}
+        }
+    }
+
+    /// Memeriksa apakah brightness diubah di luar daemon, misalnya oleh
+    /// hotkey Fn yang ditangani langsung oleh kernel/firmware.
+    ///
+    /// Perubahan seperti ini diperlakukan sama dengan perintah pengguna:
+    /// diingat sebagai brightness pilihan dan disiarkan ke pelanggan.
+    pub fn poll_hardware(&mut self) -> Result<()> {
+        let Some(keyboard) = self.keyboard.as_deref() else {
+            return Ok(());
+        };
+        let hw_changed = keyboard.brightness_hw_changed()?;
+        let info = keyboard.brightness_info()?;
+
+        // `brightness_hw_changed` bisa berubah tanpa nilai `brightness`
+        // berbeda dari yang terakhir dibaca (misalnya hotkey ditekan dua kali)
+        let hw_event = hw_changed.is_some() && hw_changed != self.known_hw_changed;
+        self.known_hw_changed = hw_changed;
+        let Some(previous) = self.known_brightness.replace(info.current) else {
+            return Ok(());
+        };
+        if !hw_event && previous == info.current {
+            return Ok(());
+        }
+
+        if info.current > 0 {
+            self.last_brightness = Some(info.current);
+        }
+        // Pengguna memilih brightness sendiri; jangan ditimpa saat bangun dari idle
+        self.idle = IdleState::default();
+        self.remember(|state| state.record_brightness(info.clone()));
+
+        // Hotkey tetap tunduk pada batas brightness aturan daya
+        let cap = self.power_rule().brightness_cap(info.max);
+        let info = if info.current > cap {
+            let keyboard = self.keyboard()?;
+            keyboard.set_brightness(cap)?;
+            let info = keyboard.brightness_info()?;
+            self.known_brightness = Some(info.current);
+            info
+        } else {
+            info
+        };
+        self.publish_brightness(previous, info);
+        Ok(())
+    }
+
+    /// Membaca ulang sumber daya dan menerapkan aturannya jika aturan yang
+    /// berlaku berubah (misalnya adaptor dicabut atau baterai mulai lemah).
+    pub fn poll_power(&mut self) -> Result<()> {
+        let status = m4arch_service::power_supply::read_status(&self.root)?;
+        if status == self.power {
+            return Ok(());
+        }
+        let previous = self.power_rule();
+        self.power = status;
+        if self.power_rule() == previous {
+            return Ok(());
+        }
+        // State tersimpan diterapkan ulang lalu dibatasi oleh aturan baru
+        self.restore()
+    }
+
+    /// Menjalankan entry jadwal yang jatuh tempo sejak pemeriksaan terakhir.
+    ///
+    /// `now` adalah waktu lokal dan `utc_offset` selisihnya terhadap UTC dalam
+    /// detik. Pemeriksaan pertama menjalankan entry yang sedang berlaku, agar
+    /// laptop yang mati saat entry jatuh tempo tetap mengikuti jadwal.
+    pub fn check_schedule(&mut self, now: NaiveDateTime, utc_offset: i32) -> Result<()> {
+        // Tunggu keyboard, agar entry yang jatuh tempo tidak terlewat
+        if self.keyboard.is_none() {
+            return Ok(());
+        }
+        // Jam yang mundur (NTP, ganti zona waktu) dianggap pemeriksaan pertama
+        let since = self
+            .schedule_checked
+            .replace(now)
+            .filter(|since| *since <= now);
+        let Some(entry) = self.settings.schedule.latest_due(since, now, utc_offset) else {
+            return Ok(());
+        };
+        let target = match &entry.action {
+            ScheduleAction::Profile(name) => FadeTarget::Profile(name.clone()),
+            ScheduleAction::Brightness(level) => FadeTarget::Brightness(*level),
+        };
+        // Dijalankan seperti perintah pengguna: disimpan dan disiarkan
+        self.handle(IpcCommand::Fade(target, SCHEDULE_FADE))
+            .map(drop)
+            .map_err(M4ArchError::from)
+    }
+
+    /// Aturan daya yang berlaku saat ini.
+    fn power_rule(&self) -> PowerRule {
+        self.power
+            .map(|status| self.settings.power.rule_for(&status))
+            .unwrap_or_default()
+    }
+
+    /// Meredupkan atau mematikan backlight setelah pengguna tidak aktif, dan
+    /// mengembalikannya begitu ada aktivitas lagi.
+    ///
+    /// Perubahan ini sementara: disiarkan ke pelanggan, tetapi tidak disimpan.
+    pub fn check_idle(&mut self, last_activity: Instant, now: Instant) -> Result<()> {
+        if self.keyboard.is_none() {
+            return Ok(());
+        }
+        let idle = self.settings.idle;
+        let level = idle.level_after(now.saturating_duration_since(last_activity));
+        if level == self.idle.level {
+            return Ok(());
+        }
+        if level == IdleLevel::Active {
+            self.idle.level = level;
+            return match self.idle.saved.take() {
+                Some(info) => self.fade_temporarily(info.current, WAKE_FADE, now),
+                None => Ok(()),
+            };
+        }
+
+        let saved = match &self.idle.saved {
+            Some(info) => info.current,
+            None => {
+                let info = self.keyboard()?.brightness_info()?;
+                // Backlight sudah dimatikan pengguna: tidak ada yang diredupkan
+                if !info.is_on {
+                    return Ok(());
+                }
+                let current = info.current;
+                self.idle.saved = Some(info);
+                current
+            }
+        };
+        let target = match level {
+            IdleLevel::Dimmed => idle.dimmed(saved),
+            _ => 0,
+        };
+        self.idle.level = level;
+        self.fade_temporarily(target, IDLE_FADE, now)
+    }
+
+    /// Menerapkan ulang brightness dan warna tersimpan, misalnya setelah boot
+    /// atau resume ketika firmware mengembalikan keyboard ke default.
+    pub fn restore(&mut self) -> Result<()> {
+        if self.keyboard.is_none() {
+            return Ok(());
+        }
+        self.idle = IdleState::default();
+        let rule = self.power_rule();
+        let mut state = self.settings.keyboard.clone();
+        // Warna aturan daya menggantikan warna dan efek tanpa disimpan
+        if let Some(rgb) = rule.color {
+            state.record_rgb(rgb);
+        }
+        // Belum ada yang disimpan: biarkan keyboard apa adanya
+        if state == KeyboardState::default() && rule.max_brightness.is_none() {
+            return Ok(());
+        }
+        self.apply_keyboard_state(&state)
+    }
+
+    /// Mendaftarkan thread yang memanggil `render()`, agar bisa dibangunkan
+    /// saat transisi atau efek dimulai.
+    pub fn set_animator(&mut self, thread: Thread) {
+        self.animator = Some(thread);
+    }
+
+    fn wake_animator(&self) {
+        if let Some(thread) = &self.animator {
+            thread.unpark();
+        }
+    }
+
+    /// Merender satu frame transisi dan efek yang sedang berjalan.
+    ///
+    /// Mengembalikan jeda sampai frame berikutnya, atau `None` jika tidak ada
+    /// yang perlu dianimasikan.
+    pub fn render(&mut self, now: Instant) -> Result<Option<Duration>> {
+        let fade = self.render_fade(now)?;
+        let effect = self.render_effect(now)?;
+        Ok(fade.into_iter().chain(effect).min())
+    }
+
+    /// Memulai transisi menuju `target`.
+    ///
+    /// Perintah instan yang setara dijalankan di akhir transisi, sehingga
+    /// state yang disimpan dan event yang disiarkan sama dengan tanpa transisi.
+    fn start_fade(&mut self, target: FadeTarget, transition: Transition) -> Result<()> {
+        let keyboard = self.keyboard()?;
+        let mut state = KeyboardState::default();
+        match &target {
+            FadeTarget::Brightness(level) => {
+                let max = keyboard.get_max_brightness()?;
+                state.record_brightness(BrightnessInfo::new((*level).min(max), max));
+            }
+            FadeTarget::Rgb(rgb) => state.record_rgb(*rgb),
+            FadeTarget::ZoneRgb(zone, rgb) => {
+                if *zone >= keyboard.zones().len() {
+                    return Err(M4ArchError::InvalidZone(*zone));
+                }
+                state.record_zone_rgb(*zone, *rgb);
+            }
+            FadeTarget::Profile(name) => state = self.settings.profile(name)?.keyboard.clone(),
+        }
+        // Transisi berakhir di batas aturan daya, sama seperti perintah instannya
+        let max = keyboard.get_max_brightness()?;
+        let cap = self.power_rule().brightness_cap(max);
+        state.brightness = state
+            .brightness_for(max)
+            .map(|level| BrightnessInfo::new(level.min(cap), max));
+
+        // Efek akan menimpa frame transisi, jadi kembali ke warna statis dulu
+        let changes_colors = state.rgb.is_some() || !state.zones.is_empty();
+        if changes_colors || !keyboard.capabilities().rgb {
+            self.stop_effect()?;
+        }
+        if changes_colors {
+            self.reset_mode()?;
+        }
+
+        let keyboard = self.keyboard()?;
+        let fade = m4arch_service::plan_fade(keyboard, &state, transition)?;
+        let origin = keyboard.get_brightness()?;
+        if fade.is_finished(Duration::ZERO) {
+            return self.apply(target.command()).map(drop);
+        }
+        self.fade = Some(RunningFade {
+            fade,
+            started: Instant::now(),
+            origin,
+            then: Some(target.command()),
+        });
+        self.wake_animator();
+        Ok(())
+    }
+
+    /// Transisi brightness sementara yang tidak disimpan ke pengaturan.
+    fn fade_temporarily(&mut self, level: u8, transition: Transition, now: Instant) -> Result<()> {
+        let keyboard = self.keyboard()?;
+        let origin = keyboard.get_brightness()?;
+        let fade = Fade::new(transition, keyboard.get_max_brightness()?).brightness(origin, level);
+        self.fade = Some(RunningFade {
+            fade,
+            started: now,
+            origin,
+            then: None,
+        });
+        self.wake_animator();
+        Ok(())
+    }
+
+    /// Mengembalikan brightness sebelum idle seketika, karena pengguna
+    /// mengirim perintah.
+    fn wake_from_idle(&mut self) -> Result<()> {
+        let Some(saved) = std::mem::take(&mut self.idle).saved else {
+            return Ok(());
+        };
+        let keyboard = self.keyboard()?;
+        let previous = keyboard.get_brightness()?;
+        keyboard.set_brightness(saved.current)?;
+        let info = keyboard.brightness_info()?;
+        self.known_brightness = Some(info.current);
+        self.publish_brightness(previous, info);
+        Ok(())
+    }
+
+    /// Menghentikan transisi di nilai antaranya karena ada perintah baru.
+    fn cancel_fade(&mut self) {
+        let Some(running) = self.fade.take() else {
+            return;
+        };
+        if running.then.is_none() {
+            return;
+        }
+        // Brightness antara itulah yang kini terlihat; warna akan ditimpa
+        // oleh perintah berikutnya atau dikembalikan saat restore
+        if let Ok(info) = self
+            .keyboard()
+            .and_then(|keyboard| keyboard.brightness_info())
+        {
+            self.remember(|state| state.record_brightness(info));
+        }
+    }
+
+    fn render_fade(&mut self, now: Instant) -> Result<Option<Duration>> {
+        // Dikeluarkan dulu agar transisi yang gagal tidak diulang terus
+        let Some(running) = self.fade.take() else {
+            return Ok(None);
+        };
+        let elapsed = now.saturating_duration_since(running.started);
+        if running.fade.is_finished(elapsed) {
+            let Some(command) = running.then else {
+                // Transisi sementara: tulis nilai akhir dan siarkan saja
+                let keyboard = self.keyboard()?;
+                m4arch_service::write_fade_frame(keyboard, &running.fade.frame(elapsed))?;
+                let info = keyboard.brightness_info()?;
+                self.known_brightness = Some(info.current);
+                self.publish_brightness(running.origin, info);
+                return Ok(None);
+            };
+            self.fade_origin = Some(running.origin);
+            let result = self.apply(command);
+            self.fade_origin = None;
+            return result.map(|_| None);
+        }
+
+        let frame = running.fade.frame(elapsed);
+        m4arch_service::write_fade_frame(self.keyboard()?, &frame)?;
+        if frame.brightness.is_some() {
+            self.known_brightness = frame.brightness;
+        }
+        self.fade = Some(running);
+        Ok(Some(FRAME_INTERVAL))
+    }
+
+    /// Merender satu frame efek yang sedang berjalan.
+    ///
+    /// Frame yang sama dengan frame sebelumnya tidak ditulis ulang.
+    fn render_effect(&mut self, now: Instant) -> Result<Option<Duration>> {
+        let rule = self.power_rule();
+        let (Some(keyboard), Some(running)) = (self.keyboard.as_deref(), self.effect.as_mut())
+        else {
+            return Ok(None);
+        };
+        let rgb = keyboard.capabilities().rgb;
+        // Backlight dimatikan pengguna: tidak ada yang perlu ditulis.
+        // Efek monokrom ditahan selama idle agar tidak menimpa brightness redup.
+        if (rgb && self.known_brightness == Some(0))
+            || (!rgb && self.idle.level != IdleLevel::Active)
+        {
+            return Ok(Some(FRAME_INTERVAL));
+        }
+
+        let elapsed = now.saturating_duration_since(running.started);
+        let frame = running.effect.render(elapsed, keyboard.zones().len());
+        if running.last_frame.as_ref() == Some(&frame) {
+            return Ok(Some(FRAME_INTERVAL));
+        }
+        if rgb {
+            for (zone, color) in frame.scaled_colors().into_iter().enumerate() {
+                keyboard.set_zone_rgb(zone, color)?;
+            }
+        } else {
+            // Keyboard monokrom: efek hanya bisa memainkan brightness
+            let max = keyboard.get_max_brightness()?;
+            let max = u16::from(rule.brightness_cap(max));
+            let level = ((max * u16::from(frame.intensity) + 127) / 255) as u8;
+            keyboard.set_brightness(level)?;
+            self.known_brightness = Some(level);
+        }
+        running.last_frame = Some(frame);
+        Ok(Some(FRAME_INTERVAL))
+    }
+
+    fn start_effect(&mut self, effect: Effect) -> Result<()> {
+        effect.validate()?;
+        if !self.power_rule().effects {
+            return Err(M4ArchError::Unsupported(
+                "lighting effects on the current power source".to_string(),
+            ));
+        }
+        self.reset_mode()?;
+        self.set_running_effect(Some(effect.clone()));
+        self.remember(|state| state.record_effect(effect));
+        Ok(())
+    }
+
+    /// Mode firmware selain `Static` menutupi warna statis dan frame efek,
+    /// jadi dikembalikan dulu sebelum keduanya ditulis.
+    fn reset_mode(&mut self) -> Result<()> {
+        if m4arch_service::ensure_static_mode(self.keyboard()?)? {
+            self.remember(|state| state.record_mode(KeyboardMode::Static));
+            self.events
+                .publish(IpcEvent::ModeChanged(KeyboardMode::Static));
+        }
+        Ok(())
+    }
+
+    fn set_mode(&mut self, mode: KeyboardMode) -> Result<()> {
+        let keyboard = self.keyboard()?;
+        if !keyboard.modes().contains(&mode) {
+            return Err(M4ArchError::Unsupported(format!("mode '{mode}'")));
+        }
+        // Animasi firmware menggantikan efek yang sedang berjalan
+        if mode != KeyboardMode::Static {
+            self.cancel_effect();
+        }
+        self.keyboard()?.set_mode(mode)?;
+        self.remember(|state| state.record_mode(mode));
+        self.events.publish(IpcEvent::ModeChanged(mode));
+        Ok(())
+    }
+
+    /// Menghentikan efek dan mengembalikan warna statis yang tersimpan.
+    fn stop_effect(&mut self) -> Result<()> {
+        if self.effect.is_none() {
+            return Ok(());
+        }
+        self.remember(|state| state.effect = None);
+        let state = self.settings.keyboard.clone();
+        self.apply_keyboard_state(&state)
+    }
+
+    /// Menghentikan efek tanpa mengembalikan warna, karena pemanggil akan
+    /// langsung menimpanya.
+    fn cancel_effect(&mut self) {
+        if self.effect.is_some() {
+            self.set_running_effect(None);
+            self.remember(|state| state.effect = None);
+        }
+    }
+
+    fn set_running_effect(&mut self, effect: Option<Effect>) {
+        if let Some(running) = &mut self.effect {
+            if Some(&running.effect) == effect.as_ref() {
+                // Efek yang sama tetap berjalan; hardware mungkin baru ditimpa
+                running.last_frame = None;
+                return;
+            }
+        }
+        self.effect = effect.clone().map(|effect| RunningEffect {
+            effect,
+            started: Instant::now(),
+            last_frame: None,
+        });
+        self.wake_animator();
+        self.events.publish(IpcEvent::EffectChanged(effect));
+    }
+
+    /// Menerapkan profil sekaligus; profil menjadi state yang diingat.
+    fn apply_profile(&mut self, name: &str) -> Result<()> {
+        let state = self.settings.profile(name)?.keyboard.clone();
+        self.apply_keyboard_state(&state)?;
+        self.remember(|current| *current = state);
+        self.events
+            .publish(IpcEvent::ProfileChanged(name.to_string()));
+        Ok(())
+    }
+
+    fn save_profile(&mut self, name: &str) -> Result<()> {
+        let mut keyboard = m4arch_service::capture_state(self.keyboard()?)?;
+        if let Some(running) = &self.effect {
+            // Yang ada di hardware adalah frame animasi; simpan state statisnya
+            let saved = &self.settings.keyboard;
+            keyboard.brightness = saved.brightness.clone().or(keyboard.brightness);
+            keyboard.rgb = saved.rgb;
+            keyboard.zones = saved.zones.clone();
+            keyboard.effect = Some(running.effect.clone());
+        }
+        self.settings.save_profile(name, Profile { keyboard })?;
+        self.store.save(&self.settings)
+    }
+
+    fn set_idle(&mut self, idle: IdleSettings) -> Result<()> {
+        idle.validate()?;
+        self.settings.idle = idle;
+        self.store.save(&self.settings)
+    }
+
+    /// Entry profil harus merujuk ke profil yang sudah ada.
+    fn set_schedule(&mut self, schedule: Schedule) -> Result<()> {
+        schedule.validate()?;
+        for entry in &schedule.entries {
+            if let ScheduleAction::Profile(name) = &entry.action {
+                self.settings.profile(name)?;
+            }
+        }
+        self.settings.schedule = schedule;
+        self.store.save(&self.settings)
+    }
+
+    /// Menyimpan kalibrasi baru lalu menerapkan ulang warna tersimpan dengannya.
+    fn set_calibration(&mut self, calibration: Calibration) -> Result<()> {
+        calibration.validate()?;
+        self.settings.calibration = calibration;
+        self.store.save(&self.settings)?;
+        // Controller dibuat ulang agar dibungkus dengan kalibrasi baru
+        self.keyboard = Some(detect_keyboard(&self.root, calibration).ok_or_else(no_keyboard)?);
+        self.reset_known_state();
+        self.restore()
+    }
+
+    /// Menampilkan `rgb` di semua zona dengan kalibrasi percobaan.
+    ///
+    /// Tidak ada yang disimpan; efek berhenti sementara agar tidak menimpa
+    /// warnanya, dan berjalan lagi saat `restore()`.
+    fn preview_calibration(&mut self, calibration: Calibration, rgb: Rgb) -> Result<()> {
+        calibration.validate()?;
+        if !self.keyboard()?.capabilities().rgb {
+            return Err(M4ArchError::Unsupported(
+                "colour calibration without RGB".to_string(),
+            ));
+        }
+        let preview = detect_keyboard(&self.root, calibration).ok_or_else(no_keyboard)?;
+        self.set_running_effect(None);
+        preview.set_rgb(rgb)
+    }
+
+    /// Menyimpan pengaturan kipas lalu langsung menerapkannya.
+    fn set_fan_settings(&mut self, settings: FanSettings) -> Result<()> {
+        settings.validate()?;
+        self.fans()?;
+        self.settings.fan = settings;
+        self.store.save(&self.settings)?;
+        // Kurva baru berlaku penuh, tanpa menunggu histeresis kurva lama
+        self.reset_fans();
+        self.update_fans()
+    }
+
+    fn fans(&self) -> Result<&FanCurveController> {
+        self.fans.as_ref().ok_or_else(|| {
+            M4ArchError::Unsupported("fan control (is tuxedo_io loaded?)".to_string())
+        })
+    }
+
+    fn power_profiles(&self) -> Result<&dyn PowerProfileController> {
+        self.power_profiles.as_deref().ok_or_else(|| {
+            M4ArchError::Unsupported(
+                "power profiles (no platform_profile or tuxedo_io)".to_string(),
+            )
+        })
+    }
+
+    /// Profil aktif. Backend yang tidak bisa dibaca (`tuxedo_io`) dijawab
+    /// dengan profil tersimpan.
+    fn power_profile(&self) -> Result<PowerProfile> {
+        match self.power_profiles()?.get_profile() {
+            Err(M4ArchError::Unsupported(what)) => self
+                .settings
+                .power_profile
+                .ok_or(M4ArchError::Unsupported(what)),
+            result => result,
+        }
+    }
+
+    /// Mengganti profil performa lalu menyimpannya untuk boot dan resume.
+    /// Tuning CPU manual dibuang karena profil membawa tuning-nya sendiri.
+    fn set_power_profile(&mut self, profile: PowerProfile) -> Result<()> {
+        self.power_profiles()?.set_profile(profile)?;
+        self.settings.power_profile = Some(profile);
+        self.settings.cpu = CpuTuning::default();
+        self.store.save(&self.settings)?;
+        self.events.publish(IpcEvent::PowerProfileChanged(profile));
+        Ok(())
+    }
+
+    fn cpu(&self) -> Result<&dyn CpuController> {
+        self.cpu
+            .as_deref()
+            .ok_or_else(|| M4ArchError::Unsupported("CPU tuning (no cpufreq)".to_string()))
+    }
+
+    /// Menerapkan tuning CPU lalu mengingatnya di atas tuning yang sudah ada.
+    fn tune_cpu(&mut self, tuning: CpuTuning) -> Result<()> {
+        self.cpu()?.apply(&tuning)?;
+        self.settings.cpu.merge(tuning);
+        self.store.save(&self.settings)
+    }
+
+    fn charge(&self) -> Result<&dyn ChargeController> {
+        self.charge.as_deref().ok_or_else(|| {
+            M4ArchError::Unsupported("charge thresholds (no charge_control attributes)".to_string())
+        })
+    }
+
+    fn set_charge_thresholds(&mut self, thresholds: ChargeThresholds) -> Result<()> {
+        self.charge()?.set_thresholds(thresholds)?;
+        self.settings.charge = Some(thresholds);
+        self.store.save(&self.settings)
+    }
+
+    fn delete_profile(&mut self, name: &str) -> Result<()> {
+        self.settings.remove_profile(name)?;
+        self.store.save(&self.settings)
+    }
+
+    /// Menerapkan state ke keyboard lalu menyiarkan hasilnya.
+    fn apply_keyboard_state(&mut self, state: &KeyboardState) -> Result<()> {
+        // Pinjam field-nya saja agar state lain tetap bisa diubah
+        let previous = self.previous_brightness()?;
+        let rule = self.power_rule();
+        let keyboard = self.keyboard.as_deref().ok_or_else(no_keyboard)?;
+        m4arch_service::apply_state(keyboard, state)?;
+        let cap = rule.brightness_cap(keyboard.get_max_brightness()?);
+        if keyboard.get_brightness()? > cap {
+            keyboard.set_brightness(cap)?;
+        }
+
+        let info = keyboard.brightness_info()?;
+        if info.current > 0 {
+            self.last_brightness = Some(info.current);
+        }
+        if keyboard.capabilities().rgb {
+            for zone in keyboard.zones() {
+                let rgb = keyboard.get_zone_rgb(zone.index)?;
+                self.events.publish(IpcEvent::ColorChanged {
+                    zone: Some(zone.index),
+                    rgb,
+                });
+            }
+        }
+        self.reset_known_state();
+        self.publish_brightness(previous, info);
+        self.set_running_effect(state.effect.clone().filter(|_| rule.effects));
+        Ok(())
+    }
+
+    /// Mencatat perubahan state dan langsung menyimpannya ke disk.
+    fn remember(&mut self, change: impl FnOnce(&mut KeyboardState)) {
+        change(&mut self.settings.keyboard);
+        if let Err(e) = self.store.save(&self.settings) {
+            eprintln!(
+                "m4arch-daemon: cannot save {}: {}",
+                self.store.path().display(),
+                e
+            );
+        }
+    }
+
+    /// Mengambil nilai awal untuk `poll_hardware()`.
+    fn reset_known_state(&mut self) {
+        let keyboard = self.keyboard.as_deref();
+        self.known_brightness = keyboard.and_then(|keyboard| keyboard.get_brightness().ok());
+        self.known_hw_changed =
+            keyboard.and_then(|keyboard| keyboard.brightness_hw_changed().ok().flatten());
+    }
+
+    /// Menjalankan satu perintah dan mengubah hasilnya menjadi balasan IPC.
+    pub fn handle(&mut self, command: IpcCommand) -> std::result::Result<IpcReply, IpcError> {
+        // Kipas dan profil performa tidak bergantung pada keyboard maupun
+        // transisinya
+        let command = match command {
+            IpcCommand::Fan(command) => return self.apply_fan(command).map_err(IpcError::from),
+            IpcCommand::PowerProfile(command) => {
+                return self.apply_power_profile(command).map_err(IpcError::from)
+            }
+            IpcCommand::Cpu(command) => return self.apply_cpu(command).map_err(IpcError::from),
+            IpcCommand::Charge(command) => {
+                return self.apply_charge(command).map_err(IpcError::from)
+            }
+            command => command,
+        };
+        if self.keyboard.is_none() && !matches!(command, IpcCommand::Hello { .. }) {
+            return Err(IpcError::NoKeyboard);
+        }
+        if !command.is_query() {
+            self.cancel_fade();
+            // Perintah dari pengguna berarti pengguna sedang aktif
+            self.wake_from_idle().map_err(IpcError::from)?;
+        }
+        self.apply(command).map_err(IpcError::from)
+    }
+
+    fn apply_fan(&mut self, command: FanCommand) -> Result<IpcReply> {
+        let reply = match command {
+            FanCommand::GetInfo => IpcReply::Fans(self.fans()?.fan_info()?),
+            FanCommand::GetSettings => IpcReply::FanSettings(self.settings.fan.clone()),
+            FanCommand::SetSettings(settings) => {
+                self.set_fan_settings(settings)?;
+                IpcReply::Done
+            }
+        };
+        Ok(reply)
+    }
+
+    fn apply_power_profile(&mut self, command: PowerProfileCommand) -> Result<IpcReply> {
+        let reply = match command {
+            PowerProfileCommand::List => IpcReply::PowerProfiles(self.power_profiles()?.profiles()),
+            PowerProfileCommand::Get => IpcReply::PowerProfile(self.power_profile()?),
+            PowerProfileCommand::Set(profile) => {
+                self.set_power_profile(profile)?;
+                IpcReply::Done
+            }
+        };
+        Ok(reply)
+    }
+
+    fn apply_cpu(&mut self, command: CpuCommand) -> Result<IpcReply> {
+        let reply = match command {
+            CpuCommand::Status => IpcReply::CpuStatus(self.cpu()?.status()?),
+            CpuCommand::Tune(tuning) => {
+                self.tune_cpu(tuning)?;
+                IpcReply::Done
+            }
+        };
+        Ok(reply)
+    }
+
+    fn apply_charge(&mut self, command: ChargeCommand) -> Result<IpcReply> {
+        let reply = match command {
+            ChargeCommand::Get => IpcReply::ChargeThresholds(self.charge()?.get_thresholds()?),
+            // Dibaca langsung dari sysfs; tidak butuh backend ambang
+            ChargeCommand::Batteries => {
+                IpcReply::Batteries(m4arch_service::battery::read_batteries(&self.root)?)
+            }
+            ChargeCommand::Set(thresholds) => {
+                self.set_charge_thresholds(thresholds)?;
+                IpcReply::Done
+            }
+        };
+        Ok(reply)
+    }
+
+    fn keyboard(&self) -> Result<&dyn KeyboardController> {
+        self.keyboard.as_deref().ok_or_else(no_keyboard)
+    }
+
+    fn apply(&mut self, command: IpcCommand) -> Result<IpcReply> {
+        let keyboard = self.keyboard()?;
+        let reply = match command {
+            // Handshake ditangani oleh server, tetapi tetap dijawab di sini
+            IpcCommand::Hello { .. } => IpcReply::Hello {
+                version: PROTOCOL_VERSION,
+            },
+
+            // === Perintah yang mengubah hardware ===
+            IpcCommand::SetBrightness(level) => {
+                self.set_brightness(level)?;
+                IpcReply::Done
+            }
+            IpcCommand::IncreaseBrightness(step) => {
+                let max = keyboard.get_max_brightness()?;
+                let level = keyboard.get_brightness()?.saturating_add(step).min(max);
+                self.set_brightness(level)?;
+                IpcReply::Done
+            }
+            IpcCommand::DecreaseBrightness(step) => {
+                let level = keyboard.get_brightness()?.saturating_sub(step);
+                self.set_brightness(level)?;
+                IpcReply::Done
+            }
+            IpcCommand::SetBrightnessPercent(percent) => {
+                let level = brightness::level_for_percent(percent, keyboard.get_max_brightness()?);
+                self.set_brightness(level)?;
+                IpcReply::Done
+            }
+            IpcCommand::StepBrightness(direction, steps) => {
+                let max = keyboard.get_max_brightness()?;
+                let level =
+                    brightness::next_level(keyboard.get_brightness()?, max, steps, direction);
+                self.set_brightness(level)?;
+                IpcReply::Done
+            }
+            IpcCommand::SetRgb(r, g, b) => {
+                let rgb = Rgb { r, g, b };
+                self.cancel_effect();
+                self.reset_mode()?;
+                self.keyboard()?.set_rgb(rgb)?;
+                self.remember(|state| state.record_rgb(rgb));
+                self.events
+                    .publish(IpcEvent::ColorChanged { zone: None, rgb });
+                IpcReply::Done
+            }
+            IpcCommand::SetZoneRgb(zone, r, g, b) => {
+                let rgb = Rgb { r, g, b };
+                // Zona lain harus kembali ke warna statisnya, bukan frame terakhir
+                self.stop_effect()?;
+                self.reset_mode()?;
+                self.keyboard()?.set_zone_rgb(zone, rgb)?;
+                self.remember(|state| state.record_zone_rgb(zone, rgb));
+                self.events.publish(IpcEvent::ColorChanged {
+                    zone: Some(zone),
+                    rgb,
+                });
+                IpcReply::Done
+            }
+            IpcCommand::Power(on) => {
+                self.power(on)?;
+                IpcReply::Done
+            }
+            IpcCommand::Restore => {
+                self.restore()?;
+                IpcReply::Done
+            }
+            IpcCommand::Fade(target, transition) => {
+                self.start_fade(target, transition)?;
+                IpcReply::Done
+            }
+
+            // === Efek ===
+            IpcCommand::SetEffect(effect) => {
+                self.start_effect(effect)?;
+                IpcReply::Done
+            }
+            IpcCommand::StopEffect => {
+                self.stop_effect()?;
+                IpcReply::Done
+            }
+            IpcCommand::GetEffect => {
+                IpcReply::Effect(self.effect.as_ref().map(|running| running.effect.clone()))
+            }
+
+            // === Mode firmware ===
+            IpcCommand::ListModes => IpcReply::Modes(keyboard.modes()),
+            IpcCommand::GetMode => IpcReply::Mode(keyboard.get_mode()?),
+            IpcCommand::SetMode(mode) => {
+                self.set_mode(mode)?;
+                IpcReply::Done
+            }
+
+            // === Timeout idle ===
+            IpcCommand::GetIdle => IpcReply::Idle(self.settings.idle),
+            IpcCommand::SetIdle(idle) => {
+                self.set_idle(idle)?;
+                IpcReply::Done
+            }
+
+            // === Jadwal ===
+            IpcCommand::GetSchedule => IpcReply::Schedule(self.settings.schedule.clone()),
+            IpcCommand::SetSchedule(schedule) => {
+                self.set_schedule(schedule)?;
+                IpcReply::Done
+            }
+
+            // === Kalibrasi warna ===
+            IpcCommand::GetCalibration => IpcReply::Calibration(self.settings.calibration),
+            IpcCommand::SetCalibration(calibration) => {
+                self.set_calibration(calibration)?;
+                IpcReply::Done
+            }
+            IpcCommand::PreviewCalibration(calibration, rgb) => {
+                self.preview_calibration(calibration, rgb)?;
+                IpcReply::Done
+            }
+
+            // === Kipas ===
+            IpcCommand::Fan(command) => self.apply_fan(command)?,
+
+            // === Profil performa ===
+            IpcCommand::PowerProfile(command) => self.apply_power_profile(command)?,
+            IpcCommand::Cpu(command) => self.apply_cpu(command)?,
+
+            // === Baterai ===
+            IpcCommand::Charge(command) => self.apply_charge(command)?,
+
+            // === Profil ===
+            IpcCommand::SaveProfile(name) => {
+                self.save_profile(&name)?;
+                IpcReply::Done
+            }
+            IpcCommand::ApplyProfile(name) => {
+                self.apply_profile(&name)?;
+                IpcReply::Done
+            }
+            IpcCommand::DeleteProfile(name) => {
+                self.delete_profile(&name)?;
+                IpcReply::Done
+            }
+            IpcCommand::ListProfiles => IpcReply::Profiles(self.settings.profile_names()),
+
+            // === Query ===
+            IpcCommand::GetBrightnessInfo => IpcReply::BrightnessInfo(keyboard.brightness_info()?),
+            IpcCommand::GetRgb => IpcReply::Rgb(keyboard.get_rgb()?),
+            IpcCommand::GetZoneRgb(zone) => IpcReply::Rgb(keyboard.get_zone_rgb(zone)?),
+            IpcCommand::GetZones => IpcReply::Zones(keyboard.zones()),
+            IpcCommand::GetCapabilities => IpcReply::Capabilities(keyboard.capabilities()),
+
+            // Langganan butuh koneksi sendiri, jadi ditangani oleh server
+            IpcCommand::Subscribe => {
+                return Err(M4ArchError::Unsupported(
+                    "subscriptions are handled by the server".to_string(),
+                ))
+            }
+        };
+        Ok(reply)
+    }
+
+    fn power(&mut self, on: bool) -> Result<()> {
+        if !on {
+            return self.set_brightness(0);
+        }
+        let keyboard = self.keyboard()?;
+        if keyboard.get_brightness()? > 0 {
+            return Ok(());
+        }
+        // Kembalikan brightness terakhir, atau maksimum jika belum ada
+        let level = match self.last_brightness {
+            Some(level) => level,
+            None => keyboard.get_max_brightness()?,
+        };
+        self.set_brightness(level)
+    }
+
+    fn set_brightness(&mut self, level: u8) -> Result<()> {
+        // Efek pada keyboard monokrom mengatur brightness sendiri
+        if !self.keyboard()?.capabilities().rgb {
+            self.cancel_effect();
+        }
+        let current = self.previous_brightness()?;
+        let keyboard = self.keyboard()?;
+        let max = keyboard.get_max_brightness()?;
+        let applied = level.min(self.power_rule().brightness_cap(max));
+        keyboard.set_brightness(applied)?;
+        let info = keyboard.brightness_info()?;
+        if current > 0 {
+            self.last_brightness = Some(current);
+        }
+        self.known_brightness = Some(info.current);
+        // Yang disimpan adalah pilihan pengguna, bukan nilai yang dibatasi
+        // aturan daya, agar kembali penuh saat adaptor dipasang lagi
+        let chosen = if applied < level {
+            BrightnessInfo::new(level.min(max), max)
+        } else {
+            info.clone()
+        };
+        self.remember(|state| state.record_brightness(chosen));
+        self.publish_brightness(current, info);
+        Ok(())
+    }
+
+    /// Brightness sebelum perubahan yang sedang diterapkan: nilai awal
+    /// transisi yang sedang diselesaikan, atau nilai hardware saat ini.
+    fn previous_brightness(&self) -> Result<u8> {
+        match self.fade_origin {
+            Some(level) => Ok(level),
+            None => self.keyboard()?.get_brightness(),
+        }
+    }
+
+    fn publish_brightness(&self, previous: u8, info: BrightnessInfo) {
+        let is_on = info.is_on;
+        self.events.publish(IpcEvent::BrightnessChanged(info));
+        if (previous > 0) != is_on {
+            self.events.publish(IpcEvent::PowerChanged(is_on));
+        }
+    }
+}
+
+/// Mencari keyboard di bawah `root` dan memasang kalibrasi warnanya.
+fn detect_keyboard(root: &Path, calibration: Calibration) -> Option<Box<dyn KeyboardController>> {
+    m4arch_service::get_keyboard_at(root)
+        .map(|keyboard| CalibratedKeyboard::wrap(keyboard, calibration))
+}
+
+fn no_keyboard() -> M4ArchError {
+    M4ArchError::from(io::Error::new(
+        io::ErrorKind::NotFound,
+        "No supported keyboard driver found",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use m4arch_core::battery::ChargePreset;
+    use m4arch_core::config::{FanMode, LowBattery, ScheduleEntry};
+    use m4arch_core::fan::{Fan, FanInfo};
+    use m4arch_core::keyboard::{EffectKind, StepDirection};
+    use m4arch_service::cpu;
+    use m4arch_service::sysfs::fake::{FakeLed, FakeSysfs};
+    use m4arch_service::tuxedo_io::fake::FakeIoctl;
+    use m4arch_service::tuxedo_io::{request, TuxedoIo};
+    use m4arch_service::tuxedo_legacy::TuxedoLegacyKeyboard;
+
+    const LED: &str = "class/leds/rgb:kbd_backlight";
+
+    fn path(attribute: &str) -> String {
+        format!("{LED}/{attribute}")
+    }
+
+    fn store(fake: &FakeSysfs) -> SettingsStore {
+        SettingsStore::new(fake.path("var/lib/m4arch/settings.toml"))
+    }
+
+    fn setup() -> (FakeSysfs, Daemon) {
+        let fake = FakeSysfs::new().unwrap();
+        fake.add_led(LED, &FakeLed::rgb(100, 200, (0, 0, 0)))
+            .unwrap();
+        let daemon = Daemon::new(fake.root(), store(&fake));
+        (fake, daemon)
+    }
+
+    #[test]
+    fn test_applies_commands() {
+        let (fake, mut daemon) = setup();
+        assert_eq!(
+            daemon.handle(IpcCommand::SetBrightness(50)),
+            Ok(IpcReply::Done)
+        );
+        assert_eq!(fake.read(path("brightness")).unwrap(), "50");
+
+        daemon.handle(IpcCommand::IncreaseBrightness(255)).unwrap();
+        assert_eq!(fake.read(path("brightness")).unwrap(), "200");
+        daemon.handle(IpcCommand::DecreaseBrightness(50)).unwrap();
+        assert_eq!(fake.read(path("brightness")).unwrap(), "150");
+
+        assert_eq!(
+            daemon.handle(IpcCommand::SetRgb(255, 0, 0)),
+            Ok(IpcReply::Done)
+        );
+        assert_eq!(fake.read(path("multi_intensity")).unwrap(), "200 0 0");
+    }
+
+    #[test]
+    fn test_percent_and_perceptual_steps() {
+        let (fake, mut daemon) = setup();
+        daemon.handle(IpcCommand::SetBrightnessPercent(40)).unwrap();
+        assert_eq!(fake.read(path("brightness")).unwrap(), "80");
+
+        // Tangga 10 langkah untuk max 200: 0, 2, 3, 5, 8, 14, 24, 41, 69, 118, 200
+        let step = |daemon: &mut Daemon, direction| {
+            daemon
+                .handle(IpcCommand::StepBrightness(direction, 10))
+                .unwrap();
+            fake.read(path("brightness")).unwrap()
+        };
+        assert_eq!(step(&mut daemon, StepDirection::Up), "118");
+        assert_eq!(step(&mut daemon, StepDirection::Down), "69");
+        assert_eq!(step(&mut daemon, StepDirection::Down), "41");
+    }
+
+    #[test]
+    fn test_answers_queries() {
+        let (_fake, mut daemon) = setup();
+        let Ok(IpcReply::BrightnessInfo(info)) = daemon.handle(IpcCommand::GetBrightnessInfo)
+        else {
+            panic!("expected brightness info");
+        };
+        assert_eq!((info.current, info.max, info.percent), (100, 200, 50));
+
+        assert_eq!(
+            daemon.handle(IpcCommand::GetRgb),
+            Ok(IpcReply::Rgb(Rgb { r: 0, g: 0, b: 0 }))
+        );
+        let Ok(IpcReply::Capabilities(caps)) = daemon.handle(IpcCommand::GetCapabilities) else {
+            panic!("expected capabilities");
+        };
+        assert!(caps.rgb && caps.brightness);
+        assert_eq!(
+            daemon.handle(IpcCommand::GetZoneRgb(1)),
+            Err(IpcError::InvalidZone(1))
+        );
+    }
+
+    #[test]
+    fn test_power_restores_last_brightness() {
+        let (fake, mut daemon) = setup();
+        daemon.handle(IpcCommand::Power(false)).unwrap();
+        assert_eq!(fake.read(path("brightness")).unwrap(), "0");
+        daemon.handle(IpcCommand::Power(true)).unwrap();
+        assert_eq!(fake.read(path("brightness")).unwrap(), "100");
+    }
+
+    #[test]
+    fn test_power_on_defaults_to_max() {
+        let (fake, mut daemon) = setup();
+        fake.write(path("brightness"), "0").unwrap();
+        daemon.handle(IpcCommand::Power(true)).unwrap();
+        assert_eq!(fake.read(path("brightness")).unwrap(), "200");
+    }
+
+    #[test]
+    fn test_reports_errors() {
+        let (fake, mut daemon) = setup();
+        fake.remove(path("brightness")).unwrap();
+        assert!(matches!(
+            daemon.handle(IpcCommand::SetBrightness(1)),
+            Err(IpcError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn test_publishes_state_changes() {
+        let (_fake, mut daemon) = setup();
+        let events = daemon.subscribe();
+
+        daemon.handle(IpcCommand::Power(false)).unwrap();
+        daemon.handle(IpcCommand::SetZoneRgb(0, 1, 2, 3)).unwrap();
+        daemon.handle(IpcCommand::GetRgb).unwrap();
+
+        let received: Vec<IpcEvent> = events.try_iter().collect();
+        assert_eq!(
+            received,
+            [
+                IpcEvent::BrightnessChanged(BrightnessInfo {
+                    current: 0,
+                    max: 200,
+                    percent: 0,
+                    is_on: false,
+                }),
+                IpcEvent::PowerChanged(false),
+                IpcEvent::ColorChanged {
+                    zone: Some(0),
+                    rgb: Rgb { r: 1, g: 2, b: 3 },
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rescan_tracks_driver() {
+        let fake = FakeSysfs::new().unwrap();
+        let mut daemon = Daemon::new(fake.root(), store(&fake));
+        let events = daemon.subscribe();
+        assert_eq!(daemon.handle(IpcCommand::GetRgb), Err(IpcError::NoKeyboard));
+
+        fake.add_led(LED, &FakeLed::rgb(1, 255, (0, 0, 0))).unwrap();
+        daemon.rescan();
+        assert!(daemon.has_keyboard());
+        assert!(matches!(events.try_recv(), Ok(IpcEvent::DriverAdded(caps)) if caps.rgb));
+
+        std::fs::remove_dir_all(fake.path(LED)).unwrap();
+        daemon.rescan();
+        assert!(!daemon.has_keyboard());
+        assert_eq!(events.try_recv(), Ok(IpcEvent::DriverRemoved));
+    }
+
+    #[test]
+    fn test_detects_hotkey_changes() {
+        let (fake, mut daemon) = setup();
+        let events = daemon.subscribe();
+        daemon.poll_hardware().unwrap();
+        assert_eq!(events.try_recv().ok(), None);
+
+        // Hotkey mematikan backlight tanpa lewat daemon
+        fake.write(path("brightness"), "0").unwrap();
+        fake.write(path("brightness_hw_changed"), "0").unwrap();
+        daemon.poll_hardware().unwrap();
+        let received: Vec<IpcEvent> = events.try_iter().collect();
+        assert_eq!(received.len(), 2);
+        assert!(matches!(&received[0], IpcEvent::BrightnessChanged(info) if info.current == 0));
+        assert_eq!(received[1], IpcEvent::PowerChanged(false));
+
+        // Perubahan yang sama tidak dilaporkan dua kali
+        daemon.poll_hardware().unwrap();
+        assert_eq!(events.try_recv().ok(), None);
+
+        // Level dari hotkey menjadi brightness pilihan untuk `Power(true)`
+        fake.write(path("brightness"), "150").unwrap();
+        fake.write(path("brightness_hw_changed"), "150").unwrap();
+        daemon.poll_hardware().unwrap();
+        daemon.handle(IpcCommand::Power(false)).unwrap();
+        daemon.handle(IpcCommand::Power(true)).unwrap();
+        assert_eq!(fake.read(path("brightness")).unwrap(), "150");
+    }
+
+    #[test]
+    fn test_own_writes_are_not_hardware_events() {
+        let (_fake, mut daemon) = setup();
+        daemon.handle(IpcCommand::SetBrightness(20)).unwrap();
+        let events = daemon.subscribe();
+        daemon.poll_hardware().unwrap();
+        assert_eq!(events.try_recv().ok(), None);
+    }
+
+    #[test]
+    fn test_persists_and_restores_state() {
+        let (fake, mut daemon) = setup();
+        daemon.handle(IpcCommand::SetBrightness(42)).unwrap();
+        daemon.handle(IpcCommand::SetRgb(10, 20, 30)).unwrap();
+        let saved = store(&fake).load().unwrap().keyboard;
+        assert_eq!(saved.brightness.map(|info| info.current), Some(42));
+        assert_eq!(
+            saved.rgb,
+            Some(Rgb {
+                r: 10,
+                g: 20,
+                b: 30
+            })
+        );
+
+        // Firmware mengembalikan keyboard ke default (reboot/resume)
+        fake.write(path("brightness"), "200").unwrap();
+        fake.write(path("multi_intensity"), "200 200 200").unwrap();
+        let mut daemon = Daemon::new(fake.root(), store(&fake));
+        let events = daemon.subscribe();
+        daemon.handle(IpcCommand::Restore).unwrap();
+        assert_eq!(fake.read(path("brightness")).unwrap(), "42");
+        assert_eq!(fake.read(path("multi_intensity")).unwrap(), "8 16 24");
+        assert!(events
+            .try_iter()
+            .any(|event| matches!(event, IpcEvent::BrightnessChanged(info) if info.current == 42)));
+    }
+
+    #[test]
+    fn test_restores_when_driver_appears() {
+        let fake = FakeSysfs::new().unwrap();
+        let mut state = KeyboardState::default();
+        state.record_rgb(Rgb { r: 0, g: 0, b: 255 });
+        store(&fake)
+            .update(|settings| settings.keyboard = state)
+            .unwrap();
+
+        let mut daemon = Daemon::new(fake.root(), store(&fake));
+        fake.add_led(LED, &FakeLed::rgb(1, 255, (0, 0, 0))).unwrap();
+        daemon.rescan();
+        assert_eq!(fake.read(path("multi_intensity")).unwrap(), "0 0 255");
+    }
+
+    #[test]
+    fn test_hotkey_changes_are_persisted() {
+        let (fake, mut daemon) = setup();
+        fake.write(path("brightness"), "7").unwrap();
+        daemon.poll_hardware().unwrap();
+        let saved = store(&fake).load().unwrap().keyboard;
+        assert_eq!(saved.brightness.map(|info| info.current), Some(7));
+    }
+
+    #[test]
+    fn test_profiles() {
+        let (fake, mut daemon) = setup();
+        let save = |name: &str| IpcCommand::SaveProfile(name.to_string());
+        daemon.handle(IpcCommand::SetRgb(255, 0, 0)).unwrap();
+        daemon.handle(save("gaming")).unwrap();
+        daemon.handle(IpcCommand::SetBrightness(0)).unwrap();
+        daemon.handle(save("night")).unwrap();
+        assert_eq!(
+            daemon.handle(IpcCommand::ListProfiles),
+            Ok(IpcReply::Profiles(vec![
+                "gaming".to_string(),
+                "night".to_string()
+            ]))
+        );
+
+        let events = daemon.subscribe();
+        daemon
+            .handle(IpcCommand::ApplyProfile("gaming".to_string()))
+            .unwrap();
+        assert_eq!(fake.read(path("brightness")).unwrap(), "100");
+        assert_eq!(fake.read(path("multi_intensity")).unwrap(), "200 0 0");
+        assert!(events
+            .try_iter()
+            .any(|event| event == IpcEvent::ProfileChanged("gaming".to_string())));
+        let saved = store(&fake).load().unwrap();
+        assert_eq!(
+            saved.keyboard.brightness.map(|info| info.current),
+            Some(100)
+        );
+
+        daemon
+            .handle(IpcCommand::DeleteProfile("gaming".to_string()))
+            .unwrap();
+        assert_eq!(
+            daemon.handle(IpcCommand::ApplyProfile("gaming".to_string())),
+            Err(IpcError::UnknownProfile("gaming".to_string()))
+        );
+        assert_eq!(daemon.handle(save("bad name")), Err(IpcError::InvalidValue));
+    }
+
+    #[test]
+    fn test_renders_effects() {
+        let (fake, mut daemon) = setup();
+        let events = daemon.subscribe();
+        let effect = Effect {
+            colors: vec![Rgb { r: 255, g: 0, b: 0 }],
+            ..Effect::new(EffectKind::Strobe)
+        };
+        daemon
+            .handle(IpcCommand::SetEffect(effect.clone()))
+            .unwrap();
+        assert_eq!(
+            daemon.handle(IpcCommand::GetEffect),
+            Ok(IpcReply::Effect(Some(effect.clone())))
+        );
+        assert_eq!(
+            events.try_recv(),
+            Ok(IpcEvent::EffectChanged(Some(effect.clone())))
+        );
+
+        let started = daemon.effect.as_ref().unwrap().started;
+        let period = effect.period();
+        assert_eq!(daemon.render(started).unwrap(), Some(FRAME_INTERVAL));
+        assert_eq!(fake.read(path("multi_intensity")).unwrap(), "200 0 0");
+        daemon.render(started + period * 3 / 4).unwrap();
+        assert_eq!(fake.read(path("multi_intensity")).unwrap(), "0 0 0");
+
+        // Frame yang sama tidak ditulis ulang
+        fake.write(path("multi_intensity"), "1 1 1").unwrap();
+        daemon.render(started + period * 3 / 4).unwrap();
+        assert_eq!(fake.read(path("multi_intensity")).unwrap(), "1 1 1");
+    }
+
+    #[test]
+    fn test_static_color_stops_effect() {
+        let (fake, mut daemon) = setup();
+        daemon.handle(IpcCommand::SetRgb(0, 255, 0)).unwrap();
+        daemon
+            .handle(IpcCommand::SetEffect(Effect::new(EffectKind::Cycle)))
+            .unwrap();
+        assert!(store(&fake).load().unwrap().keyboard.effect.is_some());
+        daemon.render(Instant::now()).unwrap();
+
+        daemon.handle(IpcCommand::StopEffect).unwrap();
+        assert_eq!(daemon.render(Instant::now()).unwrap(), None);
+        assert_eq!(fake.read(path("multi_intensity")).unwrap(), "0 200 0");
+        assert!(store(&fake).load().unwrap().keyboard.effect.is_none());
+
+        daemon
+            .handle(IpcCommand::SetEffect(Effect::new(EffectKind::Cycle)))
+            .unwrap();
+        daemon.handle(IpcCommand::SetRgb(1, 2, 3)).unwrap();
+        assert_eq!(
+            daemon.handle(IpcCommand::GetEffect),
+            Ok(IpcReply::Effect(None))
+        );
+    }
+
+    #[test]
+    fn test_static_color_and_effects_reset_firmware_mode() {
+        let (_fake, mut daemon) = setup();
+        assert_eq!(
+            daemon.handle(IpcCommand::ListModes),
+            Ok(IpcReply::Modes(Vec::new()))
+        );
+        assert!(matches!(
+            daemon.handle(IpcCommand::SetMode(KeyboardMode::Wave)),
+            Err(IpcError::Unsupported(_))
+        ));
+
+        let fake = FakeSysfs::new().unwrap();
+        let dir = TuxedoLegacyKeyboard::DEVICE_DIR;
+        for (attr, value) in [
+            ("brightness", "100"),
+            ("mode", "0"),
+            ("color_left", "000000"),
+        ] {
+            fake.write(format!("{dir}/{attr}"), value).unwrap();
+        }
+        let mode = || fake.read(format!("{dir}/mode")).unwrap();
+        let mut daemon = Daemon::new(fake.root(), store(&fake));
+        let events = daemon.subscribe();
+
+        daemon
+            .handle(IpcCommand::SetMode(KeyboardMode::Wave))
+            .unwrap();
+        assert_eq!(mode(), "7");
+        assert!(events
+            .try_iter()
+            .any(|event| event == IpcEvent::ModeChanged(KeyboardMode::Wave)));
+
+        daemon.handle(IpcCommand::SetRgb(0, 255, 0)).unwrap();
+        assert_eq!(mode(), "0");
+        assert_eq!(fake.read(format!("{dir}/color_left")).unwrap(), "0x00ff00");
+        assert!(events
+            .try_iter()
+            .any(|event| event == IpcEvent::ModeChanged(KeyboardMode::Static)));
+        assert_eq!(
+            daemon.handle(IpcCommand::GetMode),
+            Ok(IpcReply::Mode(KeyboardMode::Static))
+        );
+        assert_eq!(
+            store(&fake).load().unwrap().keyboard.mode,
+            Some(KeyboardMode::Static)
+        );
+
+        daemon
+            .handle(IpcCommand::SetMode(KeyboardMode::Dance))
+            .unwrap();
+        daemon
+            .handle(IpcCommand::SetEffect(Effect::new(EffectKind::Cycle)))
+            .unwrap();
+        assert_eq!(mode(), "0");
+
+        // Animasi firmware menggantikan efek
+        daemon
+            .handle(IpcCommand::SetMode(KeyboardMode::Breathe))
+            .unwrap();
+        assert_eq!(
+            daemon.handle(IpcCommand::GetEffect),
+            Ok(IpcReply::Effect(None))
+        );
+        let saved = store(&fake).load().unwrap().keyboard;
+        assert_eq!(saved.mode, Some(KeyboardMode::Breathe));
+        assert_eq!(saved.effect, None);
+    }
+
+    #[test]
+    fn test_rejects_invalid_effect() {
+        let (_fake, mut daemon) = setup();
+        let effect = Effect {
+            speed: 0,
+            ..Effect::new(EffectKind::Breathe)
+        };
+        assert_eq!(
+            daemon.handle(IpcCommand::SetEffect(effect)),
+            Err(IpcError::InvalidValue)
+        );
+    }
+
+    #[test]
+    fn test_fades_brightness() {
+        let (fake, mut daemon) = setup();
+        daemon.handle(IpcCommand::SetBrightness(0)).unwrap();
+        let events = daemon.subscribe();
+        let transition = Transition::from_millis(1000);
+        daemon
+            .handle(IpcCommand::Fade(FadeTarget::Brightness(200), transition))
+            .unwrap();
+        // Belum ada yang berubah sampai frame pertama dirender
+        assert_eq!(fake.read(path("brightness")).unwrap(), "0");
+
+        let started = daemon.fade.as_ref().unwrap().started;
+        let delay = daemon.render(started + transition.duration / 2).unwrap();
+        assert_eq!(delay, Some(FRAME_INTERVAL));
+        let level: u8 = fake.read(path("brightness")).unwrap().parse().unwrap();
+        assert!(0 < level && level < 200, "{level}");
+        // Frame transisi bukan perubahan dari hotkey
+        daemon.poll_hardware().unwrap();
+        assert!(events.try_recv().is_err());
+
+        assert_eq!(daemon.render(started + transition.duration).unwrap(), None);
+        assert_eq!(fake.read(path("brightness")).unwrap(), "200");
+        assert_eq!(
+            store(&fake).load().unwrap().keyboard.brightness_for(200),
+            Some(200)
+        );
+        assert!(matches!(
+            events.try_recv(),
+            Ok(IpcEvent::BrightnessChanged(info)) if info.current == 200
+        ));
+        assert_eq!(events.try_recv(), Ok(IpcEvent::PowerChanged(true)));
+    }
+
+    #[test]
+    fn test_new_command_cancels_fade() {
+        let (fake, mut daemon) = setup();
+        let transition = Transition::from_millis(1000);
+        daemon
+            .handle(IpcCommand::Fade(
+                FadeTarget::Rgb(Rgb { r: 255, g: 0, b: 0 }),
+                transition,
+            ))
+            .unwrap();
+        let started = daemon.fade.as_ref().unwrap().started;
+        daemon.render(started + transition.duration / 2).unwrap();
+
+        // Query tidak membatalkan transisi
+        daemon.handle(IpcCommand::GetRgb).unwrap();
+        assert!(daemon.fade.is_some());
+
+        daemon.handle(IpcCommand::SetBrightness(10)).unwrap();
+        assert_eq!(daemon.render(started + transition.duration).unwrap(), None);
+        assert_ne!(fake.read(path("multi_intensity")).unwrap(), "200 0 0");
+        assert_eq!(fake.read(path("brightness")).unwrap(), "10");
+    }
+
+    #[test]
+    fn test_fade_to_profile() {
+        let (fake, mut daemon) = setup();
+        daemon.handle(IpcCommand::SetRgb(0, 0, 255)).unwrap();
+        daemon
+            .handle(IpcCommand::SaveProfile("night".to_string()))
+            .unwrap();
+        daemon.handle(IpcCommand::SetRgb(255, 255, 255)).unwrap();
+        let events = daemon.subscribe();
+
+        let transition = Transition::from_millis(100);
+        let target = FadeTarget::Profile("night".to_string());
+        daemon.handle(IpcCommand::Fade(target, transition)).unwrap();
+        let started = daemon.fade.as_ref().unwrap().started;
+        daemon.render(started + transition.duration).unwrap();
+        assert_eq!(fake.read(path("multi_intensity")).unwrap(), "0 0 200");
+        assert!(events
+            .try_iter()
+            .any(|event| event == IpcEvent::ProfileChanged("night".to_string())));
+
+        assert_eq!(
+            daemon.handle(IpcCommand::Fade(
+                FadeTarget::ZoneRgb(3, Rgb { r: 0, g: 0, b: 0 }),
+                transition
+            )),
+            Err(IpcError::InvalidZone(3))
+        );
+    }
+
+    fn enable_idle(daemon: &mut Daemon) {
+        let idle = IdleSettings {
+            dim_after: Some(60),
+            off_after: Some(300),
+            dim_percent: 50,
+        };
+        daemon.handle(IpcCommand::SetIdle(idle)).unwrap();
+    }
+
+    /// Menjalankan transisi yang sedang berjalan sampai selesai.
+    fn finish_fade(daemon: &mut Daemon) {
+        let started = daemon.fade.as_ref().unwrap().started;
+        let end = started + Duration::from_secs(5);
+        assert_eq!(daemon.render(end).unwrap(), None);
+    }
+
+    #[test]
+    fn test_idle_dims_then_turns_off_and_wakes() {
+        let (fake, mut daemon) = setup();
+        enable_idle(&mut daemon);
+        let events = daemon.subscribe();
+        let start = Instant::now();
+        let secs = Duration::from_secs;
+
+        daemon.check_idle(start, start + secs(59)).unwrap();
+        assert!(daemon.fade.is_none());
+
+        daemon.check_idle(start, start + secs(60)).unwrap();
+        finish_fade(&mut daemon);
+        assert_eq!(fake.read(path("brightness")).unwrap(), "50");
+        assert!(matches!(
+            events.try_recv(),
+            Ok(IpcEvent::BrightnessChanged(info)) if info.current == 50
+        ));
+
+        daemon.check_idle(start, start + secs(300)).unwrap();
+        finish_fade(&mut daemon);
+        assert_eq!(fake.read(path("brightness")).unwrap(), "0");
+        // Tidak dianggap perubahan dari hotkey
+        daemon.poll_hardware().unwrap();
+
+        let pressed = start + secs(400);
+        daemon.check_idle(pressed, pressed).unwrap();
+        finish_fade(&mut daemon);
+        assert_eq!(fake.read(path("brightness")).unwrap(), "100");
+
+        // Redup dan mati tidak pernah disimpan
+        let saved = store(&fake).load().unwrap();
+        assert_eq!(saved.keyboard.brightness, None);
+        assert_eq!(saved.idle.dim_after, Some(60));
+    }
+
+    #[test]
+    fn test_command_wakes_from_idle() {
+        let (fake, mut daemon) = setup();
+        enable_idle(&mut daemon);
+        let start = Instant::now();
+        daemon
+            .check_idle(start, start + Duration::from_secs(300))
+            .unwrap();
+        finish_fade(&mut daemon);
+        assert_eq!(fake.read(path("brightness")).unwrap(), "0");
+
+        daemon.handle(IpcCommand::SetRgb(1, 2, 3)).unwrap();
+        assert_eq!(fake.read(path("brightness")).unwrap(), "100");
+        assert!(daemon.fade.is_none());
+    }
+
+    #[test]
+    fn test_idle_leaves_dark_keyboard_alone() {
+        let (fake, mut daemon) = setup();
+        daemon.handle(IpcCommand::SetBrightness(0)).unwrap();
+        enable_idle(&mut daemon);
+        let start = Instant::now();
+        daemon
+            .check_idle(start, start + Duration::from_secs(300))
+            .unwrap();
+        assert!(daemon.fade.is_none());
+        assert_eq!(fake.read(path("brightness")).unwrap(), "0");
+
+        let invalid = IdleSettings {
+            dim_after: Some(10),
+            off_after: Some(5),
+            ..Default::default()
+        };
+        assert_eq!(
+            daemon.handle(IpcCommand::SetIdle(invalid)),
+            Err(IpcError::InvalidValue)
+        );
+    }
+
+    /// Menulis adaptor AC dan baterai palsu.
+    fn set_power(fake: &FakeSysfs, online: bool, capacity: u8) {
+        fake.write("class/power_supply/AC/type", "Mains").unwrap();
+        fake.write(
+            "class/power_supply/AC/online",
+            if online { "1" } else { "0" },
+        )
+        .unwrap();
+        fake.write("class/power_supply/BAT0/type", "Battery")
+            .unwrap();
+        fake.write("class/power_supply/BAT0/capacity", capacity.to_string())
+            .unwrap();
+    }
+
+    #[test]
+    fn test_battery_caps_brightness_and_blocks_effects() {
+        let (fake, mut daemon) = setup();
+        daemon.settings.power.battery = PowerRule {
+            max_brightness: Some(50),
+            effects: false,
+            ..Default::default()
+        };
+        set_power(&fake, true, 90);
+        daemon.poll_power().unwrap();
+        daemon.handle(IpcCommand::SetBrightness(200)).unwrap();
+        assert_eq!(fake.read(path("brightness")).unwrap(), "200");
+
+        set_power(&fake, false, 90);
+        daemon.poll_power().unwrap();
+        assert_eq!(fake.read(path("brightness")).unwrap(), "100");
+        assert!(matches!(
+            daemon.handle(IpcCommand::SetEffect(Effect::new(EffectKind::Cycle))),
+            Err(IpcError::Unsupported(_))
+        ));
+        daemon.handle(IpcCommand::SetBrightness(150)).unwrap();
+        assert_eq!(fake.read(path("brightness")).unwrap(), "100");
+
+        // Pilihan pengguna dikembalikan begitu adaptor dipasang lagi
+        set_power(&fake, true, 90);
+        daemon.poll_power().unwrap();
+        assert_eq!(fake.read(path("brightness")).unwrap(), "150");
+    }
+
+    #[test]
+    fn test_low_battery_overrides_color() {
+        let (fake, mut daemon) = setup();
+        daemon.settings.power.low_battery = Some(LowBattery {
+            below: 20,
+            rule: PowerRule {
+                color: Some(Rgb { r: 255, g: 0, b: 0 }),
+                ..Default::default()
+            },
+        });
+        set_power(&fake, false, 50);
+        daemon.poll_power().unwrap();
+        daemon.handle(IpcCommand::SetRgb(0, 0, 255)).unwrap();
+
+        set_power(&fake, false, 10);
+        daemon.poll_power().unwrap();
+        assert_eq!(fake.read(path("multi_intensity")).unwrap(), "200 0 0");
+        // Warna aturan tidak menggantikan pilihan yang disimpan
+        assert_eq!(
+            daemon.settings.keyboard.rgb,
+            Some(Rgb { r: 0, g: 0, b: 255 })
+        );
+
+        set_power(&fake, true, 10);
+        daemon.poll_power().unwrap();
+        assert_eq!(fake.read(path("multi_intensity")).unwrap(), "0 0 200");
+    }
+
+    #[test]
+    fn test_schedule_applies_due_entries() {
+        let (fake, mut daemon) = setup();
+        daemon.handle(IpcCommand::SetBrightness(50)).unwrap();
+        daemon
+            .handle(IpcCommand::SaveProfile("night".to_string()))
+            .unwrap();
+        daemon.handle(IpcCommand::SetBrightness(200)).unwrap();
+
+        let entry = |at: &str, action| ScheduleEntry {
+            at: at.parse().unwrap(),
+            action,
+        };
+        let unknown = Schedule {
+            location: None,
+            entries: vec![entry("22:00", ScheduleAction::Profile("x".to_string()))],
+        };
+        assert_eq!(
+            daemon.handle(IpcCommand::SetSchedule(unknown)),
+            Err(IpcError::UnknownProfile("x".to_string()))
+        );
+        let schedule = Schedule {
+            location: None,
+            entries: vec![
+                entry("22:00", ScheduleAction::Profile("night".to_string())),
+                entry("07:00", ScheduleAction::Brightness(150)),
+            ],
+        };
+        daemon
+            .handle(IpcCommand::SetSchedule(schedule.clone()))
+            .unwrap();
+        assert_eq!(
+            daemon.handle(IpcCommand::GetSchedule),
+            Ok(IpcReply::Schedule(schedule))
+        );
+
+        let day = chrono::NaiveDate::from_ymd_opt(2024, 5, 1).unwrap();
+        let at = |time: &str| day.and_time(time.parse().unwrap());
+        // Pemeriksaan pertama menerapkan entry yang sedang berlaku
+        daemon.check_schedule(at("12:00:00"), 0).unwrap();
+        finish_fade(&mut daemon);
+        assert_eq!(fake.read(path("brightness")).unwrap(), "150");
+
+        daemon.check_schedule(at("21:59:00"), 0).unwrap();
+        assert!(daemon.fade.is_none());
+        daemon.check_schedule(at("22:00:00"), 0).unwrap();
+        finish_fade(&mut daemon);
+        assert_eq!(fake.read(path("brightness")).unwrap(), "50");
+        assert_eq!(
+            daemon
+                .settings
+                .keyboard
+                .brightness
+                .as_ref()
+                .map(|info| info.current),
+            Some(50)
+        );
+    }
+
+    #[test]
+    fn test_calibration_applies_to_writes() {
+        let (fake, mut daemon) = setup();
+        daemon.handle(IpcCommand::SetRgb(255, 255, 255)).unwrap();
+        let calibration = Calibration {
+            white_point: Rgb::new(255, 255, 0),
+            ..Default::default()
+        };
+        daemon
+            .handle(IpcCommand::SetCalibration(calibration))
+            .unwrap();
+        // Warna tersimpan langsung diterapkan ulang lewat kalibrasi baru
+        assert_eq!(fake.read(path("multi_intensity")).unwrap(), "200 200 0");
+        assert_eq!(
+            daemon.handle(IpcCommand::GetRgb),
+            Ok(IpcReply::Rgb(Rgb::new(255, 255, 255)))
+        );
+        assert_eq!(
+            daemon.handle(IpcCommand::GetCalibration),
+            Ok(IpcReply::Calibration(calibration))
+        );
+        assert_eq!(store(&fake).load().unwrap().calibration, calibration);
+
+        // Pratinjau tidak menyimpan apa pun; restore mengembalikan warnanya
+        let preview = Calibration {
+            gamma: 2.0,
+            ..Default::default()
+        };
+        daemon
+            .handle(IpcCommand::PreviewCalibration(
+                preview,
+                Rgb::new(255, 128, 0),
+            ))
+            .unwrap();
+        assert_eq!(fake.read(path("multi_intensity")).unwrap(), "200 50 0");
+        daemon.handle(IpcCommand::Restore).unwrap();
+        assert_eq!(fake.read(path("multi_intensity")).unwrap(), "200 200 0");
+        assert_eq!(
+            daemon.handle(IpcCommand::GetCalibration),
+            Ok(IpcReply::Calibration(calibration))
+        );
+
+        let invalid = Calibration {
+            gamma: 0.0,
+            ..Default::default()
+        };
+        assert_eq!(
+            daemon.handle(IpcCommand::SetCalibration(invalid)),
+            Err(IpcError::InvalidValue)
+        );
+    }
+
+    #[test]
+    fn test_fan_curve_without_keyboard() {
+        let fake = FakeSysfs::new().unwrap();
+        let mut daemon = Daemon::new(fake.root(), store(&fake));
+        assert_eq!(
+            daemon.handle(IpcCommand::Fan(FanCommand::GetInfo)),
+            Err(IpcError::Unsupported(
+                "fan control (is tuxedo_io loaded?)".to_string()
+            ))
+        );
+
+        let ioctl = FakeIoctl::uniwill();
+        ioctl.set(request::R_UW_FAN_TEMP, 70);
+        daemon.set_fans(Box::new(TuxedoIo::with_device(ioctl.clone()).unwrap()));
+        // Kipas tersisa manual dari daemon sebelumnya dikembalikan ke firmware
+        daemon.update_fans().unwrap();
+        assert_eq!(ioctl.get(request::W_UW_FANAUTO), Some(0));
+
+        let settings = FanSettings {
+            mode: FanMode::Curve,
+            ..Default::default()
+        };
+        daemon
+            .handle(IpcCommand::Fan(FanCommand::SetSettings(settings.clone())))
+            .unwrap();
+        // Kurva default: 70 °C → 50%, langsung diterapkan
+        assert_eq!(ioctl.get(request::W_UW_FANSPEED), Some(100));
+        assert_eq!(store(&fake).load().unwrap().fan, settings);
+        assert_eq!(
+            daemon.handle(IpcCommand::Fan(FanCommand::GetInfo)),
+            Ok(IpcReply::Fans(vec![
+                FanInfo {
+                    fan: Fan::Cpu,
+                    speed: 0,
+                    temperature: 70,
+                },
+                FanInfo {
+                    fan: Fan::Gpu,
+                    speed: 0,
+                    temperature: 0,
+                },
+            ]))
+        );
+        // Perintah keyboard tetap membutuhkan keyboard
+        assert_eq!(daemon.handle(IpcCommand::GetRgb), Err(IpcError::NoKeyboard));
+
+        ioctl.clear(request::W_UW_FANAUTO);
+        daemon.release_fans().unwrap();
+        assert_eq!(ioctl.get(request::W_UW_FANAUTO), Some(0));
+    }
+
+    #[test]
+    fn test_power_profiles_without_keyboard() {
+        let fake = FakeSysfs::new().unwrap();
+        let mut daemon = Daemon::new(fake.root(), store(&fake));
+        let get = IpcCommand::PowerProfile(PowerProfileCommand::Get);
+        assert!(matches!(
+            daemon.handle(get.clone()),
+            Err(IpcError::Unsupported(_))
+        ));
+
+        let ioctl = FakeIoctl::clevo();
+        let io = TuxedoIo::with_device(ioctl.clone()).unwrap();
+        daemon.set_power_profiles(Box::new(io)).unwrap();
+        // Belum ada yang ditulis, dan tuxedo_io tidak bisa dibaca
+        assert!(matches!(
+            daemon.handle(get.clone()),
+            Err(IpcError::Unsupported(_))
+        ));
+
+        let events = daemon.subscribe();
+        let set = PowerProfileCommand::Set(PowerProfile::Quiet);
+        daemon.handle(IpcCommand::PowerProfile(set)).unwrap();
+        assert_eq!(ioctl.get(request::W_CL_PERF_PROFILE), Some(0x00));
+        assert_eq!(
+            events.try_recv(),
+            Ok(IpcEvent::PowerProfileChanged(PowerProfile::Quiet))
+        );
+        assert_eq!(
+            store(&fake).load().unwrap().power_profile,
+            Some(PowerProfile::Quiet)
+        );
+
+        // Daemon baru menerapkan ulang profil tersimpan
+        let mut daemon = Daemon::new(fake.root(), store(&fake));
+        let ioctl = FakeIoctl::clevo();
+        let io = TuxedoIo::with_device(ioctl.clone()).unwrap();
+        daemon.set_power_profiles(Box::new(io)).unwrap();
+        assert_eq!(ioctl.get(request::W_CL_PERF_PROFILE), Some(0x00));
+        assert_eq!(
+            daemon.handle(get),
+            Ok(IpcReply::PowerProfile(PowerProfile::Quiet))
+        );
+    }
+
+    #[test]
+    fn test_charge_thresholds_are_restored() {
+        let fake = FakeSysfs::new().unwrap();
+        let battery = "class/power_supply/BAT0";
+        for (attr, value) in [
+            ("type", "Battery"),
+            ("charge_control_start_threshold", "0"),
+            ("charge_control_end_threshold", "100"),
+        ] {
+            fake.write(format!("{battery}/{attr}"), value).unwrap();
+        }
+        let charge = || m4arch_service::get_charge_at(fake.root()).unwrap();
+        let mut daemon = Daemon::new(fake.root(), store(&fake));
+        daemon.set_charge(charge()).unwrap();
+
+        let stationary = ChargePreset::Stationary.thresholds();
+        daemon
+            .handle(IpcCommand::Charge(ChargeCommand::Set(stationary)))
+            .unwrap();
+        assert_eq!(
+            daemon.handle(IpcCommand::Charge(ChargeCommand::Get)),
+            Ok(IpcReply::ChargeThresholds(stationary))
+        );
+        assert_eq!(
+            daemon.handle(IpcCommand::Charge(ChargeCommand::Set(
+                ChargeThresholds::new(80, 60)
+            ))),
+            Err(IpcError::InvalidValue)
+        );
+
+        // Firmware kembali mengisi penuh setelah reboot
+        fake.write(format!("{battery}/charge_control_start_threshold"), "0")
+            .unwrap();
+        fake.write(format!("{battery}/charge_control_end_threshold"), "100")
+            .unwrap();
+        let mut daemon = Daemon::new(fake.root(), store(&fake));
+        daemon.set_charge(charge()).unwrap();
+        let end = fake
+            .read(format!("{battery}/charge_control_end_threshold"))
+            .unwrap();
+        assert_eq!(end, "80");
+    }
+
+    #[test]
+    fn test_batteries_without_keyboard() {
+        let fake = FakeSysfs::new().unwrap();
+        let daemon_batteries = |daemon: &mut Daemon| match daemon
+            .handle(IpcCommand::Charge(ChargeCommand::Batteries))
+        {
+            Ok(IpcReply::Batteries(batteries)) => batteries,
+            reply => panic!("unexpected reply: {:?}", reply),
+        };
+        let mut daemon = Daemon::new(fake.root(), store(&fake));
+        assert!(daemon_batteries(&mut daemon).is_empty());
+
+        for (attr, value) in [
+            ("type", "Battery"),
+            ("status", "Discharging"),
+            ("capacity", "64"),
+        ] {
+            fake.write(format!("class/power_supply/BAT0/{attr}"), value)
+                .unwrap();
+        }
+        let batteries = daemon_batteries(&mut daemon);
+        assert_eq!(batteries.len(), 1);
+        assert_eq!(batteries[0].capacity, Some(64));
+    }
+
+    #[test]
+    fn test_cpu_tuning_until_profile_changes() {
+        let fake = FakeSysfs::new().unwrap();
+        let policy = format!("{}/cpufreq/policy0", cpu::CPU_DIR);
+        for (attr, value) in [
+            ("scaling_driver", "intel_pstate"),
+            ("scaling_governor", "powersave"),
+            ("scaling_available_governors", "performance powersave"),
+            ("energy_performance_preference", "balance_performance"),
+            (
+                "energy_performance_available_preferences",
+                "performance balance_performance balance_power power",
+            ),
+        ] {
+            fake.write(format!("{policy}/{attr}"), value).unwrap();
+        }
+        let mut daemon = Daemon::new(fake.root(), store(&fake));
+        daemon.set_cpu(Box::new(cpu::SysfsCpu::with_root(fake.root())));
+        let profiles = cpu::CpuTunedProfiles::new(None, cpu::SysfsCpu::with_root(fake.root()));
+        daemon.set_power_profiles(Box::new(profiles)).unwrap();
+
+        let tuning = CpuTuning {
+            energy_preference: Some("balance_power".to_string()),
+            ..Default::default()
+        };
+        daemon
+            .handle(IpcCommand::Cpu(CpuCommand::Tune(tuning.clone())))
+            .unwrap();
+        let epp = format!("{policy}/energy_performance_preference");
+        assert_eq!(fake.read(&epp).unwrap(), "balance_power");
+        assert_eq!(store(&fake).load().unwrap().cpu, tuning);
+        let invalid = CpuTuning {
+            governor: Some("ondemand".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            daemon.handle(IpcCommand::Cpu(CpuCommand::Tune(invalid))),
+            Err(IpcError::Unsupported(_))
+        ));
+
+        // Tuning manual diterapkan ulang setelah resume
+        fake.write(&epp, "performance").unwrap();
+        daemon.restore_power_profile().unwrap();
+        assert_eq!(fake.read(&epp).unwrap(), "balance_power");
+
+        // Profil membawa tuning-nya sendiri dan membuang tuning manual
+        let set = PowerProfileCommand::Set(PowerProfile::Quiet);
+        daemon.handle(IpcCommand::PowerProfile(set)).unwrap();
+        assert_eq!(fake.read(&epp).unwrap(), "power");
+        assert!(store(&fake).load().unwrap().cpu.is_empty());
+        let status = daemon.handle(IpcCommand::Cpu(CpuCommand::Status));
+        assert!(matches!(status, Ok(IpcReply::CpuStatus(status)) if status.turbo.is_none()));
+    }
+}
@@ -0,0 +1,47 @@
+//! Penyiaran `IpcEvent` ke semua front-end yang berlangganan.
+use m4arch_ipc::protocol::IpcEvent;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Mutex;
+
+#[derive(Default)]
+pub struct EventBus {
+    subscribers: Mutex<Vec<Sender<IpcEvent>>>,
+}
+
+impl EventBus {
+    /// Mendaftarkan pelanggan baru; event dikirim lewat receiver ini.
+    pub fn subscribe(&self) -> Receiver<IpcEvent> {
+        let (sender, receiver) = mpsc::channel();
+        self.lock().push(sender);
+        receiver
+    }
+
+    /// Mengirim event ke semua pelanggan; pelanggan yang sudah putus dibuang.
+    pub fn publish(&self, event: IpcEvent) {
+        self.lock()
+            .retain(|subscriber| subscriber.send(event.clone()).is_ok());
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<Sender<IpcEvent>>> {
+        self.subscribers
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_publishes_to_live_subscribers() {
+        let bus = EventBus::default();
+        let first = bus.subscribe();
+        let second = bus.subscribe();
+        drop(second);
+
+        bus.publish(IpcEvent::PowerChanged(true));
+        assert_eq!(first.try_recv(), Ok(IpcEvent::PowerChanged(true)));
+        assert_eq!(bus.lock().len(), 1);
+    }
+}
@@ -0,0 +1,208 @@
+//! Kurva kipas yang dijalankan daemon.
+//!
+//! Setiap pembaruan membaca suhu tiap kipas dan memilih duty dari kurvanya.
+//! Duty langsung naik saat suhu naik, tetapi baru turun setelah suhu turun
+//! sebesar `hysteresis` dari suhu saat duty terakhir dipilih, agar kipas
+//! tidak naik-turun mengikuti fluktuasi suhu yang kecil.
+//!
+//! Jika suhu gagal dibaca, kipas dikembalikan ke firmware: lebih baik
+//! firmware yang mengatur daripada kipas tertahan pelan.
+use m4arch_core::config::{FanMode, FanSettings};
+use m4arch_core::error::Result;
+use m4arch_core::fan::{Fan, FanInfo};
+use m4arch_service::FanController;
+use std::collections::BTreeMap;
+
+/// Duty yang terakhir ditulis ke satu kipas.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Applied {
+    duty: u8,
+    /// Suhu tertinggi selama duty ini berlaku
+    temperature: u8,
+}
+
+pub struct FanCurveController {
+    fans: Box<dyn FanController>,
+    applied: BTreeMap<Fan, Applied>,
+    /// Kipas mungkin sedang diatur manual. Benar saat start, karena daemon
+    /// sebelumnya bisa berhenti tanpa sempat mengembalikan kipas ke firmware.
+    manual: bool,
+}
+
+impl FanCurveController {
+    pub fn new(fans: Box<dyn FanController>) -> Self {
+        Self {
+            fans,
+            applied: BTreeMap::new(),
+            manual: true,
+        }
+    }
+
+    pub fn fan_info(&self) -> Result<Vec<FanInfo>> {
+        self.fans
+            .fans()
+            .into_iter()
+            .map(|fan| self.fans.fan_info(fan))
+            .collect()
+    }
+
+    /// Menjalankan satu langkah kontrol sesuai `settings`.
+    pub fn update(&mut self, settings: &FanSettings) -> Result<()> {
+        if settings.mode == FanMode::Auto {
+            return self.release();
+        }
+        let result = self.follow_curve(settings);
+        if result.is_err() {
+            // Error aslinya yang dilaporkan
+            let _ = self.release();
+        }
+        result
+    }
+
+    fn follow_curve(&mut self, settings: &FanSettings) -> Result<()> {
+        for fan in self.fans.fans() {
+            let temperature = self.fans.get_temperature(fan)?;
+            let target = settings.curve(fan).duty_at(temperature);
+            let previous = self.applied.get(&fan).copied();
+            let Some(next) = next_duty(previous, temperature, target, settings.hysteresis) else {
+                continue;
+            };
+            if previous.map(|applied| applied.duty) != Some(next.duty) {
+                self.manual = true;
+                self.fans.set_fan_duty(fan, next.duty)?;
+            }
+            self.applied.insert(fan, next);
+        }
+        Ok(())
+    }
+
+    /// Mengembalikan kipas ke firmware jika sedang diatur manual.
+    pub fn release(&mut self) -> Result<()> {
+        self.applied.clear();
+        if !self.manual {
+            return Ok(());
+        }
+        self.fans.set_fans_auto()?;
+        self.manual = false;
+        Ok(())
+    }
+
+    /// Melupakan duty yang sudah ditulis, sehingga pembaruan berikutnya
+    /// langsung menulis duty sesuai kurva (misalnya setelah kurva diganti,
+    /// atau setelah resume ketika EC kembali ke mode otomatis).
+    pub fn reset(&mut self) {
+        self.applied.clear();
+        self.manual = true;
+    }
+}
+
+/// Duty yang harus berlaku untuk suhu `temperature`, atau `None` jika
+/// tidak ada yang berubah.
+fn next_duty(
+    previous: Option<Applied>,
+    temperature: u8,
+    target: u8,
+    hysteresis: u8,
+) -> Option<Applied> {
+    let next = Applied {
+        duty: target,
+        temperature,
+    };
+    let Some(previous) = previous else {
+        return Some(next);
+    };
+    if target > previous.duty {
+        return Some(next);
+    }
+    if target == previous.duty {
+        // Penurunan suhu dihitung dari puncaknya
+        return (temperature > previous.temperature).then_some(next);
+    }
+    (temperature.saturating_add(hysteresis) <= previous.temperature).then_some(next)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use m4arch_core::fan::{CurvePoint, FanCurve};
+    use m4arch_service::tuxedo_io::fake::FakeIoctl;
+    use m4arch_service::tuxedo_io::{request, TuxedoIo};
+
+    fn settings() -> FanSettings {
+        let curve = FanCurve::new(vec![CurvePoint::new(40, 20), CurvePoint::new(80, 100)]);
+        FanSettings {
+            mode: FanMode::Curve,
+            cpu: curve.clone(),
+            gpu: curve,
+            hysteresis: 4,
+        }
+    }
+
+    /// Controller di atas EC Uniwill palsu; duty 100% = 200 mentah.
+    fn setup() -> (FakeIoctl, FanCurveController) {
+        let fake = FakeIoctl::uniwill();
+        let fans = TuxedoIo::with_device(fake.clone()).unwrap();
+        (fake, FanCurveController::new(Box::new(fans)))
+    }
+
+    #[test]
+    fn test_hysteresis() {
+        let applied = |duty, temperature| Applied { duty, temperature };
+        assert_eq!(next_duty(None, 50, 40, 4), Some(applied(40, 50)));
+        // Naik langsung
+        assert_eq!(
+            next_duty(Some(applied(40, 50)), 51, 42, 4),
+            Some(applied(42, 51))
+        );
+        // Turun sedikit: ditahan
+        assert_eq!(next_duty(Some(applied(40, 50)), 47, 34, 4), None);
+        // Turun sebesar histeresis
+        assert_eq!(
+            next_duty(Some(applied(40, 50)), 46, 32, 4),
+            Some(applied(32, 46))
+        );
+        // Duty sama di suhu lebih tinggi menggeser titik acuan
+        assert_eq!(
+            next_duty(Some(applied(40, 50)), 52, 40, 4),
+            Some(applied(40, 52))
+        );
+        assert_eq!(next_duty(Some(applied(40, 50)), 48, 40, 4), None);
+    }
+
+    #[test]
+    fn test_follows_curve() {
+        let (fake, mut controller) = setup();
+        let settings = settings();
+        fake.set(request::R_UW_FAN_TEMP, 60);
+        fake.set(request::R_UW_FAN_TEMP2, 30);
+        controller.update(&settings).unwrap();
+        // CPU 60 °C → 60%, GPU di bawah kurva → 20%
+        assert_eq!(fake.get(request::W_UW_FANSPEED), Some(120));
+        assert_eq!(fake.get(request::W_UW_FANSPEED2), Some(40));
+
+        // Suhu turun 2 °C: duty ditahan
+        fake.set(request::R_UW_FAN_TEMP, 58);
+        controller.update(&settings).unwrap();
+        assert_eq!(fake.get(request::W_UW_FANSPEED), Some(120));
+        fake.set(request::R_UW_FAN_TEMP, 56);
+        controller.update(&settings).unwrap();
+        assert_eq!(fake.get(request::W_UW_FANSPEED), Some(104));
+
+        // Kembali ke firmware
+        let auto = FanSettings {
+            mode: FanMode::Auto,
+            ..settings
+        };
+        controller.update(&auto).unwrap();
+        assert_eq!(fake.get(request::W_UW_FANAUTO), Some(0));
+    }
+
+    #[test]
+    fn test_read_error_restores_auto() {
+        let (fake, mut controller) = setup();
+        controller.update(&settings()).unwrap();
+        fake.clear(request::R_UW_FAN_TEMP);
+        assert!(controller.update(&settings()).is_err());
+        assert_eq!(fake.get(request::W_UW_FANAUTO), Some(0));
+    }
+}
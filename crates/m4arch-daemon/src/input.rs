@@ -0,0 +1,161 @@
+//! Aktivitas pengguna dari perangkat input evdev.
+//!
+//! Daemon tidak perlu tahu tombol apa yang ditekan, jadi event tidak di-parse:
+//! setiap data yang terbaca dari `/dev/input/eventN` dihitung sebagai
+//! aktivitas. Hanya perangkat dengan `EV_KEY` (keyboard, touchpad, tombol)
+//! yang dibaca, agar sensor seperti akselerometer tidak dianggap aktivitas.
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread;
+use std::time::Instant;
+
+/// Lokasi node perangkat input di sistem sungguhan.
+pub const DEV_DIR: &str = "/dev/input";
+
+/// Direktori perangkat input, relatif terhadap root sysfs.
+const INPUT_DIR: &str = "class/input";
+
+/// Bit `EV_KEY` pada `capabilities/ev`.
+const EV_KEY: u64 = 1 << 1;
+
+/// Sumber waktu aktivitas terakhir pengguna; bisa diganti saat pengujian.
+pub trait ActivitySource: Send + Sync {
+    /// Waktu aktivitas terakhir, atau `None` jika aktivitas tidak bisa
+    /// dipantau (misalnya tidak ada perangkat yang bisa dibaca).
+    fn last_activity(&self) -> Option<Instant>;
+
+    /// Mencari perangkat input yang baru muncul.
+    fn rescan(&self) {}
+}
+
+/// Memantau semua perangkat evdev dengan satu thread pembaca per perangkat.
+pub struct EvdevActivity {
+    sysfs_root: PathBuf,
+    dev_dir: PathBuf,
+    last: Arc<Mutex<Instant>>,
+    /// Nama perangkat (`eventN`) yang sedang dibaca
+    reading: Arc<Mutex<HashSet<String>>>,
+}
+
+impl EvdevActivity {
+    /// Daemon yang baru dimulai dianggap sebagai aktivitas.
+    pub fn new(sysfs_root: impl Into<PathBuf>, dev_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            sysfs_root: sysfs_root.into(),
+            dev_dir: dev_dir.into(),
+            last: Arc::new(Mutex::new(Instant::now())),
+            reading: Arc::default(),
+        }
+    }
+
+    /// Mulai membaca perangkat yang belum dibaca; mengembalikan jumlahnya.
+    pub fn scan(&self) -> usize {
+        let Ok(entries) = fs::read_dir(self.sysfs_root.join(INPUT_DIR)) else {
+            return 0;
+        };
+        let mut started = 0;
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if !name.starts_with("event") || !has_key_events(&entry.path()) {
+                continue;
+            }
+            if locked(&self.reading).contains(&name) {
+                continue;
+            }
+            // Tanpa izin baca (daemon bukan root) perangkat dilewati
+            let Ok(device) = File::open(self.dev_dir.join(&name)) else {
+                continue;
+            };
+            locked(&self.reading).insert(name.clone());
+            let last = Arc::clone(&self.last);
+            let reading = Arc::clone(&self.reading);
+            thread::spawn(move || {
+                read_events(device, &last);
+                locked(&reading).remove(&name);
+            });
+            started += 1;
+        }
+        started
+    }
+}
+
+impl ActivitySource for EvdevActivity {
+    fn last_activity(&self) -> Option<Instant> {
+        if locked(&self.reading).is_empty() {
+            return None;
+        }
+        Some(*locked(&self.last))
+    }
+
+    fn rescan(&self) {
+        self.scan();
+    }
+}
+
+/// Membaca event sampai perangkat dilepas.
+fn read_events(mut device: File, last: &Mutex<Instant>) {
+    // Beberapa `struct input_event` sekaligus
+    let mut buffer = [0u8; 24 * 16];
+    while let Ok(read) = device.read(&mut buffer) {
+        if read == 0 {
+            break;
+        }
+        *locked(last) = Instant::now();
+    }
+}
+
+/// Apakah perangkat `class/input/eventN` melaporkan `EV_KEY`.
+fn has_key_events(event_dir: &Path) -> bool {
+    let Ok(content) = fs::read_to_string(event_dir.join("device/capabilities/ev")) else {
+        return false;
+    };
+    // Bitmask heksadesimal; word terakhir berisi bit terendah
+    content
+        .split_whitespace()
+        .last()
+        .and_then(|word| u64::from_str_radix(word, 16).ok())
+        .is_some_and(|bits| bits & EV_KEY != 0)
+}
+
+fn locked<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use m4arch_service::sysfs::fake::FakeSysfs;
+    use std::time::Duration;
+
+    #[test]
+    fn test_reads_key_devices_only() {
+        let fake = FakeSysfs::new().unwrap();
+        // Keyboard (EV_SYN, EV_KEY, EV_MSC, EV_LED, EV_REP) dan akselerometer (EV_ABS)
+        fake.write("class/input/event3/device/capabilities/ev", "120013\n")
+            .unwrap();
+        fake.write("class/input/event4/device/capabilities/ev", "9\n")
+            .unwrap();
+        fake.write("class/input/mouse0/device/capabilities/ev", "17\n")
+            .unwrap();
+        for name in ["event3", "event4", "mouse0"] {
+            fake.write(format!("dev/input/{name}"), [0u8; 24]).unwrap();
+        }
+
+        let activity = EvdevActivity::new(fake.root(), fake.path("dev/input"));
+        let before = *locked(&activity.last);
+        assert_eq!(activity.scan(), 1);
+
+        // File biasa berakhir setelah satu event, jadi thread-nya selesai
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !locked(&activity.reading).is_empty() {
+            assert!(Instant::now() < deadline, "reader did not finish");
+            thread::sleep(Duration::from_millis(5));
+        }
+        assert!(*locked(&activity.last) > before);
+        // Tidak ada lagi perangkat yang dibaca
+        assert_eq!(activity.last_activity(), None);
+    }
+}
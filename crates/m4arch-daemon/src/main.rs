@@ -1,3 +1,104 @@
+mod animate;
+mod daemon;
+mod events;
+mod fan;
+mod input;
+mod peer;
+mod server;
+mod signal;
+mod watch;
+
+use daemon::Daemon;
+use m4arch_core::config::SettingsStore;
+use std::process;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
 fn main() {
-    println!("m4arch daemon placeholder");
+    // Sebelum thread apa pun dibuat, agar semuanya mewarisi mask sinyal
+    let termination = match signal::Termination::block() {
+        Ok(termination) => termination,
+        Err(e) => {
+            eprintln!("m4arch-daemon: cannot block termination signals: {}", e);
+            process::exit(1);
+        }
+    };
+
+    // Daemon adalah satu-satunya proses yang menyentuh sysfs keyboard.
+    // Tetap berjalan meski keyboard belum ada; driver bisa dimuat belakangan.
+    let mut daemon = Daemon::new(m4arch_service::sysfs::DEFAULT_ROOT, SettingsStore::system());
+    if !daemon.has_keyboard() {
+        eprintln!("m4arch-daemon: no supported keyboard driver found yet, waiting");
+    } else if let Err(e) = daemon.restore() {
+        eprintln!("m4arch-daemon: cannot restore keyboard state: {}", e);
+    }
+    match m4arch_service::get_fans() {
+        Ok(fans) => daemon.set_fans(fans),
+        Err(e) => eprintln!("m4arch-daemon: fan control is inactive: {}", e),
+    }
+    match m4arch_service::get_charge() {
+        Ok(charge) => {
+            if let Err(e) = daemon.set_charge(charge) {
+                eprintln!("m4arch-daemon: cannot restore charge thresholds: {}", e);
+            }
+        }
+        Err(e) => eprintln!("m4arch-daemon: charge thresholds are inactive: {}", e),
+    }
+    match m4arch_service::get_cpu() {
+        Ok(cpu) => daemon.set_cpu(cpu),
+        Err(e) => eprintln!("m4arch-daemon: CPU tuning is inactive: {}", e),
+    }
+    match m4arch_service::get_power_profiles() {
+        Ok(profiles) => {
+            if let Err(e) = daemon.set_power_profiles(profiles) {
+                eprintln!("m4arch-daemon: cannot restore power profile: {}", e);
+            }
+        }
+        Err(e) => eprintln!("m4arch-daemon: power profiles are inactive: {}", e),
+    }
+    let daemon = Arc::new(Mutex::new(daemon));
+
+    let path = m4arch_ipc::socket_path();
+    let listener = match server::bind(&path) {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("m4arch-daemon: cannot bind {}: {}", path.display(), e);
+            process::exit(1);
+        }
+    };
+
+    let activity = input::EvdevActivity::new(m4arch_service::sysfs::DEFAULT_ROOT, input::DEV_DIR);
+    if activity.scan() == 0 {
+        eprintln!("m4arch-daemon: no readable input devices, idle timeout is inactive");
+    }
+    let monitor = Arc::clone(&daemon);
+    thread::spawn(move || watch::run(&monitor, &activity));
+    let animator = Arc::clone(&daemon);
+    thread::spawn(move || animate::run(&animator));
+    let stopping = Arc::clone(&daemon);
+    thread::spawn(move || {
+        if let Err(e) = termination.wait() {
+            eprintln!("m4arch-daemon: cannot wait for termination signals: {}", e);
+            return;
+        }
+        // Kipas tidak boleh tertahan pada duty kurva setelah daemon berhenti
+        if let Err(e) = server::lock(&stopping).release_fans() {
+            eprintln!(
+                "m4arch-daemon: cannot return fans to automatic control: {}",
+                e
+            );
+        }
+        process::exit(0);
+    });
+
+    let access = peer::Access::system();
+    if access.admin_gid().is_none() {
+        eprintln!(
+            "m4arch-daemon: group '{}' not found, only root may change fan, power, CPU or charge settings",
+            peer::ADMIN_GROUP
+        );
+    }
+
+    eprintln!("m4arch-daemon: listening on {}", path.display());
+    server::serve(listener, daemon, access);
 }
@@ -0,0 +1,163 @@
+//! Identitas proses di ujung lain socket dan siapa yang boleh mengubah
+//! hardware platform.
+//!
+//! Socket daemon bisa ditulis semua pengguna agar pencahayaan keyboard bisa
+//! diatur tanpa root. Perintah yang menyentuh kipas, daya atau baterai
+//! (`IpcCommand::requires_privilege`) hanya diterima dari root atau anggota
+//! grup `ADMIN_GROUP`.
+use std::ffi::CString;
+use std::fs;
+use std::io;
+use std::mem::{self, MaybeUninit};
+use std::os::fd::AsRawFd;
+use std::os::unix::net::UnixStream;
+use std::ptr;
+
+/// Grup yang anggotanya boleh mengirim perintah yang butuh hak istimewa.
+pub const ADMIN_GROUP: &str = "m4arch";
+
+/// Kredensial peer saat tersambung (`SO_PEERCRED`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Peer {
+    pub pid: i32,
+    pub uid: u32,
+    pub gid: u32,
+}
+
+impl Peer {
+    pub fn of(stream: &UnixStream) -> io::Result<Self> {
+        let mut credentials = MaybeUninit::<libc::ucred>::uninit();
+        let mut length = mem::size_of::<libc::ucred>() as libc::socklen_t;
+        // SAFETY: buffer dan panjangnya sesuai dengan `ucred`
+        let result = unsafe {
+            libc::getsockopt(
+                stream.as_raw_fd(),
+                libc::SOL_SOCKET,
+                libc::SO_PEERCRED,
+                credentials.as_mut_ptr().cast(),
+                &mut length,
+            )
+        };
+        if result != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        // SAFETY: `getsockopt` berhasil mengisi seluruh struct
+        let credentials = unsafe { credentials.assume_init() };
+        Ok(Self {
+            pid: credentials.pid,
+            uid: credentials.uid,
+            gid: credentials.gid,
+        })
+    }
+
+    /// Grup tambahan proses peer dari `/proc/<pid>/status`; kosong jika
+    /// proses sudah tidak ada.
+    fn supplementary_groups(&self) -> Vec<u32> {
+        let status = fs::read_to_string(format!("/proc/{}/status", self.pid)).unwrap_or_default();
+        status
+            .lines()
+            .find_map(|line| line.strip_prefix("Groups:"))
+            .map(|groups| {
+                groups
+                    .split_whitespace()
+                    .filter_map(|gid| gid.parse().ok())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// Aturan akses untuk perintah yang butuh hak istimewa.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Access {
+    /// GID `ADMIN_GROUP`; `None` jika grup tidak ada sehingga hanya root
+    /// yang diizinkan
+    admin_gid: Option<u32>,
+}
+
+impl Access {
+    pub fn new(admin_gid: Option<u32>) -> Self {
+        Self { admin_gid }
+    }
+
+    /// Aturan dengan grup `ADMIN_GROUP` milik sistem.
+    pub fn system() -> Self {
+        Self::new(group_id(ADMIN_GROUP))
+    }
+
+    pub fn admin_gid(&self) -> Option<u32> {
+        self.admin_gid
+    }
+
+    pub fn allows(&self, peer: &Peer) -> bool {
+        if peer.uid == 0 {
+            return true;
+        }
+        let Some(admin_gid) = self.admin_gid else {
+            return false;
+        };
+        peer.gid == admin_gid || peer.supplementary_groups().contains(&admin_gid)
+    }
+}
+
+/// GID grup bernama `name`, jika ada.
+fn group_id(name: &str) -> Option<u32> {
+    let name = CString::new(name).ok()?;
+    let mut group = MaybeUninit::<libc::group>::uninit();
+    let mut buffer = vec![0 as libc::c_char; 16 * 1024];
+    let mut found = ptr::null_mut();
+    // SAFETY: semua pointer valid dan `buffer` hidup selama panggilan;
+    // `found` hanya menunjuk ke `group` bila berhasil
+    let result = unsafe {
+        libc::getgrnam_r(
+            name.as_ptr(),
+            group.as_mut_ptr(),
+            buffer.as_mut_ptr(),
+            buffer.len(),
+            &mut found,
+        )
+    };
+    if result != 0 || found.is_null() {
+        return None;
+    }
+    // SAFETY: `getgrnam_r` berhasil mengisi `group`
+    Some(unsafe { group.assume_init() }.gr_gid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// PID yang tidak mungkin ada, agar grup tambahan kosong.
+    const GONE: i32 = i32::MAX;
+
+    #[test]
+    fn test_access() {
+        let user = Peer {
+            pid: GONE,
+            uid: 1000,
+            gid: 1000,
+        };
+        let root = Peer { uid: 0, ..user };
+        let admin = Peer { gid: 970, ..user };
+
+        let root_only = Access::default();
+        assert!(root_only.allows(&root));
+        assert!(!root_only.allows(&user));
+        assert!(!root_only.allows(&admin));
+
+        let group = Access::new(Some(970));
+        assert!(group.allows(&root));
+        assert!(group.allows(&admin));
+        assert!(!group.allows(&user));
+    }
+
+    #[test]
+    fn test_peer_of_own_socket() {
+        let (client, _server) = UnixStream::pair().unwrap();
+        let peer = Peer::of(&client).unwrap();
+        assert_eq!(peer.pid, std::process::id() as i32);
+        // SAFETY: `getuid` tidak punya prasyarat
+        assert_eq!(peer.uid, unsafe { libc::getuid() });
+    }
+}
@@ -0,0 +1,276 @@
+//! Server socket Unix yang menerima `Request` dari front-end.
+//!
+//! Setiap koneksi dilayani di thread terpisah; akses ke hardware
+//! diserialisasi lewat `Mutex<Daemon>`. Pesan dikirim dengan
+//! `m4arch_ipc::codec` dan koneksi harus diawali handshake `Hello`.
+//!
+//! Koneksi yang mengirim `Subscribe` beralih menjadi aliran `IpcEvent` hingga
+//! klien menutupnya.
+//!
+//! Kredensial peer diperiksa sekali saat tersambung; perintah yang
+//! `requires_privilege()` dari peer yang tidak berhak dibalas
+//! `IpcError::PermissionDenied`.
+use crate::daemon::Daemon;
+use crate::peer::{Access, Peer};
+use m4arch_ipc::codec::{read_frame, write_frame};
+use m4arch_ipc::protocol::{IpcCommand, IpcError, IpcReply, Request, Response, PROTOCOL_VERSION};
+use std::fs;
+use std::io::{self, BufReader};
+use std::os::unix::fs::PermissionsExt;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::Path;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+
+/// Membuat socket di `path`, menghapus socket lama yang tertinggal.
+///
+/// Socket bisa ditulis semua pengguna, sehingga CLI/GUI tidak perlu izin
+/// tulis ke sysfs; hanya daemon (root) yang menyentuh hardware. Perintah
+/// platform dibatasi lewat `Access`.
+pub fn bind(path: &Path) -> io::Result<UnixListener> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+        _ => {}
+    }
+    let listener = UnixListener::bind(path)?;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o666))?;
+    Ok(listener)
+}
+
+/// Menerima koneksi selamanya.
+pub fn serve(listener: UnixListener, daemon: Arc<Mutex<Daemon>>, access: Access) {
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => {
+                let daemon = Arc::clone(&daemon);
+                // Tanpa kredensial, peer diperlakukan sebagai pengguna biasa
+                let privileged = Peer::of(&stream).is_ok_and(|peer| access.allows(&peer));
+                thread::spawn(move || {
+                    if let Err(e) = handle_client(stream, &daemon, privileged) {
+                        eprintln!("m4arch-daemon: client error: {}", e);
+                    }
+                });
+            }
+            Err(e) => eprintln!("m4arch-daemon: accept failed: {}", e),
+        }
+    }
+}
+
+fn handle_client(stream: UnixStream, daemon: &Mutex<Daemon>, privileged: bool) -> io::Result<()> {
+    let mut writer = stream.try_clone()?;
+    let mut reader = BufReader::new(stream);
+    let mut handshaken = false;
+
+    loop {
+        let request: Request = match read_frame(&mut reader) {
+            Ok(Some(request)) => request,
+            Ok(None) => return Ok(()),
+            Err(e) if e.kind() == io::ErrorKind::InvalidData => {
+                // Frame utuh tetapi isinya tidak valid: laporkan, lalu lanjutkan
+                let response = Response {
+                    id: 0,
+                    result: Err(IpcError::Malformed(e.to_string())),
+                };
+                write_frame(&mut writer, &response)?;
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
+
+        let result = match request.command {
+            IpcCommand::Hello { version } if version == PROTOCOL_VERSION => {
+                handshaken = true;
+                Ok(IpcReply::Hello {
+                    version: PROTOCOL_VERSION,
+                })
+            }
+            IpcCommand::Hello { version } => Err(IpcError::VersionMismatch {
+                client: version,
+                daemon: PROTOCOL_VERSION,
+            }),
+            _ if !handshaken => Err(IpcError::HandshakeRequired),
+            IpcCommand::Subscribe => {
+                let events = lock(daemon).subscribe();
+                write_frame(
+                    &mut writer,
+                    &Response {
+                        id: request.id,
+                        result: Ok(IpcReply::Subscribed),
+                    },
+                )?;
+                // Berhenti saat klien putus (tulis gagal)
+                for event in events {
+                    write_frame(&mut writer, &event)?;
+                }
+                return Ok(());
+            }
+            command if command.requires_privilege() && !privileged => {
+                Err(IpcError::PermissionDenied)
+            }
+            command => lock(daemon).handle(command),
+        };
+
+        write_frame(
+            &mut writer,
+            &Response {
+                id: request.id,
+                result,
+            },
+        )?;
+    }
+}
+
+/// Mengunci daemon; state tetap dipakai meski thread lain panik.
+pub fn lock(daemon: &Mutex<Daemon>) -> MutexGuard<'_, Daemon> {
+    daemon
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use m4arch_core::battery::ChargePreset;
+    use m4arch_core::config::{FanMode, FanSettings, SettingsStore};
+    use m4arch_core::cpu::CpuTuning;
+    use m4arch_core::power::PowerProfile;
+    use m4arch_ipc::protocol::{
+        ChargeCommand, CpuCommand, FanCommand, IpcEvent, PowerProfileCommand,
+    };
+    use m4arch_service::sysfs::fake::{FakeLed, FakeSysfs};
+    use std::io::Write;
+
+    fn request(stream: &mut UnixStream, id: u64, command: IpcCommand) -> Response {
+        write_frame(stream, &Request { id, command }).unwrap();
+        read_frame(stream).unwrap().unwrap()
+    }
+
+    const LED: &str = "class/leds/rgb:kbd_backlight";
+
+    fn hello(stream: &mut UnixStream) {
+        let hello = IpcCommand::Hello {
+            version: PROTOCOL_VERSION,
+        };
+        let response = request(stream, 1, hello);
+        assert!(matches!(response.result, Ok(IpcReply::Hello { .. })));
+    }
+
+    fn start() -> (FakeSysfs, tempfile::TempDir, UnixStream) {
+        let fake = FakeSysfs::new().unwrap();
+        fake.add_led(LED, &FakeLed::rgb(1, 255, (0, 0, 0))).unwrap();
+        let daemon = Daemon::new(fake.root(), SettingsStore::new(fake.path("settings.toml")));
+
+        let socket = tempfile::tempdir().unwrap();
+        let path = socket.path().join("run/m4arch.sock");
+        let listener = bind(&path).unwrap();
+        // Proses pengujian sendiri selalu berhak.
+        // SAFETY: `getgid` tidak punya prasyarat
+        let access = Access::new(Some(unsafe { libc::getgid() }));
+        thread::spawn(move || serve(listener, Arc::new(Mutex::new(daemon)), access));
+
+        let stream = UnixStream::connect(&path).unwrap();
+        (fake, socket, stream)
+    }
+
+    #[test]
+    fn test_serves_commands_over_socket() {
+        let (fake, _socket, mut stream) = start();
+
+        let hello = IpcCommand::Hello {
+            version: PROTOCOL_VERSION,
+        };
+        let response = request(&mut stream, 1, hello);
+        assert_eq!(response.id, 1);
+        assert!(matches!(response.result, Ok(IpcReply::Hello { .. })));
+
+        let response = request(&mut stream, 2, IpcCommand::SetBrightness(42));
+        assert_eq!(response.id, 2);
+        assert_eq!(response.result, Ok(IpcReply::Done));
+        assert_eq!(fake.read(format!("{LED}/brightness")).unwrap(), "42");
+    }
+
+    #[test]
+    fn test_streams_events_to_subscribers() {
+        let (_fake, socket, mut subscriber) = start();
+        hello(&mut subscriber);
+        let response = request(&mut subscriber, 2, IpcCommand::Subscribe);
+        assert_eq!(response.result, Ok(IpcReply::Subscribed));
+
+        let mut stream = UnixStream::connect(socket.path().join("run/m4arch.sock")).unwrap();
+        hello(&mut stream);
+        request(&mut stream, 2, IpcCommand::SetRgb(1, 2, 3));
+
+        let event: IpcEvent = read_frame(&mut subscriber).unwrap().unwrap();
+        assert_eq!(
+            event,
+            IpcEvent::ColorChanged {
+                zone: None,
+                rgb: m4arch_core::keyboard::Rgb { r: 1, g: 2, b: 3 },
+            }
+        );
+    }
+
+    #[test]
+    fn test_requires_handshake() {
+        let (_fake, _socket, mut stream) = start();
+        let response = request(&mut stream, 7, IpcCommand::GetRgb);
+        assert_eq!(response.result, Err(IpcError::HandshakeRequired));
+
+        let response = request(&mut stream, 8, IpcCommand::Hello { version: 999 });
+        assert_eq!(
+            response.result,
+            Err(IpcError::VersionMismatch {
+                client: 999,
+                daemon: PROTOCOL_VERSION
+            })
+        );
+    }
+
+    #[test]
+    fn test_rejects_platform_changes_from_unprivileged_peers() {
+        let fake = FakeSysfs::new().unwrap();
+        let daemon = Daemon::new(fake.root(), SettingsStore::new(fake.path("settings.toml")));
+        let daemon = Arc::new(Mutex::new(daemon));
+        let (mut stream, server) = UnixStream::pair().unwrap();
+        thread::spawn(move || handle_client(server, &daemon, false));
+        hello(&mut stream);
+
+        let settings = FanSettings {
+            mode: FanMode::Curve,
+            ..FanSettings::default()
+        };
+        let denied = [
+            IpcCommand::Fan(FanCommand::SetSettings(settings)),
+            IpcCommand::PowerProfile(PowerProfileCommand::Set(PowerProfile::Performance)),
+            IpcCommand::Cpu(CpuCommand::Tune(CpuTuning {
+                turbo: Some(false),
+                ..CpuTuning::default()
+            })),
+            IpcCommand::Charge(ChargeCommand::Set(ChargePreset::Stationary.thresholds())),
+        ];
+        for (id, command) in (2..).zip(denied) {
+            let response = request(&mut stream, id, command);
+            assert_eq!(response.result, Err(IpcError::PermissionDenied));
+        }
+        assert!(!fake.path("settings.toml").exists());
+
+        // Membaca tetap boleh
+        let response = request(&mut stream, 10, IpcCommand::Fan(FanCommand::GetSettings));
+        assert_eq!(
+            response.result,
+            Ok(IpcReply::FanSettings(FanSettings::default()))
+        );
+    }
+
+    #[test]
+    fn test_reports_malformed_frames() {
+        let (_fake, _socket, mut stream) = start();
+        stream.write_all(&5u32.to_be_bytes()).unwrap();
+        stream.write_all(b"{oops").unwrap();
+        let response: Response = read_frame(&mut stream).unwrap().unwrap();
+        assert!(matches!(response.result, Err(IpcError::Malformed(_))));
+    }
+}
@@ -0,0 +1,47 @@
+//! Menghentikan daemon dengan rapi saat menerima SIGTERM atau SIGINT.
+//!
+//! Kipas yang diatur manual tetap pada duty terakhirnya setelah proses
+//! berhenti, jadi sebelum keluar kipas harus dikembalikan ke firmware.
+//! Sinyal diblokir sejak awal (mask ini diwarisi semua thread yang dibuat
+//! sesudahnya) lalu ditunggu satu thread dengan `sigwait()`, sehingga
+//! penanganannya boleh mengunci daemon seperti kode biasa.
+use std::io;
+use std::mem::MaybeUninit;
+use std::ptr;
+
+/// Sinyal penghentian yang sudah diblokir dan siap ditunggu.
+pub struct Termination {
+    set: libc::sigset_t,
+}
+
+impl Termination {
+    /// Memblokir SIGTERM dan SIGINT; panggil sebelum thread lain dibuat.
+    pub fn block() -> io::Result<Self> {
+        let mut set = MaybeUninit::<libc::sigset_t>::uninit();
+        // SAFETY: `sigemptyset` menginisialisasi `set` sebelum dipakai
+        let set = unsafe {
+            libc::sigemptyset(set.as_mut_ptr());
+            let mut set = set.assume_init();
+            libc::sigaddset(&mut set, libc::SIGTERM);
+            libc::sigaddset(&mut set, libc::SIGINT);
+            set
+        };
+        // SAFETY: `set` sudah terinisialisasi dan mask lama tidak diminta
+        let result = unsafe { libc::pthread_sigmask(libc::SIG_BLOCK, &set, ptr::null_mut()) };
+        if result != 0 {
+            return Err(io::Error::from_raw_os_error(result));
+        }
+        Ok(Self { set })
+    }
+
+    /// Menunggu sampai salah satu sinyal diterima.
+    pub fn wait(&self) -> io::Result<()> {
+        let mut signal = 0;
+        // SAFETY: kedua pointer valid selama panggilan
+        let result = unsafe { libc::sigwait(&self.set, &mut signal) };
+        if result != 0 {
+            return Err(io::Error::from_raw_os_error(result));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,140 @@
+//! Pemantauan hardware di latar belakang.
+//!
+//! Atribut sysfs LED tidak mendukung inotify, dan `poll()` pada
+//! `brightness_hw_changed` hanya tersedia untuk sebagian driver, jadi daemon
+//! cukup membaca ulang atributnya secara berkala. Pembacaan ini murah
+//! (beberapa file kecil) dan juga menangkap driver yang tidak melaporkan
+//! `brightness_hw_changed` sama sekali.
+//!
+//! Loop yang sama mendeteksi resume dari suspend: `Instant` (CLOCK_MONOTONIC)
+//! berhenti selama suspend sedangkan jam dinding tetap berjalan, jadi selisih
+//! keduanya yang besar berarti sistem baru saja bangun.
+//!
+//! Timeout idle juga diperiksa di sini, berdasarkan `ActivitySource`, begitu
+//! pula sumber daya untuk aturan AC/baterai dan jadwal harian. Kurva kipas
+//! diperbarui lebih jarang, karena suhu tidak berubah secepat itu.
+use crate::daemon::Daemon;
+use crate::input::ActivitySource;
+use crate::server::lock;
+use chrono::Local;
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+/// Interval pembacaan brightness untuk mendeteksi hotkey.
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Keyboard dicari ulang (driver dimuat/dilepas) setiap sekian kali poll.
+const RESCAN_EVERY: u32 = 8;
+
+/// Kurva kipas diperbarui setiap sekian kali poll.
+const FAN_EVERY: u32 = 8;
+
+/// Selisih jam minimum yang dianggap sebagai suspend/resume.
+const RESUME_GAP: Duration = Duration::from_secs(5);
+
+/// Mendeteksi resume dari selisih jam monotonic dan jam dinding.
+struct ResumeDetector {
+    monotonic: Instant,
+    wall: SystemTime,
+}
+
+impl ResumeDetector {
+    fn new() -> Self {
+        Self {
+            monotonic: Instant::now(),
+            wall: SystemTime::now(),
+        }
+    }
+
+    fn check(&mut self) -> bool {
+        self.check_at(Instant::now(), SystemTime::now())
+    }
+
+    fn check_at(&mut self, monotonic: Instant, wall: SystemTime) -> bool {
+        let awake = monotonic.duration_since(self.monotonic);
+        // Jam dinding yang mundur (NTP) tidak dianggap resume
+        let elapsed = wall.duration_since(self.wall).unwrap_or_default();
+        self.monotonic = monotonic;
+        self.wall = wall;
+        elapsed.saturating_sub(awake) >= RESUME_GAP
+    }
+}
+
+/// Memantau hardware dan aktivitas pengguna selamanya.
+pub fn run(daemon: &Mutex<Daemon>, activity: &dyn ActivitySource) {
+    // Error yang sama cukup dilaporkan sekali, bukan setiap poll
+    let mut last_error = None;
+    let mut last_fan_error = None;
+    let mut resume = ResumeDetector::new();
+    for tick in 0u32.. {
+        thread::sleep(POLL_INTERVAL);
+        let mut daemon = lock(daemon);
+        if tick % RESCAN_EVERY == 0 {
+            daemon.rescan();
+            activity.rescan();
+        }
+        // Pulihkan sebelum poll, agar reset oleh firmware tidak dianggap
+        // sebagai perubahan dari hotkey
+        if resume.check() {
+            if let Err(e) = daemon.restore() {
+                eprintln!("m4arch-daemon: cannot restore keyboard state: {}", e);
+            }
+            daemon.reset_fans();
+            if let Err(e) = daemon.restore_power_profile() {
+                eprintln!("m4arch-daemon: cannot restore power profile: {}", e);
+            }
+        }
+        if tick % FAN_EVERY == 0 {
+            match daemon.update_fans() {
+                Ok(()) => last_fan_error = None,
+                Err(e) => {
+                    let message = e.to_string();
+                    if last_fan_error.as_ref() != Some(&message) {
+                        eprintln!("m4arch-daemon: cannot control fans: {}", message);
+                        last_fan_error = Some(message);
+                    }
+                }
+            }
+        }
+        // Tanpa sumber aktivitas, pengguna dianggap selalu aktif
+        let now = Instant::now();
+        let last_activity = activity.last_activity().unwrap_or(now);
+        let result = daemon
+            .poll_hardware()
+            .and_then(|()| daemon.poll_power())
+            .and_then(|()| daemon.check_idle(last_activity, now))
+            .and_then(|()| {
+                let local = Local::now();
+                daemon.check_schedule(local.naive_local(), local.offset().local_minus_utc())
+            });
+        match result {
+            Ok(()) => last_error = None,
+            Err(e) => {
+                let message = e.to_string();
+                if last_error.as_ref() != Some(&message) {
+                    eprintln!("m4arch-daemon: cannot read keyboard state: {}", message);
+                    last_error = Some(message);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detects_resume_from_clock_gap() {
+        let mut detector = ResumeDetector::new();
+        let (monotonic, wall) = (detector.monotonic, detector.wall);
+
+        let second = Duration::from_secs(1);
+        assert!(!detector.check_at(monotonic + second, wall + second));
+        // 1 detik terjaga, 60 detik berlalu: sistem sempat suspend
+        assert!(detector.check_at(monotonic + second * 2, wall + second * 61));
+        // Jam dinding mundur
+        assert!(!detector.check_at(monotonic + second * 3, wall));
+    }
+}
@@ -1,10 +1,50 @@
 mod pages;
 mod style;
 
+use iced::futures::channel::mpsc;
+use iced::futures::{SinkExt, StreamExt};
 use iced::widget::{button, column, container, row, text, Rule};
-use iced::{executor, Application, Command, Element, Font, Length, Settings, Theme};
-use m4arch_service::{get_brightness, get_rgb_color, set_brightness, set_rgb_color};
+use iced::{
+    executor, subscription, Application, Command, Element, Font, Length, Settings, Subscription,
+    Theme,
+};
+use m4arch_core::battery::BatteryInfo;
+use m4arch_core::config::{
+    Calibration, IdleSettings, Location, Schedule, ScheduleAction, ScheduleEntry,
+};
+use m4arch_core::cpu::CpuTuning;
+use m4arch_core::error::M4ArchError;
+use m4arch_core::keyboard::Hsv;
+use m4arch_core::keyboard::Transition;
+use m4arch_core::power::PowerProfile;
+use m4arch_core::{Effect, EffectKind, KeyboardMode, KeyboardZone, Rgb};
+use m4arch_ipc::client::{
+    self, battery_info, cpu_status, delete_profile, fade_brightness, fade_to_profile,
+    get_brightness, get_calibration, get_effect, get_idle_settings, get_keyboard_mode,
+    get_power_profile, get_rgb_color, get_schedule, get_zone_rgb_color, get_zones, keyboard_modes,
+    list_power_profiles, list_profiles, preview_calibration, restore, save_profile,
+    set_calibration, set_effect, set_idle_settings, set_keyboard_mode, set_power_profile,
+    set_rgb_color, set_schedule, set_zone_rgb_color, stop_effect, tune_cpu, Mode,
+};
+use m4arch_ipc::protocol::IpcEvent;
+use pages::calibration::{CalibrationField, CalibrationForm};
+use pages::power::PowerSection;
+use pages::schedule::{ActionChoice, ScheduleForm};
 use std::borrow::Cow;
+use std::thread;
+use std::time::Duration;
+
+/// Jeda sebelum mencoba berlangganan ulang setelah koneksi ke daemon putus.
+const RESUBSCRIBE_DELAY: Duration = Duration::from_secs(2);
+
+/// Selang pembacaan ulang baterai selama dashboard terbuka.
+const BATTERY_REFRESH: Duration = Duration::from_secs(30);
+
+/// Transisi untuk slider brightness; cukup singkat agar tetap responsif.
+const BRIGHTNESS_FADE: Transition = Transition::from_millis(150);
+
+/// Transisi saat berpindah profil.
+const PROFILE_FADE: Transition = Transition::from_millis(400);
 
 // Definisi Font
 const POPPINS: Font = Font {
@@ -22,6 +62,10 @@ const ICON_FONT: Font = Font {
 };
 
 pub fn main() -> iced::Result {
+    if let Some(mode) = mode_from_args(std::env::args().skip(1)) {
+        client::set_mode(mode);
+    }
+
     M4ArchGui::run(Settings {
         default_font: POPPINS,
         fonts: vec![
@@ -34,13 +78,40 @@ pub fn main() -> iced::Result {
     })
 }
 
+/// Membaca `--mode <auto|daemon|direct>` (atau `--mode=...`) dari argumen.
+fn mode_from_args(mut args: impl Iterator<Item = String>) -> Option<Mode> {
+    while let Some(arg) = args.next() {
+        let value = match arg.strip_prefix("--mode") {
+            Some("") => args.next()?,
+            Some(rest) => rest.strip_prefix('=')?.to_string(),
+            None => continue,
+        };
+        match value.parse() {
+            Ok(mode) => return Some(mode),
+            Err(e) => eprintln!("m4arch-gui: {}", e),
+        }
+    }
+    None
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Page {
     Dashboard,
     Keyboard,
+    Schedule,
+    Calibration,
+    Power,
     About,
 }
 
+/// Pengaturan idle yang diubah lewat slider.
+#[derive(Debug, Clone, Copy)]
+pub enum IdleField {
+    DimAfter,
+    OffAfter,
+    DimPercent,
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum RgbComponent {
     Red,
@@ -54,16 +125,88 @@ struct M4ArchGui {
     brightness_text: String,
     brightness_value: u8,
     rgb_value: (u8, u8, u8),
+    zones: Vec<KeyboardZone>,
+    /// Zona yang sedang diedit; `None` berarti semua zona
+    selected_zone: Option<usize>,
+    profiles: Vec<String>,
+    active_profile: Option<String>,
+    profile_name: String,
+    /// Efek yang sedang berjalan di daemon
+    effect: Option<EffectKind>,
+    effect_speed: u8,
+    /// Mode firmware yang didukung; kosong jika keyboard tidak memilikinya
+    modes: Vec<KeyboardMode>,
+    mode: Option<KeyboardMode>,
+    idle: IdleSettings,
+    schedule: Schedule,
+    schedule_form: ScheduleForm,
+    calibration: CalibrationForm,
+    power: PowerSection,
+    batteries: Vec<BatteryInfo>,
+}
+
+/// Data awal yang dibaca dari hardware saat aplikasi dibuka.
+#[derive(Debug, Clone)]
+pub struct InitialData {
+    brightness: u8,
+    rgb: (u8, u8, u8),
+    zones: Vec<KeyboardZone>,
+    profiles: Vec<String>,
+    effect: Option<Effect>,
+    modes: Vec<KeyboardMode>,
+    mode: Option<KeyboardMode>,
+    idle: IdleSettings,
+    schedule: Schedule,
+    calibration: Calibration,
 }
 
 #[derive(Debug, Clone)]
 pub enum Message {
-    Loaded(Result<(u8, (u8, u8, u8)), String>),
+    Loaded(Result<InitialData, String>),
+    /// Dimuat terpisah karena tidak bergantung pada keyboard
+    PowerProfilesLoaded(PowerSection),
+    PowerProfileSelected(PowerProfile),
+    CpuTuned(CpuTuning),
+    BatteriesLoaded(Vec<BatteryInfo>),
+    RefreshBatteries,
     PageSelected(Page),
+    ZoneSelected(Option<usize>),
     BrightnessChanged(u8),
     RgbChanged(RgbComponent, u8),
+    /// Hue dalam derajat (0–360)
+    HueChanged(u16),
+    /// Saturasi dalam persen (0–100)
+    SaturationChanged(u8),
     PresetColorSelected(u8, u8, u8),
+    ProfileNameChanged(String),
+    SaveProfile,
+    ApplyProfile(String),
+    DeleteProfile(String),
+    /// `None` menghentikan efek
+    EffectSelected(Option<EffectKind>),
+    EffectSpeedChanged(u8),
+    ModeSelected(KeyboardMode),
+    IdleChanged(IdleField, u16),
+    /// Slider idle dilepas: simpan ke daemon
+    SaveIdle,
+    ScheduleTimeChanged(String),
+    ScheduleActionSelected(ActionChoice),
+    ScheduleBrightnessChanged(u8),
+    AddScheduleEntry,
+    RemoveScheduleEntry(usize),
+    LatitudeChanged(String),
+    LongitudeChanged(String),
+    SaveLocation,
+    CalibrationChanged(CalibrationField, u16),
+    /// Menampilkan warna uji dengan draf kalibrasi
+    CalibrationTestSelected(Rgb),
+    SaveCalibration,
+    /// Membuang draf dan mengembalikan warna tersimpan
+    RevertCalibration,
+    ResetCalibration,
     OpenUrl(String),
+    /// Perubahan state hardware dari daemon
+    HardwareEvent(IpcEvent),
 }
 
 impl Application for M4ArchGui {
@@ -79,11 +222,30 @@ impl Application for M4ArchGui {
             brightness_text: "Loading...".to_string(),
             brightness_value: 0,
             rgb_value: (255, 255, 255), // Default White
+            zones: Vec::new(),
+            selected_zone: None,
+            profiles: Vec::new(),
+            active_profile: None,
+            profile_name: String::new(),
+            effect: None,
+            effect_speed: Effect::DEFAULT_SPEED,
+            modes: Vec::new(),
+            mode: None,
+            idle: IdleSettings::default(),
+            schedule: Schedule::default(),
+            schedule_form: ScheduleForm::default(),
+            calibration: CalibrationForm::default(),
+            power: PowerSection::default(),
+            batteries: Vec::new(),
         };
 
         (
             initial_state,
-            Command::perform(load_initial_data(), Message::Loaded),
+            Command::batch([
+                Command::perform(load_initial_data(), Message::Loaded),
+                Command::perform(load_power_profiles(), Message::PowerProfilesLoaded),
+                Command::perform(load_batteries(), Message::BatteriesLoaded),
+            ]),
         )
     }
 
@@ -97,23 +259,72 @@ impl Application for M4ArchGui {
 
     fn update(&mut self, message: Message) -> Command<Message> {
         match message {
-            Message::Loaded(Ok((brightness, rgb))) => {
-                self.brightness_value = brightness;
-                self.brightness_text = format!("Current Brightness: {}", brightness);
-                self.rgb_value = rgb;
+            Message::Loaded(Ok(data)) => {
+                self.brightness_value = data.brightness;
+                self.brightness_text = format!("Current Brightness: {}", data.brightness);
+                self.rgb_value = data.rgb;
+                self.zones = data.zones;
+                self.profiles = data.profiles;
+                self.show_effect(data.effect);
+                self.modes = data.modes;
+                self.mode = data.mode;
+                self.idle = data.idle;
+                self.show_schedule(data.schedule);
+                self.calibration.saved = data.calibration;
+                self.calibration.draft = data.calibration;
                 self.error_message = None;
             }
             Message::Loaded(Err(e)) => {
                 self.error_message = Some(e);
                 self.brightness_text = "Error loading data".to_string();
             }
+            Message::PowerProfilesLoaded(power) => self.power = power,
+            Message::PowerProfileSelected(profile) => match set_power_profile(profile) {
+                Ok(()) => {
+                    self.power.active = Some(profile);
+                    self.error_message = None;
+                    // Profil juga mengubah tuning CPU
+                    return Command::perform(load_power_profiles(), Message::PowerProfilesLoaded);
+                }
+                Err(e) => self.error_message = Some(format!("Error setting power profile: {}", e)),
+            },
+            Message::CpuTuned(tuning) => match tune_cpu(tuning) {
+                Ok(()) => {
+                    self.error_message = None;
+                    return Command::perform(load_power_profiles(), Message::PowerProfilesLoaded);
+                }
+                Err(e) => self.error_message = Some(format!("Error tuning CPU: {}", e)),
+            },
+            Message::BatteriesLoaded(batteries) => self.batteries = batteries,
+            Message::RefreshBatteries => {
+                return Command::perform(load_batteries(), Message::BatteriesLoaded)
+            }
             Message::PageSelected(page) => {
+                // Warna uji tidak boleh tertinggal setelah halaman ditutup
+                if page != Page::Calibration && self.calibration.previewing {
+                    self.end_calibration_preview();
+                }
                 self.active_page = page;
+                if page == Page::Dashboard {
+                    return Command::perform(load_batteries(), Message::BatteriesLoaded);
+                }
+            }
+            Message::ZoneSelected(zone) => {
+                self.selected_zone = zone;
+                // Tampilkan warna zona yang dipilih (zona pertama untuk "All")
+                match get_zone_rgb_color(zone.unwrap_or(0)) {
+                    Ok(rgb) => {
+                        self.rgb_value = rgb;
+                        self.error_message = None;
+                    }
+                    Err(e) => self.error_message = Some(format!("Error reading color: {}", e)),
+                }
             }
             Message::BrightnessChanged(val) => {
                 self.brightness_value = val;
+                self.active_profile = None;
                 // Panggil service untuk mengubah hardware
-                if let Err(e) = set_brightness(val) {
+                if let Err(e) = fade_brightness(val, ui_transition(BRIGHTNESS_FADE)) {
                     let err_msg = format!("Error: {}", e);
                     self.brightness_text = err_msg.clone();
                     self.error_message = Some(err_msg);
@@ -129,29 +340,172 @@ impl Application for M4ArchGui {
                     RgbComponent::Green => g = val,
                     RgbComponent::Blue => b = val,
                 }
-                self.rgb_value = (r, g, b);
-                if let Err(e) = set_rgb_color(r, g, b) {
-                    self.error_message = Some(format!("Error setting color: {}", e));
-                } else {
+                self.apply_rgb(r, g, b);
+            }
+            Message::HueChanged(hue) => {
+                let (r, g, b) = self.rgb_value;
+                let hsv = Hsv {
+                    hue: f32::from(hue),
+                    ..Rgb { r, g, b }.to_hsv()
+                };
+                let rgb = Rgb::from_hsv(hsv);
+                self.apply_rgb(rgb.r, rgb.g, rgb.b);
+            }
+            Message::SaturationChanged(saturation) => {
+                let (r, g, b) = self.rgb_value;
+                let hsv = Hsv {
+                    saturation: f32::from(saturation) / 100.0,
+                    ..Rgb { r, g, b }.to_hsv()
+                };
+                let rgb = Rgb::from_hsv(hsv);
+                self.apply_rgb(rgb.r, rgb.g, rgb.b);
+            }
+            Message::PresetColorSelected(r, g, b) => {
+                self.apply_rgb(r, g, b);
+            }
+            Message::ProfileNameChanged(name) => {
+                self.profile_name = name;
+            }
+            Message::SaveProfile => {
+                let name = self.profile_name.trim().to_string();
+                match save_profile(&name) {
+                    Ok(()) => {
+                        self.profile_name.clear();
+                        self.active_profile = Some(name);
+                        self.refresh_profiles();
+                    }
+                    Err(e) => {
+                        self.error_message = Some(format!("Error saving profile: {}", e));
+                    }
+                }
+            }
+            Message::ApplyProfile(name) => {
+                match fade_to_profile(&name, ui_transition(PROFILE_FADE)) {
+                    Ok(()) => {
+                        self.active_profile = Some(name);
+                        self.error_message = None;
+                        // Tanpa daemon tidak ada event, jadi baca ulang state hardware
+                        return Command::perform(load_initial_data(), Message::Loaded);
+                    }
+                    Err(e) => self.error_message = Some(format!("Error applying profile: {}", e)),
+                }
+            }
+            Message::DeleteProfile(name) => match delete_profile(&name) {
+                Ok(()) => {
+                    if self.active_profile.as_ref() == Some(&name) {
+                        self.active_profile = None;
+                    }
+                    self.refresh_profiles();
+                }
+                Err(e) => self.error_message = Some(format!("Error deleting profile: {}", e)),
+            },
+            Message::EffectSelected(kind) => self.apply_effect(kind),
+            Message::EffectSpeedChanged(speed) => {
+                self.effect_speed = speed;
+                // Efek yang sedang berjalan dimulai ulang dengan kecepatan baru
+                if self.effect.is_some() {
+                    self.apply_effect(self.effect);
+                }
+            }
+            Message::ModeSelected(mode) => match set_keyboard_mode(mode) {
+                Ok(()) => {
+                    self.mode = Some(mode);
+                    self.active_profile = None;
                     self.error_message = None;
+                    // Animasi firmware menggantikan efek
+                    if mode != KeyboardMode::Static {
+                        self.effect = None;
+                    }
+                }
+                Err(e) => self.error_message = Some(format!("Error setting mode: {}", e)),
+            },
+            Message::IdleChanged(field, value) => {
+                // Nilai 0 pada slider waktu berarti "tidak pernah"
+                let seconds = (value > 0).then_some(u64::from(value));
+                match field {
+                    IdleField::DimAfter => self.idle.dim_after = seconds,
+                    IdleField::OffAfter => self.idle.off_after = seconds,
+                    IdleField::DimPercent => self.idle.dim_percent = value.min(100) as u8,
                 }
             }
-            Message::PresetColorSelected(r, g, b) => {
-                self.rgb_value = (r, g, b);
-                if let Err(e) = set_rgb_color(r, g, b) {
-                    self.error_message = Some(format!("Error setting color: {}", e));
-                } else {
+            Message::SaveIdle => match set_idle_settings(self.idle) {
+                Ok(()) => self.error_message = None,
+                Err(e) => self.error_message = Some(format!("Error saving idle timeout: {}", e)),
+            },
+            Message::ScheduleTimeChanged(time) => self.schedule_form.time = time,
+            Message::ScheduleActionSelected(action) => self.schedule_form.action = Some(action),
+            Message::ScheduleBrightnessChanged(level) => self.schedule_form.brightness = level,
+            Message::AddScheduleEntry => self.add_schedule_entry(),
+            Message::RemoveScheduleEntry(index) => {
+                let mut schedule = self.schedule.clone();
+                if index < schedule.entries.len() {
+                    schedule.entries.remove(index);
+                    self.save_schedule(schedule);
+                }
+            }
+            Message::LatitudeChanged(value) => self.schedule_form.latitude = value,
+            Message::LongitudeChanged(value) => self.schedule_form.longitude = value,
+            Message::SaveLocation => {
+                let form = &self.schedule_form;
+                match (form.latitude.trim().parse(), form.longitude.trim().parse()) {
+                    (Ok(latitude), Ok(longitude)) => {
+                        let mut schedule = self.schedule.clone();
+                        schedule.location = Some(Location {
+                            latitude,
+                            longitude,
+                        });
+                        self.save_schedule(schedule);
+                    }
+                    _ => {
+                        self.error_message =
+                            Some("Latitude and longitude must be numbers".to_string())
+                    }
+                }
+            }
+            Message::CalibrationChanged(field, value) => {
+                self.calibration.set(field, value);
+                self.preview_calibration();
+            }
+            Message::CalibrationTestSelected(rgb) => {
+                self.calibration.test = rgb;
+                self.preview_calibration();
+            }
+            Message::SaveCalibration => match set_calibration(self.calibration.draft) {
+                Ok(()) => {
+                    // Daemon menerapkan ulang warna tersimpan dengan kalibrasi baru
+                    self.calibration.saved = self.calibration.draft;
+                    self.calibration.previewing = false;
                     self.error_message = None;
                 }
+                Err(e) => self.error_message = Some(format!("Error saving calibration: {}", e)),
+            },
+            Message::RevertCalibration => {
+                self.calibration.draft = self.calibration.saved;
+                self.end_calibration_preview();
+            }
+            Message::ResetCalibration => {
+                self.calibration.draft = Calibration::default();
+                self.preview_calibration();
             }
             Message::OpenUrl(url) => {
                 // Menggunakan xdg-open untuk membuka URL di browser default
                 let _ = std::process::Command::new("xdg-open").arg(url).spawn();
             }
+            Message::HardwareEvent(event) => return self.apply_event(event),
         }
         Command::none()
     }
 
+    fn subscription(&self) -> Subscription<Message> {
+        if self.active_page != Page::Dashboard {
+            return hardware_events();
+        }
+        Subscription::batch([
+            hardware_events(),
+            iced::time::every(BATTERY_REFRESH).map(|_| Message::RefreshBatteries),
+        ])
+    }
+
     fn view(&self) -> Element<'_, Message> {
         // --- Sidebar ---
         let sidebar = column![
@@ -159,6 +513,14 @@ impl Application for M4ArchGui {
             Rule::horizontal(10),
             sidebar_button("\u{f0e4}", "Dashboard", Page::Dashboard, self.active_page),
             sidebar_button("\u{f11c}", "Keyboard", Page::Keyboard, self.active_page),
+            sidebar_button("\u{f017}", "Schedule", Page::Schedule, self.active_page),
+            sidebar_button(
+                "\u{f1fc}",
+                "Calibration",
+                Page::Calibration,
+                self.active_page
+            ),
+            sidebar_button("\u{f0e7}", "Power", Page::Power, self.active_page),
             sidebar_button("\u{f05a}", "About", Page::About, self.active_page),
         ]
         .spacing(10)
@@ -168,10 +530,33 @@ impl Application for M4ArchGui {
 
         // --- Content Area ---
         let content = container(match self.active_page {
-            Page::Dashboard => pages::dashboard::view(),
-            Page::Keyboard => {
-                pages::keyboard::view(self.brightness_value, &self.brightness_text, self.rgb_value)
+            Page::Dashboard => pages::dashboard::view(&self.batteries),
+            Page::Keyboard => pages::keyboard::view(
+                self.brightness_value,
+                &self.brightness_text,
+                self.rgb_value,
+                pages::keyboard::ZoneSection {
+                    zones: &self.zones,
+                    selected: self.selected_zone,
+                },
+                pages::keyboard::ProfileSection {
+                    names: &self.profiles,
+                    active: self.active_profile.as_deref(),
+                    new_name: &self.profile_name,
+                },
+                pages::keyboard::EffectSection {
+                    active: self.effect,
+                    speed: self.effect_speed,
+                    modes: &self.modes,
+                    mode: self.mode,
+                },
+                self.idle,
+            ),
+            Page::Schedule => {
+                pages::schedule::view(&self.schedule, &self.schedule_form, &self.profiles)
             }
+            Page::Calibration => pages::calibration::view(&self.calibration),
+            Page::Power => pages::power::view(&self.power),
             Page::About => pages::about::view(),
         })
         .width(Length::Fill)
@@ -183,10 +568,260 @@ impl Application for M4ArchGui {
     }
 }
 
-async fn load_initial_data() -> Result<(u8, (u8, u8, u8)), String> {
+impl M4ArchGui {
+    /// Menerapkan warna ke zona yang dipilih, atau ke semua zona.
+    fn apply_rgb(&mut self, r: u8, g: u8, b: u8) {
+        self.rgb_value = (r, g, b);
+        self.active_profile = None;
+        // Warna statis menghentikan efek dan mengembalikan mode `Static`
+        self.effect = None;
+        self.leave_firmware_mode();
+        let result = match self.selected_zone {
+            Some(zone) => set_zone_rgb_color(zone, r, g, b),
+            None => set_rgb_color(r, g, b),
+        };
+        if let Err(e) = result {
+            self.error_message = Some(format!("Error setting color: {}", e));
+        } else {
+            self.error_message = None;
+        }
+    }
+}
+
+impl M4ArchGui {
+    /// Memulai efek dengan warna yang sedang dipilih, atau menghentikannya.
+    fn apply_effect(&mut self, kind: Option<EffectKind>) {
+        let result = match kind {
+            Some(kind) => {
+                let (r, g, b) = self.rgb_value;
+                set_effect(Effect {
+                    kind,
+                    speed: self.effect_speed,
+                    colors: vec![Rgb { r, g, b }],
+                })
+            }
+            None => stop_effect(),
+        };
+        match result {
+            Ok(()) => {
+                self.effect = kind;
+                self.active_profile = None;
+                self.error_message = None;
+                if kind.is_some() {
+                    self.leave_firmware_mode();
+                }
+            }
+            Err(e) => self.error_message = Some(format!("Error setting effect: {}", e)),
+        }
+    }
+
+    fn leave_firmware_mode(&mut self) {
+        if self.mode.is_some() {
+            self.mode = Some(KeyboardMode::Static);
+        }
+    }
+
+    fn show_effect(&mut self, effect: Option<Effect>) {
+        if let Some(effect) = &effect {
+            self.effect_speed = effect.speed;
+        }
+        self.effect = effect.map(|effect| effect.kind);
+    }
+
+    fn show_schedule(&mut self, schedule: Schedule) {
+        if let Some(location) = &schedule.location {
+            self.schedule_form.latitude = location.latitude.to_string();
+            self.schedule_form.longitude = location.longitude.to_string();
+        }
+        self.schedule = schedule;
+    }
+
+    fn add_schedule_entry(&mut self) {
+        let form = &self.schedule_form;
+        let Ok(at) = form.time.trim().parse() else {
+            self.error_message =
+                Some("Time must be HH:MM, sunrise or sunset (e.g. sunset+30)".to_string());
+            return;
+        };
+        let action = match &form.action {
+            Some(ActionChoice::Profile(name)) => ScheduleAction::Profile(name.clone()),
+            Some(ActionChoice::Brightness) => ScheduleAction::Brightness(form.brightness),
+            None => return,
+        };
+        let mut schedule = self.schedule.clone();
+        schedule.entries.push(ScheduleEntry { at, action });
+        if self.save_schedule(schedule) {
+            self.schedule_form.time.clear();
+        }
+    }
+
+    /// Mengirim jadwal ke daemon; tampilan hanya berubah jika berhasil.
+    fn save_schedule(&mut self, schedule: Schedule) -> bool {
+        match set_schedule(schedule.clone()) {
+            Ok(()) => {
+                self.schedule = schedule;
+                self.error_message = None;
+                true
+            }
+            Err(e) => {
+                self.error_message = Some(format!("Error saving schedule: {}", e));
+                false
+            }
+        }
+    }
+
+    fn preview_calibration(&mut self) {
+        let form = &mut self.calibration;
+        match preview_calibration(form.draft, form.test) {
+            Ok(()) => {
+                form.previewing = true;
+                self.error_message = None;
+            }
+            Err(e) => self.error_message = Some(format!("Error previewing calibration: {}", e)),
+        }
+    }
+
+    /// Mengembalikan warna tersimpan setelah pratinjau kalibrasi.
+    fn end_calibration_preview(&mut self) {
+        self.calibration.previewing = false;
+        if let Err(e) = restore() {
+            self.error_message = Some(format!("Error restoring colors: {}", e));
+        }
+    }
+
+    fn refresh_profiles(&mut self) {
+        match list_profiles() {
+            Ok(profiles) => {
+                self.profiles = profiles;
+                self.error_message = None;
+            }
+            Err(e) => self.error_message = Some(format!("Error loading profiles: {}", e)),
+        }
+    }
+
+    /// Menyelaraskan halaman keyboard dengan perubahan dari luar GUI ini.
+    fn apply_event(&mut self, event: IpcEvent) -> Command<Message> {
+        match event {
+            IpcEvent::BrightnessChanged(info) => {
+                self.brightness_value = info.current;
+                self.brightness_text = format!("Current Brightness: {}", info.current);
+            }
+            IpcEvent::ColorChanged { zone, rgb } => {
+                // Hanya perbarui jika zona yang sedang ditampilkan ikut berubah
+                let shown = self.selected_zone.unwrap_or(0);
+                if zone.is_none_or(|zone| zone == shown) {
+                    self.rgb_value = (rgb.r, rgb.g, rgb.b);
+                }
+            }
+            IpcEvent::PowerChanged(false) => {
+                self.brightness_text = "Backlight off".to_string();
+            }
+            IpcEvent::PowerChanged(true) => {}
+            IpcEvent::ProfileChanged(name) => {
+                self.active_profile = Some(name);
+                self.refresh_profiles();
+            }
+            IpcEvent::EffectChanged(effect) => self.show_effect(effect),
+            IpcEvent::ModeChanged(mode) => self.mode = Some(mode),
+            IpcEvent::DriverAdded(_) => {
+                return Command::perform(load_initial_data(), Message::Loaded);
+            }
+            IpcEvent::DriverRemoved => {
+                self.zones.clear();
+                self.selected_zone = None;
+                self.error_message = Some("Keyboard driver was removed".to_string());
+            }
+            IpcEvent::PowerProfileChanged(profile) => self.power.active = Some(profile),
+        }
+        Command::none()
+    }
+}
+
+/// Aliran `IpcEvent` dari daemon.
+///
+/// Socket dibaca secara blocking, jadi dibaca di thread terpisah yang
+/// meneruskan event ke executor iced. Thread tersambung ulang jika daemon
+/// dimulai ulang, dan berhenti jika mode `direct` (tanpa daemon) dipakai.
+fn hardware_events() -> Subscription<Message> {
+    struct HardwareEvents;
+
+    subscription::channel(
+        std::any::TypeId::of::<HardwareEvents>(),
+        32,
+        |mut output| async move {
+            let (sender, mut receiver) = mpsc::unbounded();
+            thread::spawn(move || loop {
+                match client::subscribe() {
+                    Ok(events) => {
+                        for event in events.map_while(Result::ok) {
+                            if sender.unbounded_send(event).is_err() {
+                                return;
+                            }
+                        }
+                    }
+                    Err(M4ArchError::Unsupported(_)) => return,
+                    Err(_) => {}
+                }
+                thread::sleep(RESUBSCRIBE_DELAY);
+            });
+
+            while let Some(event) = receiver.next().await {
+                let _ = output.send(Message::HardwareEvent(event)).await;
+            }
+            // Thread berhenti; subscription tetap hidup tanpa event baru
+            iced::futures::future::pending().await
+        },
+    )
+}
+
+async fn load_initial_data() -> Result<InitialData, String> {
     let brightness = get_brightness().map_err(|e| e.to_string())?;
     let rgb = get_rgb_color().map_err(|e| e.to_string())?;
-    Ok((brightness, rgb))
+    let zones = get_zones().map_err(|e| e.to_string())?;
+    // Pengaturan yang rusak tidak boleh menghalangi kontrol keyboard
+    let profiles = list_profiles().unwrap_or_default();
+    let effect = get_effect().unwrap_or_default();
+    let modes = keyboard_modes().unwrap_or_default();
+    let mode = get_keyboard_mode().ok();
+    let idle = get_idle_settings().unwrap_or_default();
+    let schedule = get_schedule().unwrap_or_default();
+    let calibration = get_calibration().unwrap_or_default();
+    Ok(InitialData {
+        brightness,
+        rgb,
+        zones,
+        profiles,
+        effect,
+        modes,
+        mode,
+        idle,
+        schedule,
+        calibration,
+    })
+}
+
+/// Transisi hanya dipakai jika daemon yang menjalankannya. Tanpa daemon,
+/// transisi memblokir `update()` sampai selesai dan tidak bisa dibatalkan
+/// oleh perubahan berikutnya, jadi nilai langsung diterapkan.
+fn ui_transition(transition: Transition) -> Transition {
+    if client::uses_daemon() {
+        transition
+    } else {
+        Transition::from_millis(0)
+    }
+}
+
+/// Kosong jika tidak ada baterai atau tidak bisa dibaca.
+async fn load_batteries() -> Vec<BatteryInfo> {
+    battery_info().unwrap_or_default()
+}
+
+async fn load_power_profiles() -> PowerSection {
+    PowerSection {
+        available: list_power_profiles().unwrap_or_default(),
+        active: get_power_profile().ok(),
+        cpu: cpu_status().ok(),
+    }
 }
 
 fn sidebar_button<'a>(
@@ -0,0 +1,198 @@
+use crate::{style, Message, RgbComponent};
+use iced::widget::{button, column, row, slider, text};
+use iced::{Alignment, Color, Element};
+use m4arch_core::config::Calibration;
+use m4arch_core::keyboard::Rgb;
+
+/// Nilai kalibrasi yang diubah lewat slider.
+#[derive(Debug, Clone, Copy)]
+pub enum CalibrationField {
+    /// Gamma dalam perseratus (20–500)
+    Gamma,
+    /// Gain kanal dalam persen (0–100)
+    Gain(RgbComponent),
+    WhitePoint(RgbComponent),
+}
+
+/// Warna uji yang ditampilkan keyboard selama kalibrasi.
+pub const TEST_COLORS: [(&str, Rgb); 5] = [
+    ("White", Rgb::new(255, 255, 255)),
+    ("Grey 25%", Rgb::new(64, 64, 64)),
+    ("Red", Rgb::new(255, 0, 0)),
+    ("Green", Rgb::new(0, 255, 0)),
+    ("Blue", Rgb::new(0, 0, 255)),
+];
+
+/// Kalibrasi tersimpan dan draf yang sedang dipratinjau di keyboard.
+#[derive(Debug, Clone)]
+pub struct CalibrationForm {
+    pub saved: Calibration,
+    pub draft: Calibration,
+    pub test: Rgb,
+    /// Keyboard sedang menampilkan warna uji, bukan state tersimpan
+    pub previewing: bool,
+}
+
+impl Default for CalibrationForm {
+    fn default() -> Self {
+        Self {
+            saved: Calibration::default(),
+            draft: Calibration::default(),
+            test: TEST_COLORS[0].1,
+            previewing: false,
+        }
+    }
+}
+
+impl CalibrationForm {
+    pub fn set(&mut self, field: CalibrationField, value: u16) {
+        let draft = &mut self.draft;
+        match field {
+            CalibrationField::Gamma => draft.gamma = f32::from(value) / 100.0,
+            CalibrationField::Gain(component) => {
+                let gain = f32::from(value.min(100)) / 100.0;
+                match component {
+                    RgbComponent::Red => draft.gain.red = gain,
+                    RgbComponent::Green => draft.gain.green = gain,
+                    RgbComponent::Blue => draft.gain.blue = gain,
+                }
+            }
+            CalibrationField::WhitePoint(component) => {
+                let level = value.min(255) as u8;
+                match component {
+                    RgbComponent::Red => draft.white_point.r = level,
+                    RgbComponent::Green => draft.white_point.g = level,
+                    RgbComponent::Blue => draft.white_point.b = level,
+                }
+            }
+        }
+    }
+}
+
+pub fn view(form: &CalibrationForm) -> Element<'_, Message> {
+    let draft = form.draft;
+    let percent = |gain: f32| (gain * 100.0).round() as u16;
+    let tests = TEST_COLORS.iter().map(|&(label, rgb)| {
+        let selected = form.previewing && form.test == rgb;
+        button(text(label))
+            .on_press(Message::CalibrationTestSelected(rgb))
+            .padding(10)
+            .style(if selected {
+                iced::theme::Button::Primary
+            } else {
+                iced::theme::Button::Custom(Box::new(style::PresetButtonStyle {
+                    background: Color::from_rgb8(rgb.r, rgb.g, rgb.b),
+                }))
+            })
+            .into()
+    });
+
+    let changed = form.draft != form.saved;
+    column![
+        text("Color Calibration").size(30),
+        text("Adjust until the keyboard shows the test color as it looks on screen").size(14),
+        row(tests).spacing(10),
+        column![
+            text("White Point").size(20),
+            text("Lower the channel that makes white look tinted").size(14),
+            field_slider(
+                "Red",
+                0..=255,
+                u16::from(draft.white_point.r),
+                CalibrationField::WhitePoint(RgbComponent::Red),
+            ),
+            field_slider(
+                "Green",
+                0..=255,
+                u16::from(draft.white_point.g),
+                CalibrationField::WhitePoint(RgbComponent::Green),
+            ),
+            field_slider(
+                "Blue",
+                0..=255,
+                u16::from(draft.white_point.b),
+                CalibrationField::WhitePoint(RgbComponent::Blue),
+            ),
+        ]
+        .spacing(10),
+        column![
+            text("Brightness Curve").size(20),
+            text("Lower gamma brightens dark colors (try the 25% grey)").size(14),
+            field_slider(
+                "Gamma",
+                20..=500,
+                (draft.gamma * 100.0).round() as u16,
+                CalibrationField::Gamma,
+            ),
+        ]
+        .spacing(10),
+        column![
+            text("Channel Gain").size(20),
+            field_slider(
+                "Red",
+                0..=100,
+                percent(draft.gain.red),
+                CalibrationField::Gain(RgbComponent::Red),
+            ),
+            field_slider(
+                "Green",
+                0..=100,
+                percent(draft.gain.green),
+                CalibrationField::Gain(RgbComponent::Green),
+            ),
+            field_slider(
+                "Blue",
+                0..=100,
+                percent(draft.gain.blue),
+                CalibrationField::Gain(RgbComponent::Blue),
+            ),
+        ]
+        .spacing(10),
+        row![
+            button(text("Save")).on_press_maybe(changed.then_some(Message::SaveCalibration)),
+            button(text("Revert"))
+                .on_press_maybe((changed || form.previewing).then_some(Message::RevertCalibration))
+                .style(iced::theme::Button::Secondary),
+            button(text("Reset to Default"))
+                .on_press_maybe((!draft.is_identity()).then_some(Message::ResetCalibration))
+                .style(iced::theme::Button::Destructive),
+        ]
+        .spacing(10),
+    ]
+    .spacing(20)
+    .into()
+}
+
+fn field_slider(
+    label: &str,
+    range: std::ops::RangeInclusive<u16>,
+    value: u16,
+    field: CalibrationField,
+) -> Element<'_, Message> {
+    let color = match field {
+        CalibrationField::Gain(component) | CalibrationField::WhitePoint(component) => {
+            match component {
+                RgbComponent::Red => Color::from_rgb8(255, 0, 0),
+                RgbComponent::Green => Color::from_rgb8(0, 255, 0),
+                RgbComponent::Blue => Color::from_rgb8(0, 0, 255),
+            }
+        }
+        CalibrationField::Gamma => Color::WHITE,
+    };
+    let shown = match field {
+        CalibrationField::Gamma => format!("{:.2}", f32::from(value) / 100.0),
+        CalibrationField::Gain(_) => format!("{}%", value),
+        CalibrationField::WhitePoint(_) => value.to_string(),
+    };
+
+    row![
+        text(label).width(80),
+        slider(range, value, move |v| Message::CalibrationChanged(field, v)).style(
+            iced::theme::Slider::Custom(Box::new(style::RgbSliderStyle { color }))
+        ),
+        text(shown).width(50),
+    ]
+    .spacing(10)
+    .align_items(Alignment::Center)
+    .into()
+}
@@ -1,12 +1,60 @@
 use crate::Message;
-use iced::widget::{column, text};
-use iced::Element;
+use iced::widget::{column, container, text, Column};
+use iced::{Element, Length};
+use m4arch_core::battery::{BatteryInfo, BatteryState};
+use std::time::Duration;
 
-pub fn view() -> Element<'static, Message> {
-    column![
+pub fn view(batteries: &[BatteryInfo]) -> Element<'_, Message> {
+    let mut content = column![
         text("Dashboard").size(30),
         text("Welcome to m4arch configuration center.")
     ]
-    .spacing(20)
-    .into()
+    .spacing(20);
+    for battery in batteries {
+        content = content.push(battery_card(battery));
+    }
+    content.into()
+}
+
+/// Sisa kapasitas, perkiraan waktu dan kesehatan satu baterai.
+fn battery_card(battery: &BatteryInfo) -> Element<'_, Message> {
+    let capacity = battery
+        .capacity
+        .map_or_else(|| "-".to_string(), |capacity| format!("{}%", capacity));
+    let mut card = Column::new()
+        .spacing(8)
+        .push(text(format!("Battery {}", battery.name)).size(20))
+        .push(text(format!("{} · {}", capacity, battery.state)).size(26));
+
+    if let Some(remaining) = battery.time_remaining() {
+        let until = match battery.state {
+            BatteryState::Charging => "until full",
+            _ => "remaining",
+        };
+        card = card.push(text(format!("{} {}", format_duration(remaining), until)).size(16));
+    }
+    if let Some(power) = battery.power_mw {
+        card = card.push(text(format!("{:.1} W", f64::from(power) / 1000.0)).size(14));
+    }
+    let mut health = Vec::new();
+    if let Some(percent) = battery.health() {
+        health.push(format!("Health {:.0}%", percent));
+    }
+    if let Some(cycles) = battery.cycle_count {
+        health.push(format!("{} cycles", cycles));
+    }
+    if !health.is_empty() {
+        card = card.push(text(health.join(" · ")).size(14));
+    }
+
+    container(card)
+        .padding(15)
+        .width(Length::Fill)
+        .style(iced::theme::Container::Box)
+        .into()
+}
+
+fn format_duration(duration: Duration) -> String {
+    let minutes = duration.as_secs() / 60;
+    format!("{}h {:02}m", minutes / 60, minutes % 60)
 }
@@ -1,8 +1,45 @@
-use crate::{style, Message, RgbComponent};
-use iced::widget::{button, column, container, row, slider, text};
+use crate::{style, IdleField, Message, RgbComponent};
+use iced::widget::{button, column, container, row, slider, text, text_input, Column, Row};
 use iced::{Alignment, Color, Element};
+use m4arch_core::config::IdleSettings;
+use m4arch_core::keyboard::Hsv;
+use m4arch_core::{Effect, EffectKind, KeyboardMode, KeyboardZone, Rgb};
 
-pub fn view(brightness: u8, brightness_text: &str, rgb: (u8, u8, u8)) -> Element<'_, Message> {
+/// Data untuk bagian profil di halaman keyboard.
+pub struct ProfileSection<'a> {
+    pub names: &'a [String],
+    /// Profil yang terakhir diterapkan
+    pub active: Option<&'a str>,
+    /// Isi kolom nama untuk menyimpan profil baru
+    pub new_name: &'a str,
+}
+
+/// Data untuk pemilih zona RGB.
+pub struct ZoneSection<'a> {
+    pub zones: &'a [KeyboardZone],
+    /// Zona yang dipilih, `None` berarti semua zona
+    pub selected: Option<usize>,
+}
+
+/// Data untuk bagian efek di halaman keyboard.
+pub struct EffectSection<'a> {
+    /// Efek yang sedang berjalan
+    pub active: Option<EffectKind>,
+    pub speed: u8,
+    /// Mode bawaan firmware; kosong jika keyboard tidak memilikinya
+    pub modes: &'a [KeyboardMode],
+    pub mode: Option<KeyboardMode>,
+}
+
+pub fn view<'a>(
+    brightness: u8,
+    brightness_text: &'a str,
+    rgb: (u8, u8, u8),
+    zones: ZoneSection<'a>,
+    profiles: ProfileSection<'a>,
+    effects: EffectSection<'a>,
+    idle: IdleSettings,
+) -> Element<'a, Message> {
     let (r, g, b) = rgb;
 
     column![
@@ -20,6 +57,7 @@ pub fn view(brightness: u8, brightness_text: &str, rgb: (u8, u8, u8)) -> Element
         column![
             row![
                 text("RGB Color").size(20),
+                text(Rgb { r, g, b }.to_string()).size(14),
                 container(row![])
                     .width(30)
                     .height(30)
@@ -31,9 +69,11 @@ pub fn view(brightness: u8, brightness_text: &str, rgb: (u8, u8, u8)) -> Element
             ]
             .spacing(10)
             .align_items(Alignment::Center),
+            zone_selector(zones.zones, zones.selected),
             rgb_slider("Red", r, RgbComponent::Red),
             rgb_slider("Green", g, RgbComponent::Green),
             rgb_slider("Blue", b, RgbComponent::Blue),
+            hue_saturation(Rgb { r, g, b }),
             row![
                 preset_button("Red", 255, 0, 0),
                 preset_button("Green", 0, 255, 0),
@@ -42,12 +82,222 @@ pub fn view(brightness: u8, brightness_text: &str, rgb: (u8, u8, u8)) -> Element
             ]
             .spacing(10)
         ]
-        .spacing(10)
+        .spacing(10),
+        effect_section(effects),
+        idle_section(idle),
+        profile_section(profiles),
     ]
     .spacing(30)
     .into()
 }
 
+/// Tombol efek animasi dan slider kecepatannya, serta mode firmware.
+fn effect_section(effects: EffectSection<'_>) -> Element<'_, Message> {
+    let kinds = std::iter::once(None).chain(EffectKind::ALL.into_iter().map(Some));
+    let buttons = kinds.map(|kind| {
+        let label = kind.map_or("Off".to_string(), |kind| capitalize(&kind.to_string()));
+        button(text(label))
+            .on_press(Message::EffectSelected(kind))
+            .padding(8)
+            .style(iced::theme::Button::Custom(Box::new(
+                style::SidebarButtonStyle {
+                    selected: kind == effects.active,
+                },
+            )))
+            .into()
+    });
+
+    column![
+        text("Effects").size(20),
+        Row::with_children(buttons).spacing(10),
+        row![
+            text("Speed").width(60),
+            slider(
+                Effect::MIN_SPEED..=Effect::MAX_SPEED,
+                effects.speed,
+                Message::EffectSpeedChanged
+            )
+            .style(iced::theme::Slider::Custom(Box::new(
+                style::RgbSliderStyle {
+                    color: Color::WHITE
+                }
+            ))),
+            text(effects.speed.to_string()).width(40),
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center),
+        mode_selector(effects.modes, effects.mode),
+    ]
+    .spacing(10)
+    .into()
+}
+
+/// Tombol mode bawaan firmware; disembunyikan jika keyboard tidak memilikinya.
+fn mode_selector(modes: &[KeyboardMode], active: Option<KeyboardMode>) -> Element<'_, Message> {
+    if modes.is_empty() {
+        return row![].into();
+    }
+
+    let buttons = modes.iter().map(|&mode| {
+        button(text(capitalize(&mode.to_string())))
+            .on_press(Message::ModeSelected(mode))
+            .padding(8)
+            .style(iced::theme::Button::Custom(Box::new(
+                style::SidebarButtonStyle {
+                    selected: Some(mode) == active,
+                },
+            )))
+            .into()
+    });
+
+    column![
+        text("Hardware Modes").size(20),
+        Row::with_children(buttons).spacing(10),
+    ]
+    .spacing(10)
+    .into()
+}
+
+/// Slider timeout idle; perubahan disimpan saat slider dilepas.
+fn idle_section<'a>(idle: IdleSettings) -> Element<'a, Message> {
+    let seconds = |value: Option<u64>| value.map_or(0, |secs| secs.min(u64::from(u16::MAX)) as u16);
+    let describe = |value: u16| match value {
+        0 => "Never".to_string(),
+        secs if secs % 60 == 0 => format!("{} min", secs / 60),
+        secs => format!("{} s", secs),
+    };
+
+    column![
+        text("Idle Timeout").size(20),
+        idle_slider(
+            "Dim after",
+            0..=600,
+            15,
+            seconds(idle.dim_after),
+            IdleField::DimAfter,
+            describe(seconds(idle.dim_after)),
+        ),
+        idle_slider(
+            "Turn off after",
+            0..=1800,
+            30,
+            seconds(idle.off_after),
+            IdleField::OffAfter,
+            describe(seconds(idle.off_after)),
+        ),
+        idle_slider(
+            "Dim level",
+            0..=100,
+            5,
+            u16::from(idle.dim_percent),
+            IdleField::DimPercent,
+            format!("{}%", idle.dim_percent),
+        ),
+    ]
+    .spacing(10)
+    .into()
+}
+
+fn idle_slider<'a>(
+    label: &'a str,
+    range: std::ops::RangeInclusive<u16>,
+    step: u16,
+    value: u16,
+    field: IdleField,
+    description: String,
+) -> Element<'a, Message> {
+    row![
+        text(label).width(120),
+        slider(range, value, move |v| Message::IdleChanged(field, v))
+            .step(step)
+            .on_release(Message::SaveIdle)
+            .style(iced::theme::Slider::Custom(Box::new(
+                style::RgbSliderStyle {
+                    color: Color::WHITE
+                }
+            ))),
+        text(description).width(70),
+    ]
+    .spacing(10)
+    .align_items(Alignment::Center)
+    .into()
+}
+
+fn capitalize(name: &str) -> String {
+    let mut chars = name.chars();
+    chars.next().map_or_else(String::new, |first| {
+        first.to_uppercase().chain(chars).collect()
+    })
+}
+
+/// Daftar profil (klik untuk menerapkan) dan kolom untuk menyimpan profil baru.
+fn profile_section(profiles: ProfileSection<'_>) -> Element<'_, Message> {
+    let list = profiles.names.iter().map(|name| {
+        let is_active = profiles.active == Some(name.as_str());
+        row![
+            button(text(name))
+                .on_press(Message::ApplyProfile(name.clone()))
+                .padding(8)
+                .width(200)
+                .style(iced::theme::Button::Custom(Box::new(
+                    style::SidebarButtonStyle {
+                        selected: is_active
+                    },
+                ))),
+            button(text("Delete").size(14))
+                .on_press(Message::DeleteProfile(name.clone()))
+                .padding(8)
+                .style(iced::theme::Button::Destructive),
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center)
+        .into()
+    });
+
+    column![
+        text("Profiles").size(20),
+        Column::with_children(list).spacing(5),
+        row![
+            text_input("Profile name", profiles.new_name)
+                .on_input(Message::ProfileNameChanged)
+                .on_submit(Message::SaveProfile)
+                .width(200),
+            button(text("Save current")).on_press(Message::SaveProfile),
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center),
+    ]
+    .spacing(10)
+    .into()
+}
+
+/// Tombol pemilih zona; disembunyikan jika keyboard hanya punya satu zona.
+fn zone_selector(zones: &[KeyboardZone], selected: Option<usize>) -> Element<'_, Message> {
+    if zones.len() <= 1 {
+        return row![].into();
+    }
+
+    let buttons = std::iter::once(zone_button("All", None, selected)).chain(
+        zones
+            .iter()
+            .map(|zone| zone_button(&zone.name, Some(zone.index), selected)),
+    );
+
+    Row::with_children(buttons).spacing(10).into()
+}
+
+fn zone_button(label: &str, zone: Option<usize>, selected: Option<usize>) -> Element<'_, Message> {
+    button(text(label))
+        .on_press(Message::ZoneSelected(zone))
+        .padding(8)
+        .style(iced::theme::Button::Custom(Box::new(
+            style::SidebarButtonStyle {
+                selected: zone == selected,
+            },
+        )))
+        .into()
+}
+
 fn rgb_slider(label: &str, value: u8, component: RgbComponent) -> Element<'_, Message> {
     let color = match component {
         RgbComponent::Red => Color::from_rgb8(255, 0, 0),
@@ -56,7 +306,7 @@ fn rgb_slider(label: &str, value: u8, component: RgbComponent) -> Element<'_, Me
     };
 
     row![
-        text(label).width(60),
+        text(label).width(80),
         slider(0..=255, value, move |v| Message::RgbChanged(component, v)).style(
             iced::theme::Slider::Custom(Box::new(style::RgbSliderStyle { color }))
         ),
@@ -67,6 +317,46 @@ fn rgb_slider(label: &str, value: u8, component: RgbComponent) -> Element<'_, Me
     .into()
 }
 
+/// Slider hue dan saturasi; nilai (kecerahan warna) dipertahankan.
+fn hue_saturation<'a>(rgb: Rgb) -> Element<'a, Message> {
+    let hsv = rgb.to_hsv();
+    let hue = hsv.hue.round() as u16;
+    let saturation = (hsv.saturation * 100.0).round() as u8;
+    // Warna jenuh dengan hue saat ini sebagai petunjuk di slider
+    let pure = Rgb::from_hsv(Hsv {
+        hue: hsv.hue,
+        saturation: 1.0,
+        value: 1.0,
+    });
+
+    column![
+        row![
+            text("Hue").width(80),
+            slider(0..=360, hue, Message::HueChanged).style(iced::theme::Slider::Custom(Box::new(
+                style::RgbSliderStyle {
+                    color: Color::from_rgb8(pure.r, pure.g, pure.b)
+                }
+            ))),
+            text(hue.to_string()).width(40),
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center),
+        row![
+            text("Saturation").width(80),
+            slider(0..=100, saturation, Message::SaturationChanged).style(
+                iced::theme::Slider::Custom(Box::new(style::RgbSliderStyle {
+                    color: Color::from_rgb8(rgb.r, rgb.g, rgb.b)
+                }))
+            ),
+            text(format!("{}%", saturation)).width(40),
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center),
+    ]
+    .spacing(10)
+    .into()
+}
+
 fn preset_button(label: &str, r: u8, g: u8, b: u8) -> Element<'_, Message> {
     let color = Color::from_rgb8(r, g, b);
 
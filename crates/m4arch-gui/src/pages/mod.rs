@@ -1,3 +1,6 @@
 pub mod about;
+pub mod calibration;
 pub mod dashboard;
 pub mod keyboard;
+pub mod power;
+pub mod schedule;
@@ -0,0 +1,128 @@
+use crate::Message;
+use iced::widget::{button, column, pick_list, row, text, toggler, Column};
+use iced::{Alignment, Element, Length};
+use m4arch_core::cpu::{CpuStatus, CpuTuning};
+use m4arch_core::power::PowerProfile;
+
+/// Profil performa yang didukung dan yang sedang aktif.
+#[derive(Debug, Clone, Default)]
+pub struct PowerSection {
+    pub available: Vec<PowerProfile>,
+    /// `None` jika profil aktif tidak bisa dibaca
+    pub active: Option<PowerProfile>,
+    /// `None` jika tidak ada cpufreq
+    pub cpu: Option<CpuStatus>,
+}
+
+fn description(profile: PowerProfile) -> &'static str {
+    match profile {
+        PowerProfile::Quiet => "Lower power limits, quiet fans",
+        PowerProfile::Balanced => "Default limits for everyday use",
+        PowerProfile::Performance => "Maximum power limits, louder fans",
+    }
+}
+
+pub fn view(section: &PowerSection) -> Element<'_, Message> {
+    let buttons = PowerProfile::ALL.into_iter().map(|profile| {
+        let label = column![
+            text(profile.to_string()).size(20),
+            text(description(profile)).size(14),
+        ]
+        .spacing(5);
+        let mut choice = button(label).width(Length::Fill).padding(15).style(
+            if section.active == Some(profile) {
+                iced::theme::Button::Primary
+            } else {
+                iced::theme::Button::Secondary
+            },
+        );
+        // Profil yang tidak didukung tetap tampil, tetapi tidak bisa dipilih
+        if section.available.contains(&profile) {
+            choice = choice.on_press(Message::PowerProfileSelected(profile));
+        }
+        choice.into()
+    });
+
+    let status = if section.available.is_empty() {
+        "Power profiles are not supported on this laptop"
+    } else {
+        "The daemon reapplies the chosen profile after boot and resume"
+    };
+    let mut content = column![
+        text("Power Profile").size(30),
+        row(buttons).spacing(10),
+        text(status).size(14),
+    ]
+    .spacing(20);
+    if let Some(cpu) = &section.cpu {
+        content = content.push(cpu_view(cpu));
+    }
+    content.into()
+}
+
+/// Governor, EPP dan turbo. Pilihan berlaku untuk semua policy, jadi yang
+/// ditampilkan adalah nilai policy pertama.
+fn cpu_view(status: &CpuStatus) -> Element<'_, Message> {
+    let mut content = Column::new().spacing(10).push(text("CPU").size(20)).push(
+        text(format!(
+            "Driver: {}. Changes last until the profile is changed",
+            status.driver
+        ))
+        .size(14),
+    );
+    let Some(policy) = status.policies.first() else {
+        return content.into();
+    };
+
+    content = content.push(setting_row(
+        "Governor",
+        pick_list(
+            policy.governors.clone(),
+            Some(policy.governor.clone()),
+            |governor| {
+                Message::CpuTuned(CpuTuning {
+                    governor: Some(governor),
+                    ..Default::default()
+                })
+            },
+        )
+        .into(),
+    ));
+    if policy.energy_preference.is_some() {
+        content = content.push(setting_row(
+            "Energy preference",
+            pick_list(
+                policy.energy_preferences.clone(),
+                policy.energy_preference.clone(),
+                |preference| {
+                    Message::CpuTuned(CpuTuning {
+                        energy_preference: Some(preference),
+                        ..Default::default()
+                    })
+                },
+            )
+            .into(),
+        ));
+    }
+    if let Some(turbo) = status.turbo {
+        content = content.push(setting_row(
+            "Turbo boost",
+            toggler(None, turbo, |turbo| {
+                Message::CpuTuned(CpuTuning {
+                    turbo: Some(turbo),
+                    ..Default::default()
+                })
+            })
+            .width(Length::Shrink)
+            .into(),
+        ));
+    }
+    content.into()
+}
+
+fn setting_row<'a>(label: &'a str, control: Element<'a, Message>) -> Element<'a, Message> {
+    row![text(label).width(160), control]
+        .spacing(10)
+        .align_items(Alignment::Center)
+        .into()
+}
@@ -0,0 +1,133 @@
+use crate::{style, Message};
+use iced::widget::{button, column, pick_list, row, slider, text, text_input, Column};
+use iced::{Alignment, Color, Element};
+use m4arch_core::config::Schedule;
+use std::fmt;
+
+/// Pilihan aksi untuk entry jadwal baru.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ActionChoice {
+    Profile(String),
+    Brightness,
+}
+
+impl fmt::Display for ActionChoice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ActionChoice::Profile(name) => write!(f, "Profile: {}", name),
+            ActionChoice::Brightness => write!(f, "Brightness"),
+        }
+    }
+}
+
+/// Isi formulir entry baru dan lokasi yang belum disimpan.
+#[derive(Debug, Clone, Default)]
+pub struct ScheduleForm {
+    pub time: String,
+    pub action: Option<ActionChoice>,
+    pub brightness: u8,
+    pub latitude: String,
+    pub longitude: String,
+}
+
+pub fn view<'a>(
+    schedule: &'a Schedule,
+    form: &'a ScheduleForm,
+    profiles: &'a [String],
+) -> Element<'a, Message> {
+    let entries = schedule.entries.iter().enumerate().map(|(index, entry)| {
+        row![
+            text(entry.to_string()).width(260),
+            button(text("Remove").size(14))
+                .on_press(Message::RemoveScheduleEntry(index))
+                .padding(8)
+                .style(iced::theme::Button::Destructive),
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center)
+        .into()
+    });
+    let entries: Element<'a, Message> = if schedule.entries.is_empty() {
+        text("No schedule entries").size(14).into()
+    } else {
+        Column::with_children(entries).spacing(5).into()
+    };
+
+    column![
+        text("Schedule").size(30),
+        text("Applied every day by m4arch-daemon").size(14),
+        entries,
+        new_entry(form, profiles),
+        location(form),
+    ]
+    .spacing(20)
+    .into()
+}
+
+/// Formulir untuk menambah entry: waktu, aksi dan (untuk brightness) levelnya.
+fn new_entry<'a>(form: &'a ScheduleForm, profiles: &'a [String]) -> Element<'a, Message> {
+    let mut choices: Vec<ActionChoice> = profiles
+        .iter()
+        .cloned()
+        .map(ActionChoice::Profile)
+        .collect();
+    choices.push(ActionChoice::Brightness);
+
+    let mut form_row = row![
+        text_input("22:00 or sunset+30", &form.time)
+            .on_input(Message::ScheduleTimeChanged)
+            .on_submit(Message::AddScheduleEntry)
+            .width(180),
+        pick_list(
+            choices,
+            form.action.clone(),
+            Message::ScheduleActionSelected
+        )
+        .placeholder("Action"),
+    ]
+    .spacing(10)
+    .align_items(Alignment::Center);
+
+    if form.action == Some(ActionChoice::Brightness) {
+        form_row = form_row.push(
+            slider(0..=255, form.brightness, Message::ScheduleBrightnessChanged)
+                .width(150)
+                .style(iced::theme::Slider::Custom(Box::new(
+                    style::RgbSliderStyle {
+                        color: Color::WHITE,
+                    },
+                ))),
+        );
+        form_row = form_row.push(text(form.brightness).width(40));
+    }
+    let add = button(text("Add")).on_press_maybe(
+        (form.action.is_some() && !form.time.trim().is_empty())
+            .then_some(Message::AddScheduleEntry),
+    );
+
+    column![text("Add Entry").size(20), form_row.push(add)]
+        .spacing(10)
+        .into()
+}
+
+/// Lokasi untuk entry `sunrise`/`sunset`.
+fn location(form: &ScheduleForm) -> Element<'_, Message> {
+    column![
+        text("Location").size(20),
+        text("Needed for sunrise and sunset entries, in degrees").size(14),
+        row![
+            text_input("Latitude", &form.latitude)
+                .on_input(Message::LatitudeChanged)
+                .width(120),
+            text_input("Longitude", &form.longitude)
+                .on_input(Message::LongitudeChanged)
+                .on_submit(Message::SaveLocation)
+                .width(120),
+            button(text("Save")).on_press(Message::SaveLocation),
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center),
+    ]
+    .spacing(10)
+    .into()
+}
@@ -0,0 +1,974 @@
+//! Klien daemon untuk CLI dan GUI.
+//!
+//! Menyediakan fungsi facade yang sama dengan `m4arch_service` (misalnya
+//! `brightness_info`, `set_rgb_color`), tetapi dijalankan lewat socket
+//! daemon. Pada mode `Auto`, jika daemon tidak berjalan, fungsi-fungsi ini
+//! kembali mengakses sysfs secara langsung lewat `m4arch_service`.
+use crate::codec::{read_frame, write_frame};
+use crate::protocol::{
+    ChargeCommand, CpuCommand, FadeTarget, FanCommand, IpcCommand, IpcEvent, IpcReply,
+    PowerProfileCommand, Request, Response, PROTOCOL_VERSION,
+};
+use m4arch_core::battery::{BatteryInfo, ChargeThresholds};
+use m4arch_core::config::{
+    Calibration, FanMode, FanSettings, IdleSettings, KeyboardState, Profile, Schedule,
+    SettingsStore,
+};
+use m4arch_core::cpu::{CpuStatus, CpuTuning};
+use m4arch_core::error::{M4ArchError, Result};
+use m4arch_core::fan::FanInfo;
+use m4arch_core::keyboard::{
+    BrightnessInfo, Effect, KeyboardCapabilities, KeyboardMode, KeyboardZone, Rgb, StepDirection,
+    Transition,
+};
+use m4arch_core::power::PowerProfile;
+use std::io::{self, BufReader};
+use std::os::unix::net::UnixStream;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// Cara front-end mengakses hardware.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Mode {
+    /// Pakai daemon jika berjalan, selain itu akses sysfs langsung
+    #[default]
+    Auto,
+    /// Hanya lewat daemon; error jika daemon tidak berjalan
+    Daemon,
+    /// Selalu akses sysfs langsung (butuh izin tulis ke sysfs)
+    Direct,
+}
+
+impl FromStr for Mode {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "auto" => Ok(Mode::Auto),
+            "daemon" => Ok(Mode::Daemon),
+            "direct" => Ok(Mode::Direct),
+            other => Err(format!(
+                "unknown mode '{other}' (expected auto, daemon or direct)"
+            )),
+        }
+    }
+}
+
+/// 0 = belum diatur (pakai `M4ARCH_MODE` atau `Auto`), selain itu `Mode` + 1.
+static MODE: AtomicU8 = AtomicU8::new(0);
+
+/// Memaksa mode akses untuk seluruh proses.
+pub fn set_mode(mode: Mode) {
+    MODE.store(mode as u8 + 1, Ordering::Relaxed);
+}
+
+/// Mode akses aktif: hasil `set_mode()`, variabel `M4ARCH_MODE`, atau `Auto`.
+pub fn mode() -> Mode {
+    match MODE.load(Ordering::Relaxed) {
+        1 => Mode::Auto,
+        2 => Mode::Daemon,
+        3 => Mode::Direct,
+        _ => std::env::var("M4ARCH_MODE")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or_default(),
+    }
+}
+
+/// Koneksi ke daemon yang sudah melewati handshake.
+pub struct Client {
+    reader: BufReader<UnixStream>,
+    writer: UnixStream,
+    next_id: u64,
+}
+
+impl Client {
+    /// Terhubung ke socket daemon default (`m4arch_ipc::socket_path()`).
+    pub fn connect() -> Result<Self> {
+        Self::connect_to(&crate::socket_path())
+    }
+
+    /// Terhubung ke socket tertentu dan melakukan handshake versi.
+    pub fn connect_to(path: &Path) -> Result<Self> {
+        Self::handshake(UnixStream::connect(path)?)
+    }
+
+    fn handshake(stream: UnixStream) -> Result<Self> {
+        let mut client = Self {
+            reader: BufReader::new(stream.try_clone()?),
+            writer: stream,
+            next_id: 1,
+        };
+        client.request(IpcCommand::Hello {
+            version: PROTOCOL_VERSION,
+        })?;
+        Ok(client)
+    }
+
+    /// Mengirim satu perintah dan menunggu balasannya.
+    pub fn request(&mut self, command: IpcCommand) -> Result<IpcReply> {
+        let id = self.next_id;
+        self.next_id += 1;
+        write_frame(&mut self.writer, &Request { id, command })?;
+
+        let response: Response = read_frame(&mut self.reader)?.ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "m4arch-daemon closed the connection",
+            )
+        })?;
+        if response.id != id {
+            return Err(unexpected_reply());
+        }
+        response.result.map_err(M4ArchError::from)
+    }
+
+    /// Mengubah koneksi ini menjadi aliran `IpcEvent`.
+    pub fn subscribe(mut self) -> Result<EventStream> {
+        match self.request(IpcCommand::Subscribe)? {
+            IpcReply::Subscribed => Ok(EventStream {
+                reader: self.reader,
+            }),
+            _ => Err(unexpected_reply()),
+        }
+    }
+
+    /// Mengirim perintah yang tidak mengembalikan data.
+    fn run(&mut self, command: IpcCommand) -> Result<()> {
+        match self.request(command)? {
+            IpcReply::Done => Ok(()),
+            _ => Err(unexpected_reply()),
+        }
+    }
+}
+
+/// Aliran event dari daemon; berakhir saat daemon menutup koneksi.
+pub struct EventStream {
+    reader: BufReader<UnixStream>,
+}
+
+impl Iterator for EventStream {
+    type Item = Result<IpcEvent>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        read_frame(&mut self.reader)
+            .map_err(M4ArchError::from)
+            .transpose()
+    }
+}
+
+fn unexpected_reply() -> M4ArchError {
+    M4ArchError::Io(io::Error::new(
+        io::ErrorKind::InvalidData,
+        "unexpected reply from m4arch-daemon",
+    ))
+}
+
+/// Memilih daemon atau akses langsung sesuai mode.
+fn dispatch<T>(
+    mode: Mode,
+    socket: &Path,
+    daemon: impl FnOnce(&mut Client) -> Result<T>,
+    direct: impl FnOnce() -> Result<T>,
+) -> Result<T> {
+    match mode {
+        Mode::Direct => direct(),
+        Mode::Daemon => daemon(&mut Client::connect_to(socket).map_err(daemon_unavailable)?),
+        // Fallback hanya jika tidak ada daemon yang mendengarkan. Daemon yang
+        // berjalan tetap memegang hardware meski handshake atau perintahnya
+        // gagal, jadi error-nya diteruskan.
+        Mode::Auto => match UnixStream::connect(socket) {
+            Ok(stream) => daemon(&mut Client::handshake(stream)?),
+            Err(e) if is_not_listening(&e) => direct(),
+            Err(e) => Err(daemon_unavailable(e.into())),
+        },
+    }
+}
+
+/// Apakah perintah berikutnya akan dijalankan lewat daemon, sesuai mode
+/// aktif. Pada mode `Auto` ini hanya mencoba tersambung ke socket.
+///
+/// Tanpa daemon, transisi dijalankan di thread pemanggil sampai selesai dan
+/// tidak bisa dibatalkan, jadi front-end interaktif bisa memilih perubahan
+/// instan.
+pub fn uses_daemon() -> bool {
+    uses_daemon_at(mode(), &crate::socket_path())
+}
+
+fn uses_daemon_at(mode: Mode, socket: &Path) -> bool {
+    match mode {
+        Mode::Direct => false,
+        Mode::Daemon => true,
+        Mode::Auto => match UnixStream::connect(socket) {
+            Ok(_) => true,
+            Err(e) => !is_not_listening(&e),
+        },
+    }
+}
+
+/// Socket tidak ada, atau tertinggal dari daemon yang sudah berhenti.
+fn is_not_listening(e: &io::Error) -> bool {
+    matches!(
+        e.kind(),
+        io::ErrorKind::NotFound | io::ErrorKind::ConnectionRefused
+    )
+}
+
+fn daemon_unavailable(e: M4ArchError) -> M4ArchError {
+    match e {
+        M4ArchError::Io(e) => M4ArchError::Io(io::Error::new(
+            e.kind(),
+            format!("m4arch-daemon is not reachable: {e}"),
+        )),
+        other => other,
+    }
+}
+
+fn with_backend<T>(
+    daemon: impl FnOnce(&mut Client) -> Result<T>,
+    direct: impl FnOnce() -> Result<T>,
+) -> Result<T> {
+    dispatch(mode(), &crate::socket_path(), daemon, direct)
+}
+
+/// Tanpa daemon tidak ada yang mengingat state, jadi mode direct mencatat
+/// perubahan di store milik pengguna. Best-effort: gagal menyimpan tidak
+/// membatalkan perubahan yang sudah diterapkan ke hardware.
+fn remember(change: impl FnOnce(&mut KeyboardState)) {
+    if let Some(store) = SettingsStore::user() {
+        let _ = store.update(|settings| change(&mut settings.keyboard));
+    }
+}
+
+fn user_store() -> Result<SettingsStore> {
+    SettingsStore::user()
+        .ok_or_else(|| M4ArchError::Config("cannot locate the user config directory".to_string()))
+}
+
+fn remember_brightness() {
+    if let Ok(info) = m4arch_service::brightness_info() {
+        remember(|state| state.record_brightness(info));
+    }
+}
+
+// === Facade Functions (sama dengan m4arch_service) ===
+
+pub fn get_brightness() -> Result<u8> {
+    brightness_info().map(|info| info.current)
+}
+
+pub fn get_max_brightness() -> Result<u8> {
+    brightness_info().map(|info| info.max)
+}
+
+pub fn set_brightness(level: u8) -> Result<()> {
+    with_backend(
+        |client| client.run(IpcCommand::SetBrightness(level)),
+        || {
+            m4arch_service::set_brightness(level)?;
+            remember_brightness();
+            Ok(())
+        },
+    )
+}
+
+pub fn increase_brightness(step: u8) -> Result<()> {
+    with_backend(
+        |client| client.run(IpcCommand::IncreaseBrightness(step)),
+        || {
+            m4arch_service::increase_brightness(step)?;
+            remember_brightness();
+            Ok(())
+        },
+    )
+}
+
+pub fn decrease_brightness(step: u8) -> Result<()> {
+    with_backend(
+        |client| client.run(IpcCommand::DecreaseBrightness(step)),
+        || {
+            m4arch_service::decrease_brightness(step)?;
+            remember_brightness();
+            Ok(())
+        },
+    )
+}
+
+pub fn set_brightness_percent(percent: u8) -> Result<()> {
+    with_backend(
+        |client| client.run(IpcCommand::SetBrightnessPercent(percent)),
+        || {
+            m4arch_service::set_brightness_percent(percent)?;
+            remember_brightness();
+            Ok(())
+        },
+    )
+}
+
+/// Satu langkah pada tangga brightness perseptual dengan `steps` langkah,
+/// sehingga hotkey terasa sama di semua hardware.
+pub fn step_brightness(direction: StepDirection, steps: u8) -> Result<()> {
+    with_backend(
+        |client| client.run(IpcCommand::StepBrightness(direction, steps)),
+        || {
+            m4arch_service::step_brightness(direction, steps)?;
+            remember_brightness();
+            Ok(())
+        },
+    )
+}
+
+pub fn brightness_info() -> Result<BrightnessInfo> {
+    with_backend(
+        |client| match client.request(IpcCommand::GetBrightnessInfo)? {
+            IpcReply::BrightnessInfo(info) => Ok(info),
+            _ => Err(unexpected_reply()),
+        },
+        m4arch_service::brightness_info,
+    )
+}
+
+pub fn get_rgb_color() -> Result<(u8, u8, u8)> {
+    with_backend(
+        |client| match client.request(IpcCommand::GetRgb)? {
+            IpcReply::Rgb(rgb) => Ok((rgb.r, rgb.g, rgb.b)),
+            _ => Err(unexpected_reply()),
+        },
+        m4arch_service::get_rgb_color,
+    )
+}
+
+pub fn set_rgb_color(r: u8, g: u8, b: u8) -> Result<()> {
+    with_backend(
+        |client| client.run(IpcCommand::SetRgb(r, g, b)),
+        || {
+            m4arch_service::set_rgb_color(r, g, b)?;
+            remember(|state| state.record_rgb(Rgb { r, g, b }));
+            Ok(())
+        },
+    )
+}
+
+pub fn get_capabilities() -> Result<KeyboardCapabilities> {
+    with_backend(
+        |client| match client.request(IpcCommand::GetCapabilities)? {
+            IpcReply::Capabilities(caps) => Ok(caps),
+            _ => Err(unexpected_reply()),
+        },
+        m4arch_service::get_capabilities,
+    )
+}
+
+pub fn get_zones() -> Result<Vec<KeyboardZone>> {
+    with_backend(
+        |client| match client.request(IpcCommand::GetZones)? {
+            IpcReply::Zones(zones) => Ok(zones),
+            _ => Err(unexpected_reply()),
+        },
+        m4arch_service::get_zones,
+    )
+}
+
+pub fn get_zone_rgb_color(zone: usize) -> Result<(u8, u8, u8)> {
+    with_backend(
+        |client| match client.request(IpcCommand::GetZoneRgb(zone))? {
+            IpcReply::Rgb(rgb) => Ok((rgb.r, rgb.g, rgb.b)),
+            _ => Err(unexpected_reply()),
+        },
+        || m4arch_service::get_zone_rgb_color(zone),
+    )
+}
+
+pub fn set_zone_rgb_color(zone: usize, r: u8, g: u8, b: u8) -> Result<()> {
+    with_backend(
+        |client| client.run(IpcCommand::SetZoneRgb(zone, r, g, b)),
+        || {
+            m4arch_service::set_zone_rgb_color(zone, r, g, b)?;
+            remember(|state| state.record_zone_rgb(zone, Rgb { r, g, b }));
+            Ok(())
+        },
+    )
+}
+
+// === Transisi ===
+//
+// Daemon menganimasikan transisi di thread-nya sendiri sehingga perintah
+// langsung kembali. Dalam mode direct, fungsi ini memblokir selama transisi.
+
+pub fn fade_brightness(level: u8, transition: Transition) -> Result<()> {
+    with_backend(
+        |client| client.run(IpcCommand::Fade(FadeTarget::Brightness(level), transition)),
+        || {
+            let mut state = KeyboardState::default();
+            let max = m4arch_service::get_max_brightness()?;
+            state.record_brightness(BrightnessInfo::new(level.min(max), max));
+            m4arch_service::fade_to_state(&state, transition)?;
+            remember_brightness();
+            Ok(())
+        },
+    )
+}
+
+pub fn fade_rgb_color(r: u8, g: u8, b: u8, transition: Transition) -> Result<()> {
+    let rgb = Rgb { r, g, b };
+    with_backend(
+        |client| client.run(IpcCommand::Fade(FadeTarget::Rgb(rgb), transition)),
+        || {
+            let mut state = KeyboardState::default();
+            state.record_rgb(rgb);
+            m4arch_service::fade_to_state(&state, transition)?;
+            remember(|state| state.record_rgb(rgb));
+            Ok(())
+        },
+    )
+}
+
+pub fn fade_zone_rgb_color(zone: usize, r: u8, g: u8, b: u8, transition: Transition) -> Result<()> {
+    let rgb = Rgb { r, g, b };
+    with_backend(
+        |client| client.run(IpcCommand::Fade(FadeTarget::ZoneRgb(zone, rgb), transition)),
+        || {
+            let keyboard_zones = m4arch_service::get_zones()?;
+            if zone >= keyboard_zones.len() {
+                return Err(M4ArchError::InvalidZone(zone));
+            }
+            let mut state = KeyboardState::default();
+            state.record_zone_rgb(zone, rgb);
+            m4arch_service::fade_to_state(&state, transition)?;
+            remember(|state| state.record_zone_rgb(zone, rgb));
+            Ok(())
+        },
+    )
+}
+
+pub fn fade_to_profile(name: &str, transition: Transition) -> Result<()> {
+    with_backend(
+        |client| {
+            let target = FadeTarget::Profile(name.to_string());
+            client.run(IpcCommand::Fade(target, transition))
+        },
+        || {
+            let store = user_store()?;
+            let mut settings = store.load()?;
+            let state = settings.profile(name)?.keyboard.clone();
+            m4arch_service::fade_to_state(&state, transition)?;
+            settings.keyboard = state;
+            store.save(&settings)
+        },
+    )
+}
+
+/// Menerapkan ulang brightness dan warna yang terakhir disimpan.
+pub fn restore() -> Result<()> {
+    with_backend(
+        |client| client.run(IpcCommand::Restore),
+        || m4arch_service::restore_state(&user_store()?.load()?.keyboard),
+    )
+}
+
+/// Memulai efek animasi. Efek dirender terus-menerus oleh daemon, jadi
+/// tidak tersedia dalam mode direct.
+pub fn set_effect(effect: Effect) -> Result<()> {
+    with_backend(
+        |client| client.run(IpcCommand::SetEffect(effect)),
+        || {
+            Err(M4ArchError::Unsupported(
+                "lighting effects require m4arch-daemon".to_string(),
+            ))
+        },
+    )
+}
+
+pub fn stop_effect() -> Result<()> {
+    // Tanpa daemon tidak ada efek yang berjalan
+    with_backend(|client| client.run(IpcCommand::StopEffect), || Ok(()))
+}
+
+pub fn get_effect() -> Result<Option<Effect>> {
+    with_backend(
+        |client| match client.request(IpcCommand::GetEffect)? {
+            IpcReply::Effect(effect) => Ok(effect),
+            _ => Err(unexpected_reply()),
+        },
+        || Ok(None),
+    )
+}
+
+/// Mode firmware yang didukung; kosong jika keyboard tidak memilikinya.
+pub fn keyboard_modes() -> Result<Vec<KeyboardMode>> {
+    with_backend(
+        |client| match client.request(IpcCommand::ListModes)? {
+            IpcReply::Modes(modes) => Ok(modes),
+            _ => Err(unexpected_reply()),
+        },
+        m4arch_service::keyboard_modes,
+    )
+}
+
+pub fn get_keyboard_mode() -> Result<KeyboardMode> {
+    with_backend(
+        |client| match client.request(IpcCommand::GetMode)? {
+            IpcReply::Mode(mode) => Ok(mode),
+            _ => Err(unexpected_reply()),
+        },
+        m4arch_service::get_keyboard_mode,
+    )
+}
+
+pub fn set_keyboard_mode(mode: KeyboardMode) -> Result<()> {
+    with_backend(
+        |client| client.run(IpcCommand::SetMode(mode)),
+        || {
+            m4arch_service::set_keyboard_mode(mode)?;
+            remember(|state| state.record_mode(mode));
+            Ok(())
+        },
+    )
+}
+
+/// Pengaturan timeout idle. Tanpa daemon tidak ada yang memantau aktivitas,
+/// jadi mode direct selalu melaporkan pengaturan default (nonaktif).
+pub fn get_idle_settings() -> Result<IdleSettings> {
+    with_backend(
+        |client| match client.request(IpcCommand::GetIdle)? {
+            IpcReply::Idle(idle) => Ok(idle),
+            _ => Err(unexpected_reply()),
+        },
+        || Ok(IdleSettings::default()),
+    )
+}
+
+pub fn set_idle_settings(idle: IdleSettings) -> Result<()> {
+    with_backend(
+        |client| client.run(IpcCommand::SetIdle(idle)),
+        || {
+            Err(M4ArchError::Unsupported(
+                "idle timeout requires m4arch-daemon".to_string(),
+            ))
+        },
+    )
+}
+
+/// Jadwal harian. Tanpa daemon tidak ada yang menjalankan jadwal, jadi mode
+/// direct selalu melaporkan jadwal kosong.
+pub fn get_schedule() -> Result<Schedule> {
+    with_backend(
+        |client| match client.request(IpcCommand::GetSchedule)? {
+            IpcReply::Schedule(schedule) => Ok(schedule),
+            _ => Err(unexpected_reply()),
+        },
+        || Ok(Schedule::default()),
+    )
+}
+
+pub fn set_schedule(schedule: Schedule) -> Result<()> {
+    with_backend(
+        |client| client.run(IpcCommand::SetSchedule(schedule)),
+        || {
+            Err(M4ArchError::Unsupported(
+                "schedules require m4arch-daemon".to_string(),
+            ))
+        },
+    )
+}
+
+/// Kalibrasi warna. Hanya daemon yang menerapkan kalibrasi, jadi mode direct
+/// selalu melaporkan kalibrasi netral.
+pub fn get_calibration() -> Result<Calibration> {
+    with_backend(
+        |client| match client.request(IpcCommand::GetCalibration)? {
+            IpcReply::Calibration(calibration) => Ok(calibration),
+            _ => Err(unexpected_reply()),
+        },
+        || Ok(Calibration::default()),
+    )
+}
+
+pub fn set_calibration(calibration: Calibration) -> Result<()> {
+    with_backend(
+        |client| client.run(IpcCommand::SetCalibration(calibration)),
+        || {
+            Err(M4ArchError::Unsupported(
+                "colour calibration requires m4arch-daemon".to_string(),
+            ))
+        },
+    )
+}
+
+/// Menampilkan `rgb` dengan kalibrasi percobaan; `restore()` mengakhirinya.
+pub fn preview_calibration(calibration: Calibration, rgb: Rgb) -> Result<()> {
+    with_backend(
+        |client| client.run(IpcCommand::PreviewCalibration(calibration, rgb)),
+        || {
+            Err(M4ArchError::Unsupported(
+                "colour calibration requires m4arch-daemon".to_string(),
+            ))
+        },
+    )
+}
+
+/// Kecepatan dan suhu setiap kipas.
+pub fn get_fans() -> Result<Vec<FanInfo>> {
+    with_backend(
+        |client| match client.request(IpcCommand::Fan(FanCommand::GetInfo))? {
+            IpcReply::Fans(fans) => Ok(fans),
+            _ => Err(unexpected_reply()),
+        },
+        m4arch_service::fan_info,
+    )
+}
+
+/// Pengaturan kurva kipas. Tanpa daemon tidak ada yang menjalankan kurva,
+/// jadi mode direct selalu melaporkan kipas otomatis.
+pub fn get_fan_settings() -> Result<FanSettings> {
+    with_backend(
+        |client| match client.request(IpcCommand::Fan(FanCommand::GetSettings))? {
+            IpcReply::FanSettings(settings) => Ok(settings),
+            _ => Err(unexpected_reply()),
+        },
+        || Ok(FanSettings::default()),
+    )
+}
+
+/// Menyimpan pengaturan kipas. Mode direct hanya bisa mengembalikan kipas
+/// ke firmware.
+pub fn set_fan_settings(settings: FanSettings) -> Result<()> {
+    let auto = settings.mode == FanMode::Auto;
+    with_backend(
+        |client| client.run(IpcCommand::Fan(FanCommand::SetSettings(settings))),
+        || {
+            if !auto {
+                return Err(M4ArchError::Unsupported(
+                    "fan curves require m4arch-daemon".to_string(),
+                ));
+            }
+            m4arch_service::set_fans_auto()
+        },
+    )
+}
+
+/// Profil performa yang didukung perangkat.
+pub fn list_power_profiles() -> Result<Vec<PowerProfile>> {
+    with_backend(
+        |client| match client.request(IpcCommand::PowerProfile(PowerProfileCommand::List))? {
+            IpcReply::PowerProfiles(profiles) => Ok(profiles),
+            _ => Err(unexpected_reply()),
+        },
+        m4arch_service::list_power_profiles,
+    )
+}
+
+pub fn get_power_profile() -> Result<PowerProfile> {
+    with_backend(
+        |client| match client.request(IpcCommand::PowerProfile(PowerProfileCommand::Get))? {
+            IpcReply::PowerProfile(profile) => Ok(profile),
+            _ => Err(unexpected_reply()),
+        },
+        m4arch_service::get_power_profile,
+    )
+}
+
+/// Mengganti profil performa. Lewat daemon, pilihan ini juga disimpan dan
+/// diterapkan ulang saat boot dan resume.
+pub fn set_power_profile(profile: PowerProfile) -> Result<()> {
+    with_backend(
+        |client| client.run(IpcCommand::PowerProfile(PowerProfileCommand::Set(profile))),
+        || m4arch_service::set_power_profile(profile),
+    )
+}
+
+pub fn get_charge_thresholds() -> Result<ChargeThresholds> {
+    with_backend(
+        |client| match client.request(IpcCommand::Charge(ChargeCommand::Get))? {
+            IpcReply::ChargeThresholds(thresholds) => Ok(thresholds),
+            _ => Err(unexpected_reply()),
+        },
+        m4arch_service::get_charge_thresholds,
+    )
+}
+
+/// Mengubah ambang pengisian baterai. Lewat daemon, ambang ini disimpan dan
+/// diterapkan ulang saat boot.
+pub fn set_charge_thresholds(thresholds: ChargeThresholds) -> Result<()> {
+    with_backend(
+        |client| client.run(IpcCommand::Charge(ChargeCommand::Set(thresholds))),
+        || m4arch_service::set_charge_thresholds(thresholds),
+    )
+}
+
+/// Keadaan dan kesehatan semua baterai sistem; kosong jika tidak ada baterai.
+pub fn battery_info() -> Result<Vec<BatteryInfo>> {
+    with_backend(
+        |client| match client.request(IpcCommand::Charge(ChargeCommand::Batteries))? {
+            IpcReply::Batteries(batteries) => Ok(batteries),
+            _ => Err(unexpected_reply()),
+        },
+        m4arch_service::battery_info,
+    )
+}
+
+/// Governor, EPP dan turbo setiap policy CPU.
+pub fn cpu_status() -> Result<CpuStatus> {
+    with_backend(
+        |client| match client.request(IpcCommand::Cpu(CpuCommand::Status))? {
+            IpcReply::CpuStatus(status) => Ok(status),
+            _ => Err(unexpected_reply()),
+        },
+        m4arch_service::cpu_status,
+    )
+}
+
+/// Mengubah tuning CPU. Lewat daemon, perubahan ini disimpan sampai profil
+/// performa diganti; mode direct hanya menulisnya ke sysfs.
+pub fn tune_cpu(tuning: CpuTuning) -> Result<()> {
+    with_backend(
+        |client| client.run(IpcCommand::Cpu(CpuCommand::Tune(tuning.clone()))),
+        || m4arch_service::tune_cpu(&tuning),
+    )
+}
+
+/// Menyimpan brightness dan warna saat ini sebagai profil `name`.
+pub fn save_profile(name: &str) -> Result<()> {
+    with_backend(
+        |client| client.run(IpcCommand::SaveProfile(name.to_string())),
+        || {
+            let profile = Profile {
+                keyboard: m4arch_service::current_state()?,
+            };
+            let store = user_store()?;
+            let mut settings = store.load()?;
+            settings.save_profile(name, profile)?;
+            store.save(&settings)
+        },
+    )
+}
+
+pub fn apply_profile(name: &str) -> Result<()> {
+    with_backend(
+        |client| client.run(IpcCommand::ApplyProfile(name.to_string())),
+        || {
+            let store = user_store()?;
+            let mut settings = store.load()?;
+            let state = settings.profile(name)?.keyboard.clone();
+            m4arch_service::restore_state(&state)?;
+            settings.keyboard = state;
+            store.save(&settings)
+        },
+    )
+}
+
+pub fn delete_profile(name: &str) -> Result<()> {
+    with_backend(
+        |client| client.run(IpcCommand::DeleteProfile(name.to_string())),
+        || {
+            let store = user_store()?;
+            let mut settings = store.load()?;
+            settings.remove_profile(name)?;
+            store.save(&settings)
+        },
+    )
+}
+
+pub fn list_profiles() -> Result<Vec<String>> {
+    with_backend(
+        |client| match client.request(IpcCommand::ListProfiles)? {
+            IpcReply::Profiles(names) => Ok(names),
+            _ => Err(unexpected_reply()),
+        },
+        || Ok(user_store()?.load()?.profile_names()),
+    )
+}
+
+/// Berlangganan perubahan state hardware; hanya tersedia lewat daemon.
+pub fn subscribe() -> Result<EventStream> {
+    if mode() == Mode::Direct {
+        return Err(M4ArchError::Unsupported(
+            "live events require m4arch-daemon".to_string(),
+        ));
+    }
+    Client::connect().map_err(daemon_unavailable)?.subscribe()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::IpcError;
+    use m4arch_core::keyboard::Rgb;
+    use std::os::unix::net::UnixListener;
+    use std::thread;
+
+    /// Daemon tiruan yang menjawab satu koneksi.
+    fn fake_daemon(
+        dir: &Path,
+        reply: impl Fn(IpcCommand) -> std::result::Result<IpcReply, IpcError> + Send + 'static,
+    ) -> std::path::PathBuf {
+        let path = dir.join("m4arch.sock");
+        let listener = UnixListener::bind(&path).unwrap();
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            while let Some(request) = read_frame::<_, Request>(&mut stream).unwrap() {
+                let result = match request.command {
+                    IpcCommand::Hello { .. } => Ok(IpcReply::Hello {
+                        version: PROTOCOL_VERSION,
+                    }),
+                    command => reply(command),
+                };
+                let response = Response {
+                    id: request.id,
+                    result,
+                };
+                write_frame(&mut stream, &response).unwrap();
+            }
+        });
+        path
+    }
+
+    #[test]
+    fn test_parse_mode() {
+        assert_eq!("auto".parse(), Ok(Mode::Auto));
+        assert_eq!("Daemon".parse(), Ok(Mode::Daemon));
+        assert_eq!("direct".parse(), Ok(Mode::Direct));
+        assert!("sysfs".parse::<Mode>().is_err());
+    }
+
+    #[test]
+    fn test_request_through_daemon() {
+        let dir = tempfile::tempdir().unwrap();
+        let socket = fake_daemon(dir.path(), |command| match command {
+            IpcCommand::GetRgb => Ok(IpcReply::Rgb(Rgb { r: 1, g: 2, b: 3 })),
+            IpcCommand::GetZoneRgb(zone) => Err(IpcError::InvalidZone(zone)),
+            _ => Ok(IpcReply::Done),
+        });
+
+        let mut client = Client::connect_to(&socket).unwrap();
+        assert_eq!(
+            client.request(IpcCommand::GetRgb).unwrap(),
+            IpcReply::Rgb(Rgb { r: 1, g: 2, b: 3 })
+        );
+        client.run(IpcCommand::SetBrightness(3)).unwrap();
+        assert!(matches!(
+            client.request(IpcCommand::GetZoneRgb(5)),
+            Err(M4ArchError::InvalidZone(5))
+        ));
+    }
+
+    #[test]
+    fn test_subscribe_streams_events() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("m4arch.sock");
+        let listener = UnixListener::bind(&path).unwrap();
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            for _ in 0..2 {
+                let request: Request = read_frame(&mut stream).unwrap().unwrap();
+                let result = match request.command {
+                    IpcCommand::Subscribe => Ok(IpcReply::Subscribed),
+                    _ => Ok(IpcReply::Hello {
+                        version: PROTOCOL_VERSION,
+                    }),
+                };
+                write_frame(
+                    &mut stream,
+                    &Response {
+                        id: request.id,
+                        result,
+                    },
+                )
+                .unwrap();
+            }
+            write_frame(&mut stream, &IpcEvent::PowerChanged(false)).unwrap();
+            write_frame(&mut stream, &IpcEvent::DriverRemoved).unwrap();
+        });
+
+        let events: Vec<IpcEvent> = Client::connect_to(&path)
+            .unwrap()
+            .subscribe()
+            .unwrap()
+            .map(|event| event.unwrap())
+            .collect();
+        assert_eq!(
+            events,
+            [IpcEvent::PowerChanged(false), IpcEvent::DriverRemoved]
+        );
+    }
+
+    #[test]
+    fn test_auto_uses_daemon_when_running() {
+        let dir = tempfile::tempdir().unwrap();
+        let socket = fake_daemon(dir.path(), |_| Ok(IpcReply::Done));
+        let used = dispatch(
+            Mode::Auto,
+            &socket,
+            |client| client.run(IpcCommand::Power(true)).map(|_| "daemon"),
+            || Ok("direct"),
+        );
+        assert_eq!(used.unwrap(), "daemon");
+    }
+
+    #[test]
+    fn test_auto_falls_back_without_daemon() {
+        let dir = tempfile::tempdir().unwrap();
+        let missing = dir.path().join("missing.sock");
+        let used = dispatch(Mode::Auto, &missing, |_| Ok("daemon"), || Ok("direct"));
+        assert_eq!(used.unwrap(), "direct");
+
+        let forced = dispatch(Mode::Daemon, &missing, |_| Ok("daemon"), || Ok("direct"));
+        assert!(forced.unwrap_err().to_string().contains("not reachable"));
+
+        // Socket yang tertinggal setelah daemon berhenti
+        let stale = dir.path().join("stale.sock");
+        drop(UnixListener::bind(&stale).unwrap());
+        let used = dispatch(Mode::Auto, &stale, |_| Ok("daemon"), || Ok("direct"));
+        assert_eq!(used.unwrap(), "direct");
+    }
+
+    #[test]
+    fn test_auto_reports_incompatible_daemon() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("m4arch.sock");
+        let listener = UnixListener::bind(&path).unwrap();
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let request: Request = read_frame(&mut stream).unwrap().unwrap();
+            let result = match request.command {
+                IpcCommand::Hello { version } => Err(IpcError::VersionMismatch {
+                    client: version,
+                    daemon: PROTOCOL_VERSION + 1,
+                }),
+                _ => Err(IpcError::HandshakeRequired),
+            };
+            write_frame(
+                &mut stream,
+                &Response {
+                    id: request.id,
+                    result,
+                },
+            )
+            .unwrap();
+        });
+
+        let used = dispatch(Mode::Auto, &path, |_| Ok("daemon"), || Ok("direct"));
+        assert!(used.unwrap_err().to_string().contains("protocol mismatch"));
+    }
+
+    #[test]
+    fn test_uses_daemon() {
+        let dir = tempfile::tempdir().unwrap();
+        let missing = dir.path().join("missing.sock");
+        assert!(!uses_daemon_at(Mode::Auto, &missing));
+        assert!(uses_daemon_at(Mode::Daemon, &missing));
+
+        let socket = fake_daemon(dir.path(), |_| Ok(IpcReply::Done));
+        assert!(uses_daemon_at(Mode::Auto, &socket));
+        assert!(!uses_daemon_at(Mode::Direct, &socket));
+    }
+
+    #[test]
+    fn test_direct_skips_daemon() {
+        let dir = tempfile::tempdir().unwrap();
+        let socket = fake_daemon(dir.path(), |_| Ok(IpcReply::Done));
+        let used = dispatch(Mode::Direct, &socket, |_| Ok("daemon"), || Ok("direct"));
+        assert_eq!(used.unwrap(), "direct");
+    }
+}
@@ -0,0 +1,97 @@
+//! Framing pesan: panjang `u32` big-endian diikuti JSON sebanyak itu.
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::io::{self, Read, Write};
+
+/// Batas ukuran satu frame agar peer yang rusak tidak menghabiskan memori.
+pub const MAX_FRAME_LEN: usize = 1024 * 1024;
+
+/// Menulis satu pesan sebagai frame.
+pub fn write_frame<W: Write, T: Serialize>(writer: &mut W, message: &T) -> io::Result<()> {
+    let payload = serde_json::to_vec(message)?;
+    if payload.len() > MAX_FRAME_LEN {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "IPC frame too large",
+        ));
+    }
+    writer.write_all(&(payload.len() as u32).to_be_bytes())?;
+    writer.write_all(&payload)?;
+    writer.flush()
+}
+
+/// Membaca satu frame; `Ok(None)` jika koneksi ditutup di antara frame.
+pub fn read_frame<R: Read, T: DeserializeOwned>(reader: &mut R) -> io::Result<Option<T>> {
+    let mut header = [0u8; 4];
+    match reader.read_exact(&mut header) {
+        Ok(()) => {}
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e),
+    }
+
+    let len = u32::from_be_bytes(header) as usize;
+    if len > MAX_FRAME_LEN {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("IPC frame of {len} bytes exceeds limit"),
+        ));
+    }
+
+    let mut payload = vec![0u8; len];
+    reader.read_exact(&mut payload)?;
+    serde_json::from_slice(&payload)
+        .map(Some)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::{IpcCommand, Request};
+    use std::io::Cursor;
+
+    #[test]
+    fn test_frame_round_trip() {
+        let requests = [
+            Request {
+                id: 1,
+                command: IpcCommand::SetRgb(1, 2, 3),
+            },
+            Request {
+                id: 2,
+                command: IpcCommand::GetZones,
+            },
+        ];
+        let mut buffer = Vec::new();
+        for request in &requests {
+            write_frame(&mut buffer, request).unwrap();
+        }
+
+        let mut reader = Cursor::new(buffer);
+        for request in &requests {
+            let read: Request = read_frame(&mut reader).unwrap().unwrap();
+            assert_eq!(&read, request);
+        }
+        assert!(read_frame::<_, Request>(&mut reader).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_rejects_oversized_frame() {
+        let header = ((MAX_FRAME_LEN + 1) as u32).to_be_bytes();
+        let err = read_frame::<_, Request>(&mut Cursor::new(header)).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_rejects_truncated_and_invalid_frames() {
+        let mut truncated = 10u32.to_be_bytes().to_vec();
+        truncated.extend_from_slice(b"{}");
+        let err = read_frame::<_, Request>(&mut Cursor::new(truncated)).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+
+        let mut invalid = 3u32.to_be_bytes().to_vec();
+        invalid.extend_from_slice(b"{x}");
+        let err = read_frame::<_, Request>(&mut Cursor::new(invalid)).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
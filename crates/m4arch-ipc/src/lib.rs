@@ -1 +1,15 @@
+use std::path::PathBuf;
+
+pub mod client;
+pub mod codec;
 pub mod protocol;
+
+/// Lokasi socket Unix daemon (dibuat oleh systemd lewat `RuntimeDirectory`).
+pub const DEFAULT_SOCKET_PATH: &str = "/run/m4arch/m4arch.sock";
+
+/// Path socket daemon; bisa diganti lewat variabel lingkungan `M4ARCH_SOCKET`.
+pub fn socket_path() -> PathBuf {
+    std::env::var_os("M4ARCH_SOCKET")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_SOCKET_PATH))
+}
@@ -1,5 +1,714 @@
+//! Format pesan antara front-end (CLI/GUI) dan daemon.
+//!
+//! Setiap koneksi diawali `IpcCommand::Hello` untuk mencocokkan versi
+//! protokol. Setelah itu klien mengirim `Request` dan daemon membalas
+//! `Response` dengan `id` yang sama. Pesan dikirim lewat `codec`.
+//!
+//! Setelah `IpcCommand::Subscribe` dibalas `IpcReply::Subscribed`, koneksi
+//! tersebut hanya berisi frame `IpcEvent` dari daemon.
+use m4arch_core::battery::{BatteryInfo, ChargeThresholds};
+use m4arch_core::config::{Calibration, FanSettings, IdleSettings, Schedule};
+use m4arch_core::cpu::{CpuStatus, CpuTuning};
+use m4arch_core::error::M4ArchError;
+use m4arch_core::fan::FanInfo;
+use m4arch_core::keyboard::{
+    BrightnessInfo, Effect, KeyboardCapabilities, KeyboardMode, KeyboardZone, Rgb, StepDirection,
+    Transition,
+};
+use m4arch_core::power::PowerProfile;
+use serde::{Deserialize, Serialize};
+use std::io;
+
+/// Versi protokol.
+///
+/// Naikkan setiap kali varian `IpcCommand`, `IpcReply`, `IpcEvent` atau
+/// `IpcError` (beserta isinya) ditambah, diubah atau dihapus. Penambahan
+/// juga dihitung: daemon lama tidak bisa men-decode varian baru, dan tanpa
+/// kenaikan versi klien baru justru gagal setelah handshake, bukan dengan
+/// `IpcError::VersionMismatch`.
+///
+/// Versi 1 adalah protokol awal; versi 2 mencakup semua perintah, balasan,
+/// event dan error yang ditambahkan sesudahnya dalam seri yang sama; versi 3
+/// menambahkan mode firmware keyboard. `test_version_covers_wire_enums`
+/// menjaga aturan ini.
+pub const PROTOCOL_VERSION: u32 = 3;
+
+/// Perintah yang dikirim front-end (CLI/GUI) ke daemon.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum IpcCommand {
+    /// Handshake; wajib menjadi perintah pertama di setiap koneksi
+    Hello {
+        version: u32,
+    },
+
+    // === Perintah yang mengubah hardware ===
     SetBrightness(u8),
+    IncreaseBrightness(u8),
+    DecreaseBrightness(u8),
+    /// Brightness dalam persen dari brightness maksimum
+    SetBrightnessPercent(u8),
+    /// Satu langkah pada tangga brightness perseptual dengan N langkah
+    StepBrightness(StepDirection, u8),
     SetRgb(u8, u8, u8),
+    SetZoneRgb(usize, u8, u8, u8),
     Power(bool),
+    /// Menerapkan ulang state tersimpan (misalnya dari hook resume)
+    Restore,
+    /// Berpindah ke target secara halus; dibatalkan oleh perintah berikutnya
+    Fade(FadeTarget, Transition),
+
+    // === Efek animasi (dijalankan oleh daemon) ===
+    SetEffect(Effect),
+    /// Menghentikan efek dan kembali ke warna statis
+    StopEffect,
+    GetEffect,
+
+    // === Mode bawaan firmware ===
+    /// Mode yang didukung keyboard; kosong jika tidak ada
+    ListModes,
+    GetMode,
+    /// Mode selain `Static` menghentikan efek animasi
+    SetMode(KeyboardMode),
+
+    // === Timeout idle (dijalankan oleh daemon) ===
+    GetIdle,
+    SetIdle(IdleSettings),
+
+    // === Jadwal harian (dijalankan oleh daemon) ===
+    GetSchedule,
+    SetSchedule(Schedule),
+
+    // === Kalibrasi warna (diterapkan oleh daemon) ===
+    GetCalibration,
+    SetCalibration(Calibration),
+    /// Menampilkan warna dengan kalibrasi percobaan tanpa menyimpan apa pun;
+    /// `Restore` mengembalikan state tersimpan
+    PreviewCalibration(Calibration, Rgb),
+
+    // === Kipas (dijalankan oleh daemon) ===
+    Fan(FanCommand),
+
+    // === Profil performa ===
+    PowerProfile(PowerProfileCommand),
+    Cpu(CpuCommand),
+
+    // === Baterai ===
+    Charge(ChargeCommand),
+
+    // === Profil ===
+    /// Menyimpan state keyboard saat ini sebagai profil bernama
+    SaveProfile(String),
+    ApplyProfile(String),
+    DeleteProfile(String),
+    ListProfiles,
+
+    // === Query ===
+    GetBrightnessInfo,
+    GetRgb,
+    GetZoneRgb(usize),
+    GetZones,
+    GetCapabilities,
+
+    /// Berlangganan `IpcEvent`; koneksi ini tidak lagi menerima perintah lain
+    Subscribe,
+}
+
+impl IpcCommand {
+    /// Perintah yang hanya membaca state, sehingga tidak membatalkan
+    /// transisi yang sedang berjalan.
+    pub fn is_query(&self) -> bool {
+        matches!(
+            self,
+            IpcCommand::Hello { .. }
+                | IpcCommand::GetEffect
+                | IpcCommand::ListModes
+                | IpcCommand::GetMode
+                | IpcCommand::GetIdle
+                | IpcCommand::GetSchedule
+                | IpcCommand::GetCalibration
+                | IpcCommand::Fan(FanCommand::GetInfo | FanCommand::GetSettings)
+                | IpcCommand::PowerProfile(PowerProfileCommand::List | PowerProfileCommand::Get)
+                | IpcCommand::Cpu(CpuCommand::Status)
+                | IpcCommand::Charge(ChargeCommand::Get | ChargeCommand::Batteries)
+                | IpcCommand::ListProfiles
+                | IpcCommand::GetBrightnessInfo
+                | IpcCommand::GetRgb
+                | IpcCommand::GetZoneRgb(_)
+                | IpcCommand::GetZones
+                | IpcCommand::GetCapabilities
+                | IpcCommand::Subscribe
+        )
+    }
+
+    /// Perintah yang mengubah hardware platform sebagai root, sehingga
+    /// daemon hanya menerimanya dari peer yang berhak. Pencahayaan keyboard
+    /// tetap terbuka untuk semua pengguna.
+    pub fn requires_privilege(&self) -> bool {
+        matches!(
+            self,
+            IpcCommand::Fan(FanCommand::SetSettings(_))
+                | IpcCommand::PowerProfile(PowerProfileCommand::Set(_))
+                | IpcCommand::Cpu(CpuCommand::Tune(_))
+                | IpcCommand::Charge(ChargeCommand::Set(_))
+        )
+    }
+}
+
+/// Perintah kipas; tetap dijalankan meski keyboard tidak ditemukan.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FanCommand {
+    /// Kecepatan dan suhu setiap kipas
+    GetInfo,
+    GetSettings,
+    SetSettings(FanSettings),
+}
+
+/// Perintah profil performa; tetap dijalankan meski keyboard tidak ditemukan.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PowerProfileCommand {
+    /// Profil yang didukung perangkat
+    List,
+    Get,
+    Set(PowerProfile),
+}
+
+/// Perintah tuning CPU; tetap dijalankan meski keyboard tidak ditemukan.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CpuCommand {
+    Status,
+    /// Menimpa tuning bawaan profil performa sampai profil diganti
+    Tune(CpuTuning),
+}
+
+/// Perintah baterai; tetap dijalankan meski keyboard tidak ditemukan.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChargeCommand {
+    Get,
+    Set(ChargeThresholds),
+    /// Keadaan dan kesehatan semua baterai sistem
+    Batteries,
+}
+
+/// Tujuan `IpcCommand::Fade`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FadeTarget {
+    Brightness(u8),
+    Rgb(Rgb),
+    ZoneRgb(usize, Rgb),
+    Profile(String),
+}
+
+impl FadeTarget {
+    /// Perintah instan yang setara, dijalankan saat transisi selesai.
+    pub fn command(&self) -> IpcCommand {
+        match self {
+            FadeTarget::Brightness(level) => IpcCommand::SetBrightness(*level),
+            FadeTarget::Rgb(rgb) => IpcCommand::SetRgb(rgb.r, rgb.g, rgb.b),
+            FadeTarget::ZoneRgb(zone, rgb) => IpcCommand::SetZoneRgb(*zone, rgb.r, rgb.g, rgb.b),
+            FadeTarget::Profile(name) => IpcCommand::ApplyProfile(name.clone()),
+        }
+    }
+}
+
+/// Hasil sukses sebuah perintah.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum IpcReply {
+    Hello {
+        version: u32,
+    },
+    /// Perintah berhasil tanpa data balasan
+    Done,
+    BrightnessInfo(BrightnessInfo),
+    Rgb(Rgb),
+    Zones(Vec<KeyboardZone>),
+    Capabilities(KeyboardCapabilities),
+    /// Nama profil yang tersimpan, terurut
+    Profiles(Vec<String>),
+    /// Efek yang sedang berjalan, jika ada
+    Effect(Option<Effect>),
+    /// Mode firmware yang didukung
+    Modes(Vec<KeyboardMode>),
+    Mode(KeyboardMode),
+    Idle(IdleSettings),
+    Schedule(Schedule),
+    Calibration(Calibration),
+    Fans(Vec<FanInfo>),
+    FanSettings(FanSettings),
+    PowerProfiles(Vec<PowerProfile>),
+    PowerProfile(PowerProfile),
+    CpuStatus(CpuStatus),
+    ChargeThresholds(ChargeThresholds),
+    Batteries(Vec<BatteryInfo>),
+    /// Langganan aktif; frame berikutnya berupa `IpcEvent`
+    Subscribed,
+}
+
+/// Perubahan state hardware yang disiarkan daemon ke semua pelanggan.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum IpcEvent {
+    BrightnessChanged(BrightnessInfo),
+    /// Warna berubah; `zone` kosong berarti semua zona
+    ColorChanged {
+        zone: Option<usize>,
+        rgb: Rgb,
+    },
+    /// Backlight dinyalakan atau dimatikan
+    PowerChanged(bool),
+    /// Profil pencahayaan diterapkan
+    ProfileChanged(String),
+    /// Efek dimulai, diganti atau dihentikan (`None`)
+    EffectChanged(Option<Effect>),
+    /// Mode firmware diganti
+    ModeChanged(KeyboardMode),
+    /// Keyboard yang didukung muncul (misalnya modul driver dimuat)
+    DriverAdded(KeyboardCapabilities),
+    /// Keyboard tidak lagi tersedia
+    DriverRemoved,
+    /// Profil performa diganti
+    PowerProfileChanged(PowerProfile),
+}
+
+/// Error yang dikirim lewat socket.
+///
+/// Varian awal mencerminkan `M4ArchError`; sisanya adalah error protokol.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum IpcError {
+    Io(String),
+    InvalidValue,
+    PermissionDenied,
+    Unsupported(String),
+    InvalidZone(usize),
+    UnexpectedChannels(String),
+    Config(String),
+    UnknownProfile(String),
+
+    /// Daemon tidak menemukan keyboard yang didukung
+    NoKeyboard,
+    /// Versi protokol klien dan daemon berbeda
+    VersionMismatch {
+        client: u32,
+        daemon: u32,
+    },
+    /// Perintah dikirim sebelum `Hello`
+    HandshakeRequired,
+    /// Pesan tidak bisa di-decode
+    Malformed(String),
+}
+
+/// Permintaan dari klien.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Request {
+    pub id: u64,
+    pub command: IpcCommand,
+}
+
+/// Balasan daemon untuk `Request` dengan `id` yang sama.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Response {
+    pub id: u64,
+    pub result: Result<IpcReply, IpcError>,
+}
+
+impl From<&M4ArchError> for IpcError {
+    fn from(e: &M4ArchError) -> Self {
+        match e {
+            M4ArchError::Io(e) => IpcError::Io(e.to_string()),
+            M4ArchError::InvalidValue => IpcError::InvalidValue,
+            M4ArchError::PermissionDenied => IpcError::PermissionDenied,
+            M4ArchError::Unsupported(what) => IpcError::Unsupported(what.clone()),
+            M4ArchError::InvalidZone(zone) => IpcError::InvalidZone(*zone),
+            M4ArchError::UnexpectedChannels(found) => IpcError::UnexpectedChannels(found.clone()),
+            M4ArchError::Config(message) => IpcError::Config(message.clone()),
+            M4ArchError::UnknownProfile(name) => IpcError::UnknownProfile(name.clone()),
+        }
+    }
+}
+
+impl From<M4ArchError> for IpcError {
+    fn from(e: M4ArchError) -> Self {
+        IpcError::from(&e)
+    }
+}
+
+impl From<IpcError> for M4ArchError {
+    fn from(e: IpcError) -> Self {
+        match e {
+            IpcError::Io(message) => M4ArchError::Io(io::Error::other(message)),
+            IpcError::InvalidValue => M4ArchError::InvalidValue,
+            IpcError::PermissionDenied => M4ArchError::PermissionDenied,
+            IpcError::Unsupported(what) => M4ArchError::Unsupported(what),
+            IpcError::InvalidZone(zone) => M4ArchError::InvalidZone(zone),
+            IpcError::UnexpectedChannels(found) => M4ArchError::UnexpectedChannels(found),
+            IpcError::Config(message) => M4ArchError::Config(message),
+            IpcError::UnknownProfile(name) => M4ArchError::UnknownProfile(name),
+            IpcError::NoKeyboard => M4ArchError::Io(io::Error::new(
+                io::ErrorKind::NotFound,
+                "No supported keyboard driver found",
+            )),
+            IpcError::VersionMismatch { client, daemon } => M4ArchError::Io(io::Error::other(
+                format!("IPC protocol mismatch (client v{client}, daemon v{daemon})"),
+            )),
+            IpcError::HandshakeRequired => {
+                M4ArchError::Io(io::Error::other("IPC handshake required"))
+            }
+            IpcError::Malformed(message) => M4ArchError::Io(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Malformed IPC message: {message}"),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use m4arch_core::battery::BatteryState;
+    use m4arch_core::config::{FanMode, Location, ScheduleAction, ScheduleEntry, ScheduleTime};
+    use m4arch_core::cpu::CpuPolicy;
+    use m4arch_core::fan::Fan;
+    use m4arch_core::keyboard::{Easing, EffectKind};
+
+    fn round_trip<T>(value: &T) -> T
+    where
+        T: Serialize + for<'de> Deserialize<'de>,
+    {
+        serde_json::from_str(&serde_json::to_string(value).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_request_round_trip() {
+        let commands = [
+            IpcCommand::Hello {
+                version: PROTOCOL_VERSION,
+            },
+            IpcCommand::SetBrightness(7),
+            IpcCommand::IncreaseBrightness(1),
+            IpcCommand::DecreaseBrightness(2),
+            IpcCommand::SetBrightnessPercent(40),
+            IpcCommand::StepBrightness(StepDirection::Up, 10),
+            IpcCommand::SetRgb(1, 2, 3),
+            IpcCommand::SetZoneRgb(2, 4, 5, 6),
+            IpcCommand::Power(false),
+            IpcCommand::Restore,
+            IpcCommand::Fade(FadeTarget::Brightness(9), Transition::from_millis(250)),
+            IpcCommand::Fade(
+                FadeTarget::Profile("night".to_string()),
+                Transition {
+                    easing: Easing::Linear,
+                    ..Transition::from_millis(1)
+                },
+            ),
+            IpcCommand::SetEffect(Effect::new(EffectKind::Wave)),
+            IpcCommand::StopEffect,
+            IpcCommand::GetEffect,
+            IpcCommand::ListModes,
+            IpcCommand::GetMode,
+            IpcCommand::SetMode(KeyboardMode::Wave),
+            IpcCommand::GetIdle,
+            IpcCommand::SetIdle(IdleSettings {
+                dim_after: Some(30),
+                ..Default::default()
+            }),
+            IpcCommand::GetSchedule,
+            IpcCommand::SetSchedule(Schedule {
+                location: Some(Location {
+                    latitude: -6.2,
+                    longitude: 106.8,
+                }),
+                entries: vec![ScheduleEntry {
+                    at: ScheduleTime::Sunset(30),
+                    action: ScheduleAction::Profile("warm".to_string()),
+                }],
+            }),
+            IpcCommand::GetCalibration,
+            IpcCommand::SetCalibration(Calibration {
+                gamma: 0.8,
+                ..Default::default()
+            }),
+            IpcCommand::PreviewCalibration(Calibration::default(), Rgb::new(255, 255, 255)),
+            IpcCommand::Fan(FanCommand::GetInfo),
+            IpcCommand::Fan(FanCommand::GetSettings),
+            IpcCommand::Fan(FanCommand::SetSettings(FanSettings {
+                mode: FanMode::Curve,
+                ..Default::default()
+            })),
+            IpcCommand::PowerProfile(PowerProfileCommand::List),
+            IpcCommand::PowerProfile(PowerProfileCommand::Get),
+            IpcCommand::PowerProfile(PowerProfileCommand::Set(PowerProfile::Quiet)),
+            IpcCommand::Cpu(CpuCommand::Status),
+            IpcCommand::Charge(ChargeCommand::Get),
+            IpcCommand::Charge(ChargeCommand::Set(ChargeThresholds::new(40, 80))),
+            IpcCommand::Charge(ChargeCommand::Batteries),
+            IpcCommand::Cpu(CpuCommand::Tune(CpuTuning {
+                energy_preference: Some("balance_power".to_string()),
+                turbo: Some(false),
+                ..Default::default()
+            })),
+            IpcCommand::SaveProfile("office".to_string()),
+            IpcCommand::ApplyProfile("office".to_string()),
+            IpcCommand::DeleteProfile("office".to_string()),
+            IpcCommand::ListProfiles,
+            IpcCommand::GetBrightnessInfo,
+            IpcCommand::GetRgb,
+            IpcCommand::GetZoneRgb(1),
+            IpcCommand::GetZones,
+            IpcCommand::GetCapabilities,
+            IpcCommand::Subscribe,
+        ];
+        for (id, command) in commands.into_iter().enumerate() {
+            let request = Request {
+                id: id as u64,
+                command,
+            };
+            assert_eq!(round_trip(&request), request);
+        }
+    }
+
+    #[test]
+    fn test_response_round_trip() {
+        let results = [
+            Ok(IpcReply::Done),
+            Ok(IpcReply::BrightnessInfo(BrightnessInfo {
+                current: 10,
+                max: 255,
+                percent: 3,
+                is_on: true,
+            })),
+            Ok(IpcReply::Rgb(Rgb { r: 1, g: 2, b: 3 })),
+            Ok(IpcReply::Zones(vec![KeyboardZone::standard(0, 1)])),
+            Ok(IpcReply::Profiles(vec!["night".to_string()])),
+            Ok(IpcReply::Modes(KeyboardMode::ALL.to_vec())),
+            Ok(IpcReply::Mode(KeyboardMode::Static)),
+            Ok(IpcReply::Idle(IdleSettings::default())),
+            Ok(IpcReply::Schedule(Schedule::default())),
+            Ok(IpcReply::Calibration(Calibration::default())),
+            Ok(IpcReply::Fans(vec![FanInfo {
+                fan: Fan::Cpu,
+                speed: 40,
+                temperature: 62,
+            }])),
+            Ok(IpcReply::FanSettings(FanSettings::default())),
+            Ok(IpcReply::PowerProfiles(PowerProfile::ALL.to_vec())),
+            Ok(IpcReply::PowerProfile(PowerProfile::Balanced)),
+            Ok(IpcReply::ChargeThresholds(ChargeThresholds::new(60, 90))),
+            Ok(IpcReply::Batteries(vec![BatteryInfo {
+                name: "BAT0".to_string(),
+                state: BatteryState::Discharging,
+                capacity: Some(76),
+                energy_now_mwh: Some(37_240),
+                energy_full_mwh: Some(49_000),
+                energy_full_design_mwh: Some(53_000),
+                cycle_count: None,
+                power_mw: Some(9_800),
+                voltage_mv: Some(11_900),
+            }])),
+            Ok(IpcReply::CpuStatus(CpuStatus {
+                driver: "intel_pstate".to_string(),
+                policies: vec![CpuPolicy {
+                    id: 0,
+                    governor: "powersave".to_string(),
+                    governors: vec!["performance".to_string(), "powersave".to_string()],
+                    energy_preference: None,
+                    energy_preferences: Vec::new(),
+                }],
+                turbo: Some(true),
+            })),
+            Ok(IpcReply::Effect(Some(Effect {
+                colors: vec![Rgb { r: 1, g: 2, b: 3 }],
+                ..Effect::new(EffectKind::Pulse)
+            }))),
+            Ok(IpcReply::Capabilities(KeyboardCapabilities {
+                brightness: true,
+                rgb: true,
+                ..Default::default()
+            })),
+            Err(IpcError::VersionMismatch {
+                client: 2,
+                daemon: 1,
+            }),
+            Err(IpcError::Io("boom".to_string())),
+        ];
+        for (id, result) in results.into_iter().enumerate() {
+            let response = Response {
+                id: id as u64,
+                result,
+            };
+            assert_eq!(round_trip(&response), response);
+        }
+    }
+
+    #[test]
+    fn test_event_round_trip() {
+        let events = [
+            IpcEvent::BrightnessChanged(BrightnessInfo {
+                current: 0,
+                max: 255,
+                percent: 0,
+                is_on: false,
+            }),
+            IpcEvent::ColorChanged {
+                zone: Some(1),
+                rgb: Rgb { r: 9, g: 8, b: 7 },
+            },
+            IpcEvent::PowerChanged(true),
+            IpcEvent::ProfileChanged("night".to_string()),
+            IpcEvent::EffectChanged(None),
+            IpcEvent::ModeChanged(KeyboardMode::Breathe),
+            IpcEvent::DriverAdded(KeyboardCapabilities::default()),
+            IpcEvent::DriverRemoved,
+            IpcEvent::PowerProfileChanged(PowerProfile::Performance),
+        ];
+        for event in events {
+            assert_eq!(round_trip(&event), event);
+        }
+    }
+
+    #[test]
+    fn test_errors_mirror_core_errors() {
+        let errors = [
+            M4ArchError::InvalidValue,
+            M4ArchError::PermissionDenied,
+            M4ArchError::Unsupported("rgb".to_string()),
+            M4ArchError::InvalidZone(4),
+            M4ArchError::UnexpectedChannels("red".to_string()),
+            M4ArchError::Config("bad toml".to_string()),
+            M4ArchError::UnknownProfile("gaming".to_string()),
+            M4ArchError::Io(io::Error::other("disk on fire")),
+        ];
+        for error in errors {
+            let message = error.to_string();
+            let back = M4ArchError::from(round_trip(&IpcError::from(error)));
+            assert!(message.ends_with(&back.to_string()), "{message} vs {back}");
+        }
+    }
+
+    /// Match lengkap atas semua enum yang dikirim lewat socket. Varian baru
+    /// membuat test ini gagal dikompilasi; saat menambahkannya ke sini,
+    /// naikkan juga `PROTOCOL_VERSION` dan `WIRE_VERSION`.
+    #[test]
+    fn test_version_covers_wire_enums() {
+        const WIRE_VERSION: u32 = 3;
+        assert_eq!(
+            PROTOCOL_VERSION, WIRE_VERSION,
+            "wire enums changed: bump PROTOCOL_VERSION together with WIRE_VERSION"
+        );
+
+        fn command(command: &IpcCommand) {
+            match command {
+                IpcCommand::Hello { .. }
+                | IpcCommand::SetBrightness(_)
+                | IpcCommand::IncreaseBrightness(_)
+                | IpcCommand::DecreaseBrightness(_)
+                | IpcCommand::SetBrightnessPercent(_)
+                | IpcCommand::StepBrightness(..)
+                | IpcCommand::SetRgb(..)
+                | IpcCommand::SetZoneRgb(..)
+                | IpcCommand::Power(_)
+                | IpcCommand::Restore
+                | IpcCommand::SetEffect(_)
+                | IpcCommand::StopEffect
+                | IpcCommand::GetEffect
+                | IpcCommand::ListModes
+                | IpcCommand::GetMode
+                | IpcCommand::SetMode(_)
+                | IpcCommand::GetIdle
+                | IpcCommand::SetIdle(_)
+                | IpcCommand::GetSchedule
+                | IpcCommand::SetSchedule(_)
+                | IpcCommand::GetCalibration
+                | IpcCommand::SetCalibration(_)
+                | IpcCommand::PreviewCalibration(..)
+                | IpcCommand::SaveProfile(_)
+                | IpcCommand::ApplyProfile(_)
+                | IpcCommand::DeleteProfile(_)
+                | IpcCommand::ListProfiles
+                | IpcCommand::GetBrightnessInfo
+                | IpcCommand::GetRgb
+                | IpcCommand::GetZoneRgb(_)
+                | IpcCommand::GetZones
+                | IpcCommand::GetCapabilities
+                | IpcCommand::Subscribe => {}
+                IpcCommand::Fade(target, _) => match target {
+                    FadeTarget::Brightness(_)
+                    | FadeTarget::Rgb(_)
+                    | FadeTarget::ZoneRgb(..)
+                    | FadeTarget::Profile(_) => {}
+                },
+                IpcCommand::Fan(
+                    FanCommand::GetInfo | FanCommand::GetSettings | FanCommand::SetSettings(_),
+                )
+                | IpcCommand::PowerProfile(
+                    PowerProfileCommand::List
+                    | PowerProfileCommand::Get
+                    | PowerProfileCommand::Set(_),
+                )
+                | IpcCommand::Cpu(CpuCommand::Status | CpuCommand::Tune(_))
+                | IpcCommand::Charge(
+                    ChargeCommand::Get | ChargeCommand::Set(_) | ChargeCommand::Batteries,
+                ) => {}
+            }
+        }
+
+        fn reply(reply: &IpcReply) {
+            match reply {
+                IpcReply::Hello { .. }
+                | IpcReply::Done
+                | IpcReply::BrightnessInfo(_)
+                | IpcReply::Rgb(_)
+                | IpcReply::Zones(_)
+                | IpcReply::Capabilities(_)
+                | IpcReply::Profiles(_)
+                | IpcReply::Effect(_)
+                | IpcReply::Modes(_)
+                | IpcReply::Mode(_)
+                | IpcReply::Idle(_)
+                | IpcReply::Schedule(_)
+                | IpcReply::Calibration(_)
+                | IpcReply::Fans(_)
+                | IpcReply::FanSettings(_)
+                | IpcReply::PowerProfiles(_)
+                | IpcReply::PowerProfile(_)
+                | IpcReply::CpuStatus(_)
+                | IpcReply::ChargeThresholds(_)
+                | IpcReply::Batteries(_)
+                | IpcReply::Subscribed => {}
+            }
+        }
+
+        fn event(event: &IpcEvent) {
+            match event {
+                IpcEvent::BrightnessChanged(_)
+                | IpcEvent::ColorChanged { .. }
+                | IpcEvent::PowerChanged(_)
+                | IpcEvent::ProfileChanged(_)
+                | IpcEvent::EffectChanged(_)
+                | IpcEvent::ModeChanged(_)
+                | IpcEvent::DriverAdded(_)
+                | IpcEvent::DriverRemoved
+                | IpcEvent::PowerProfileChanged(_) => {}
+            }
+        }
+
+        fn error(error: &IpcError) {
+            match error {
+                IpcError::Io(_)
+                | IpcError::InvalidValue
+                | IpcError::PermissionDenied
+                | IpcError::Unsupported(_)
+                | IpcError::InvalidZone(_)
+                | IpcError::UnexpectedChannels(_)
+                | IpcError::Config(_)
+                | IpcError::UnknownProfile(_)
+                | IpcError::NoKeyboard
+                | IpcError::VersionMismatch { .. }
+                | IpcError::HandshakeRequired
+                | IpcError::Malformed(_) => {}
+            }
+        }
+
+        command(&IpcCommand::Subscribe);
+        reply(&IpcReply::Done);
+        event(&IpcEvent::DriverRemoved);
+        error(&IpcError::NoKeyboard);
+    }
 }
@@ -0,0 +1,315 @@
+//! Ambang pengisian serta keadaan dan kesehatan baterai.
+//!
+//! Ada dua mekanisme:
+//!
+//! - Atribut kernel `charge_control_start_threshold` dan
+//!   `charge_control_end_threshold` pada baterai di kelas `power_supply`.
+//!   Pada laptop Clevo, tuxedo-drivers memublikasikan flexicharger firmware
+//!   lewat atribut ini juga, jadi tidak perlu jalur `tuxedo_io` tersendiri.
+//! - `charging_profile` milik `tuxedo_keyboard` pada laptop Uniwill, yang
+//!   hanya mengenal tiga profil tetap; ketiganya sama dengan `ChargePreset`.
+//!
+//! Driver baterai melaporkan energi (`energy_*`, µWh) atau muatan
+//! (`charge_*`, µAh); `read_batteries()` mengubah keduanya menjadi mWh.
+use crate::power_supply::system_batteries;
+use crate::sysfs::{read_value, write_value};
+use m4arch_core::battery::{
+    BatteryInfo, BatteryState, ChargeControl, ChargePreset, ChargeThresholds,
+};
+use m4arch_core::error::{M4ArchError, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Direktori `charging_profile` Uniwill relatif terhadap root sysfs.
+pub const CHARGING_PROFILE_DIR: &str = "devices/platform/tuxedo_keyboard/charging_profile";
+
+/// Membaca keadaan semua baterai sistem di bawah root sysfs.
+pub fn read_batteries(root: &Path) -> Result<Vec<BatteryInfo>> {
+    Ok(system_batteries(root)?
+        .iter()
+        .map(|dir| read_battery(dir))
+        .collect())
+}
+
+fn read_battery(dir: &Path) -> BatteryInfo {
+    // Atribut yang tidak ada atau tidak terbaca (`ENODATA` saat baterai
+    // dilepas) dianggap tidak dilaporkan. Arus bernilai negatif saat
+    // discharging pada sebagian driver.
+    let micro = |name: &str| {
+        read_value::<i64>(&dir.join(name))
+            .ok()
+            .map(i64::unsigned_abs)
+    };
+    let milli = |value: u64| u32::try_from(value / 1000).ok();
+    // µA × µV = 1e-12 W (begitu juga µAh × µV), jadi dibagi 1e9
+    let product = |a: u64, b: u64| u32::try_from(a.checked_mul(b)? / 1_000_000_000).ok();
+    let voltage = micro("voltage_now");
+    // Muatan dikonversi dengan tegangan desain agar kapasitas tidak ikut
+    // naik turun bersama tegangan
+    let design_voltage = micro("voltage_min_design").or(voltage);
+    let energy = |name: &str| match micro(&format!("energy_{name}")) {
+        Some(energy) => milli(energy),
+        None => product(micro(&format!("charge_{name}"))?, design_voltage?),
+    };
+    let power = match micro("power_now") {
+        Some(power) => milli(power),
+        None => micro("current_now")
+            .zip(voltage)
+            .and_then(|(current, voltage)| product(current, voltage)),
+    };
+    let status = fs::read_to_string(dir.join("status")).unwrap_or_default();
+
+    BatteryInfo {
+        name: dir
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+        state: BatteryState::from_kernel(&status),
+        capacity: read_value::<u8>(&dir.join("capacity"))
+            .ok()
+            .map(|capacity| capacity.min(100)),
+        energy_now_mwh: energy("now"),
+        energy_full_mwh: energy("full"),
+        energy_full_design_mwh: energy("full_design"),
+        cycle_count: read_value(&dir.join("cycle_count")).ok(),
+        power_mw: power,
+        voltage_mv: voltage.and_then(milli),
+    }
+}
+
+/// Ambang lewat atribut `charge_control_*_threshold` kernel.
+pub struct KernelThresholds {
+    battery: PathBuf,
+}
+
+impl KernelThresholds {
+    /// Baterai pertama yang mendukung ambang akhir.
+    pub fn detect(root: &Path) -> Option<Self> {
+        system_batteries(root)
+            .ok()?
+            .into_iter()
+            .find(|dir| dir.join("charge_control_end_threshold").exists())
+            .map(|battery| Self { battery })
+    }
+
+    fn start_path(&self) -> Option<PathBuf> {
+        let path = self.battery.join("charge_control_start_threshold");
+        path.exists().then_some(path)
+    }
+
+    fn end_path(&self) -> PathBuf {
+        self.battery.join("charge_control_end_threshold")
+    }
+}
+
+impl ChargeControl for KernelThresholds {
+    fn get_thresholds(&self) -> Result<ChargeThresholds> {
+        let start = match self.start_path() {
+            Some(path) => read_value(&path)?,
+            None => 0,
+        };
+        Ok(ChargeThresholds::new(start, read_value(&self.end_path())?))
+    }
+
+    /// Tanpa atribut ambang awal, hanya ambang akhir yang ditulis; firmware
+    /// menentukan sendiri kapan mulai mengisi lagi.
+    fn set_thresholds(&self, thresholds: ChargeThresholds) -> Result<()> {
+        thresholds.validate()?;
+        let Some(start_path) = self.start_path() else {
+            return write_value(&self.end_path(), thresholds.end);
+        };
+        // Kernel menolak awal >= akhir di setiap langkah, jadi urutan
+        // penulisan mengikuti arah perubahannya
+        let current_end: u8 = read_value(&self.end_path())?;
+        if thresholds.start >= current_end {
+            write_value(&self.end_path(), thresholds.end)?;
+            write_value(&start_path, thresholds.start)
+        } else {
+            write_value(&start_path, thresholds.start)?;
+            write_value(&self.end_path(), thresholds.end)
+        }
+    }
+}
+
+/// `charging_profile` Uniwill dari `tuxedo_keyboard`.
+pub struct UniwillChargingProfile {
+    dir: PathBuf,
+}
+
+impl UniwillChargingProfile {
+    pub fn detect(root: &Path) -> Option<Self> {
+        let dir = root.join(CHARGING_PROFILE_DIR);
+        dir.join("charging_profile")
+            .exists()
+            .then_some(Self { dir })
+    }
+}
+
+fn profile_name(preset: ChargePreset) -> &'static str {
+    match preset {
+        ChargePreset::Full => "high_capacity",
+        ChargePreset::Balanced => "balanced",
+        ChargePreset::Stationary => "stationary",
+    }
+}
+
+impl ChargeControl for UniwillChargingProfile {
+    fn get_thresholds(&self) -> Result<ChargeThresholds> {
+        let name: String = read_value(&self.dir.join("charging_profile"))?;
+        ChargePreset::ALL
+            .into_iter()
+            .find(|preset| profile_name(*preset) == name)
+            .map(ChargePreset::thresholds)
+            .ok_or_else(|| M4ArchError::Unsupported(format!("charging profile '{}'", name)))
+    }
+
+    fn set_thresholds(&self, thresholds: ChargeThresholds) -> Result<()> {
+        thresholds.validate()?;
+        let preset = ChargePreset::matching(thresholds).ok_or_else(|| {
+            M4ArchError::Unsupported(
+                "custom charge thresholds (only full, balanced and stationary)".to_string(),
+            )
+        })?;
+        write_value(&self.dir.join("charging_profile"), profile_name(preset))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::power_supply::POWER_SUPPLY_DIR;
+    use crate::sysfs::fake::FakeSysfs;
+
+    fn battery(fake: &FakeSysfs, attributes: &[(&str, &str)]) {
+        for (attribute, value) in attributes {
+            fake.write(format!("{POWER_SUPPLY_DIR}/BAT0/{attribute}"), value)
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_read_batteries() {
+        let fake = FakeSysfs::new().unwrap();
+        assert!(read_batteries(fake.root()).unwrap().is_empty());
+        battery(
+            &fake,
+            &[
+                ("type", "Battery"),
+                ("status", "Discharging\n"),
+                ("capacity", "50\n"),
+                ("energy_now", "20000000\n"),
+                ("energy_full", "40000000\n"),
+                ("energy_full_design", "50000000\n"),
+                ("cycle_count", "120\n"),
+                ("power_now", "10000000\n"),
+                ("voltage_now", "11400000\n"),
+            ],
+        );
+        // Driver yang melaporkan muatan dan arus (negatif saat discharging)
+        for (attribute, value) in [
+            ("type", "Battery"),
+            ("status", "Charging"),
+            ("charge_now", "1000000"),
+            ("charge_full", "4000000"),
+            ("charge_full_design", "5000000"),
+            ("current_now", "-2000000"),
+            ("voltage_now", "12000000"),
+            ("voltage_min_design", "10000000"),
+        ] {
+            fake.write(format!("{POWER_SUPPLY_DIR}/BAT1/{attribute}"), value)
+                .unwrap();
+        }
+        fake.write(
+            format!("{POWER_SUPPLY_DIR}/hidpp_battery_0/type"),
+            "Battery",
+        )
+        .unwrap();
+        fake.write(
+            format!("{POWER_SUPPLY_DIR}/hidpp_battery_0/scope"),
+            "Device",
+        )
+        .unwrap();
+
+        let batteries = read_batteries(fake.root()).unwrap();
+        assert_eq!(batteries.len(), 2);
+        let first = &batteries[0];
+        assert_eq!(first.name, "BAT0");
+        assert_eq!(first.state, BatteryState::Discharging);
+        assert_eq!(first.capacity, Some(50));
+        assert_eq!(first.health(), Some(80.0));
+        assert_eq!(first.cycle_count, Some(120));
+        assert_eq!(first.power_mw, Some(10_000));
+        assert_eq!(first.voltage_mv, Some(11_400));
+
+        let second = &batteries[1];
+        assert_eq!(second.state, BatteryState::Charging);
+        assert_eq!(second.capacity, None);
+        assert_eq!(second.energy_now_mwh, Some(10_000));
+        assert_eq!(second.energy_full_design_mwh, Some(50_000));
+        assert_eq!(second.power_mw, Some(24_000));
+        assert_eq!(second.cycle_count, None);
+    }
+
+    #[test]
+    fn test_kernel_thresholds() {
+        let fake = FakeSysfs::new().unwrap();
+        battery(
+            &fake,
+            &[
+                ("type", "Battery"),
+                ("charge_control_start_threshold", "0\n"),
+                ("charge_control_end_threshold", "100\n"),
+            ],
+        );
+        let charge = KernelThresholds::detect(fake.root()).unwrap();
+        assert_eq!(
+            charge.get_thresholds().unwrap(),
+            ChargePreset::Full.thresholds()
+        );
+
+        charge
+            .set_thresholds(ChargePreset::Stationary.thresholds())
+            .unwrap();
+        assert_eq!(
+            charge.get_thresholds().unwrap(),
+            ChargeThresholds::new(40, 80)
+        );
+        // Awal baru di atas akhir lama: akhir ditulis lebih dulu
+        charge
+            .set_thresholds(ChargeThresholds::new(85, 95))
+            .unwrap();
+        assert_eq!(
+            charge.get_thresholds().unwrap(),
+            ChargeThresholds::new(85, 95)
+        );
+        assert!(charge
+            .set_thresholds(ChargeThresholds::new(90, 90))
+            .is_err());
+    }
+
+    #[test]
+    fn test_uniwill_profiles() {
+        let fake = FakeSysfs::new().unwrap();
+        assert!(UniwillChargingProfile::detect(fake.root()).is_none());
+        fake.write(
+            format!("{CHARGING_PROFILE_DIR}/charging_profile"),
+            "high_capacity\n",
+        )
+        .unwrap();
+        let charge = UniwillChargingProfile::detect(fake.root()).unwrap();
+        assert_eq!(
+            charge.get_thresholds().unwrap(),
+            ChargePreset::Full.thresholds()
+        );
+
+        charge
+            .set_thresholds(ChargePreset::Balanced.thresholds())
+            .unwrap();
+        let path = format!("{CHARGING_PROFILE_DIR}/charging_profile");
+        assert_eq!(fake.read(path).unwrap(), "balanced");
+        assert!(matches!(
+            charge.set_thresholds(ChargeThresholds::new(50, 70)),
+            Err(M4ArchError::Unsupported(_))
+        ));
+    }
+}
@@ -0,0 +1,198 @@
+//! Controller yang menerapkan kalibrasi warna sebelum menulis ke driver.
+//!
+//! `CalibratedKeyboard` membungkus controller lain: warna yang ditulis
+//! diubah dengan `Calibration::apply()`, warna yang dibaca dikembalikan ke
+//! warna pilihan pengguna, sehingga pemanggil tidak perlu tahu kalibrasinya.
+use crate::KeyboardController;
+use m4arch_core::config::Calibration;
+use m4arch_core::error::Result;
+use m4arch_core::keyboard::{
+    BrightnessInfo, KeyboardBrightness, KeyboardCapabilities, KeyboardMode, KeyboardRgb,
+    KeyboardZone, Rgb,
+};
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+pub struct CalibratedKeyboard {
+    inner: Box<dyn KeyboardController>,
+    calibration: Calibration,
+    /// Warna terakhir yang ditulis per zona beserta nilai mentahnya.
+    /// Kurva tidak bisa dibalik tanpa rugi, jadi warna ini yang dikembalikan
+    /// selama hardware masih menampilkan nilai mentah yang sama.
+    written: RefCell<HashMap<usize, (Rgb, Rgb)>>,
+}
+
+impl CalibratedKeyboard {
+    pub fn new(inner: Box<dyn KeyboardController>, calibration: Calibration) -> Self {
+        Self {
+            inner,
+            calibration,
+            written: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// Membungkus `inner` dengan kalibrasi; kalibrasi netral dan keyboard
+    /// tanpa RGB dikembalikan apa adanya.
+    pub fn wrap(
+        inner: Box<dyn KeyboardController>,
+        calibration: Calibration,
+    ) -> Box<dyn KeyboardController> {
+        if calibration.is_identity() || !inner.capabilities().rgb {
+            return inner;
+        }
+        Box::new(Self::new(inner, calibration))
+    }
+
+    fn read(&self, zone: usize, raw: Rgb) -> Rgb {
+        match self.written.borrow().get(&zone) {
+            Some(&(rgb, written)) if same_raw(written, raw) => rgb,
+            _ => self.calibration.invert(raw),
+        }
+    }
+
+    fn remember(&self, zone: usize, rgb: Rgb, raw: Rgb) {
+        self.written.borrow_mut().insert(zone, (rgb, raw));
+    }
+}
+
+/// Driver yang menskalakan intensitas bisa membaca kembali nilai yang
+/// berbeda satu angka dari yang ditulis.
+fn same_raw(written: Rgb, read: Rgb) -> bool {
+    [
+        (written.r, read.r),
+        (written.g, read.g),
+        (written.b, read.b),
+    ]
+    .iter()
+    .all(|(a, b)| a.abs_diff(*b) <= 1)
+}
+
+impl KeyboardController for CalibratedKeyboard {
+    fn capabilities(&self) -> KeyboardCapabilities {
+        self.inner.capabilities()
+    }
+
+    fn brightness_hw_changed(&self) -> Result<Option<u8>> {
+        self.inner.brightness_hw_changed()
+    }
+
+    fn modes(&self) -> Vec<KeyboardMode> {
+        self.inner.modes()
+    }
+
+    fn get_mode(&self) -> Result<KeyboardMode> {
+        self.inner.get_mode()
+    }
+
+    fn set_mode(&self, mode: KeyboardMode) -> Result<()> {
+        self.inner.set_mode(mode)
+    }
+}
+
+impl KeyboardBrightness for CalibratedKeyboard {
+    fn get_brightness(&self) -> Result<u8> {
+        self.inner.get_brightness()
+    }
+
+    fn get_max_brightness(&self) -> Result<u8> {
+        self.inner.get_max_brightness()
+    }
+
+    fn set_brightness(&self, level: u8) -> Result<()> {
+        self.inner.set_brightness(level)
+    }
+
+    fn increase_brightness(&self, step: u8) -> Result<()> {
+        self.inner.increase_brightness(step)
+    }
+
+    fn decrease_brightness(&self, step: u8) -> Result<()> {
+        self.inner.decrease_brightness(step)
+    }
+
+    fn brightness_info(&self) -> Result<BrightnessInfo> {
+        self.inner.brightness_info()
+    }
+}
+
+impl KeyboardRgb for CalibratedKeyboard {
+    fn get_rgb(&self) -> Result<Rgb> {
+        Ok(self.read(0, self.inner.get_rgb()?))
+    }
+
+    fn set_rgb(&self, rgb: Rgb) -> Result<()> {
+        let raw = self.calibration.apply(rgb);
+        self.inner.set_rgb(raw)?;
+        for zone in self.inner.zones() {
+            self.remember(zone.index, rgb, raw);
+        }
+        Ok(())
+    }
+
+    fn zones(&self) -> Vec<KeyboardZone> {
+        self.inner.zones()
+    }
+
+    fn get_zone_rgb(&self, zone: usize) -> Result<Rgb> {
+        Ok(self.read(zone, self.inner.get_zone_rgb(zone)?))
+    }
+
+    fn set_zone_rgb(&self, zone: usize, rgb: Rgb) -> Result<()> {
+        let raw = self.calibration.apply(rgb);
+        self.inner.set_zone_rgb(zone, raw)?;
+        self.remember(zone, rgb, raw);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sysfs::fake::{FakeLed, FakeSysfs};
+    use m4arch_core::config::ChannelGain;
+
+    const LED: &str = "class/leds/rgb:kbd_backlight";
+
+    #[test]
+    fn test_writes_calibrated_colors() {
+        let fake = FakeSysfs::new().unwrap();
+        fake.add_led(LED, &FakeLed::rgb(255, 255, (0, 0, 0)))
+            .unwrap();
+        let calibration = Calibration {
+            gamma: 2.0,
+            gain: ChannelGain {
+                blue: 0.8,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let kbd =
+            CalibratedKeyboard::wrap(crate::get_keyboard_at(fake.root()).unwrap(), calibration);
+
+        kbd.set_rgb(Rgb::new(255, 128, 255)).unwrap();
+        let raw = fake.read(format!("{LED}/multi_intensity")).unwrap();
+        assert_eq!(raw, "255 64 204");
+        // Warna pilihan yang dibaca kembali persis sama
+        assert_eq!(kbd.get_rgb().unwrap(), Rgb::new(255, 128, 255));
+
+        // Nilai yang ditulis pihak lain dibaca lewat kebalikan kalibrasi
+        fake.write(format!("{LED}/multi_intensity"), "64 0 204")
+            .unwrap();
+        assert_eq!(kbd.get_rgb().unwrap(), Rgb::new(128, 0, 255));
+    }
+
+    #[test]
+    fn test_identity_is_not_wrapped() {
+        let fake = FakeSysfs::new().unwrap();
+        fake.add_led(LED, &FakeLed::rgb(0, 255, (1, 2, 3))).unwrap();
+        let kbd = CalibratedKeyboard::wrap(
+            crate::get_keyboard_at(fake.root()).unwrap(),
+            Calibration::default(),
+        );
+        kbd.set_rgb(Rgb::new(4, 5, 6)).unwrap();
+        assert_eq!(
+            fake.read(format!("{LED}/multi_intensity")).unwrap(),
+            "4 5 6"
+        );
+    }
+}
@@ -0,0 +1,374 @@
+//! Tuning CPU lewat `/sys/devices/system/cpu`.
+//!
+//! Governor dan EPP ditulis ke setiap `cpufreq/policyN`. Turbo diatur lewat
+//! `intel_pstate/no_turbo` (nilainya terbalik: 1 berarti turbo mati), atau
+//! `cpufreq/boost` pada driver lain.
+//!
+//! `CpuTunedProfiles` menambahkan tuning bawaan setiap profil performa ke
+//! backend profil firmware, sehingga profil tetap berguna di laptop tanpa
+//! `platform_profile` maupun `tuxedo_io`.
+use crate::sysfs::{map_io_error, read_value, write_value};
+use crate::PowerProfileController;
+use m4arch_core::cpu::{CpuControl, CpuPolicy, CpuStatus, CpuTuning};
+use m4arch_core::error::{M4ArchError, Result};
+use m4arch_core::power::{PowerProfile, PowerProfileControl};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Direktori CPU relatif terhadap root sysfs.
+pub const CPU_DIR: &str = "devices/system/cpu";
+
+/// Atribut turbo yang tersedia.
+enum TurboSwitch {
+    /// `intel_pstate/no_turbo`
+    NoTurbo(PathBuf),
+    /// `cpufreq/boost`
+    Boost(PathBuf),
+}
+
+pub struct SysfsCpu {
+    dir: PathBuf,
+}
+
+impl SysfsCpu {
+    pub fn with_root(root: impl AsRef<Path>) -> Self {
+        Self {
+            dir: root.as_ref().join(CPU_DIR),
+        }
+    }
+
+    /// Apakah ada policy cpufreq di bawah `root`.
+    pub fn detect(root: &Path) -> bool {
+        Self::with_root(root)
+            .policy_dirs()
+            .is_ok_and(|policies| !policies.is_empty())
+    }
+
+    /// Direktori `policyN`, terurut menurut N.
+    fn policy_dirs(&self) -> Result<Vec<(u32, PathBuf)>> {
+        let entries = match fs::read_dir(self.dir.join("cpufreq")) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(map_io_error(e)),
+        };
+        let mut policies = Vec::new();
+        for entry in entries {
+            let entry = entry?;
+            let name = entry.file_name();
+            let id = name
+                .to_str()
+                .and_then(|name| name.strip_prefix("policy"))
+                .and_then(|id| id.parse().ok());
+            if let Some(id) = id {
+                policies.push((id, entry.path()));
+            }
+        }
+        policies.sort_by_key(|(id, _)| *id);
+        Ok(policies)
+    }
+
+    fn turbo_switch(&self) -> Option<TurboSwitch> {
+        let no_turbo = self.dir.join("intel_pstate/no_turbo");
+        if no_turbo.exists() {
+            return Some(TurboSwitch::NoTurbo(no_turbo));
+        }
+        let boost = self.dir.join("cpufreq/boost");
+        boost.exists().then_some(TurboSwitch::Boost(boost))
+    }
+
+    fn read_policy(id: u32, dir: &Path) -> Result<CpuPolicy> {
+        Ok(CpuPolicy {
+            id,
+            governor: read_value(&dir.join("scaling_governor"))?,
+            governors: read_list(&dir.join("scaling_available_governors"))?,
+            energy_preference: read_optional(&dir.join("energy_performance_preference"))?,
+            energy_preferences: read_list(&dir.join("energy_performance_available_preferences"))?,
+        })
+    }
+
+    /// Memastikan semua nilai di `tuning` bisa ditulis ke setiap policy.
+    fn validate(&self, policies: &[CpuPolicy], tuning: &CpuTuning) -> Result<()> {
+        if let Some(governor) = &tuning.governor {
+            if policies
+                .iter()
+                .any(|policy| !policy.governors.contains(governor))
+            {
+                return Err(M4ArchError::Unsupported(format!(
+                    "CPU governor '{}'",
+                    governor
+                )));
+            }
+        }
+        if let Some(preference) = &tuning.energy_preference {
+            if policies
+                .iter()
+                .any(|policy| policy.energy_preference.is_none())
+            {
+                return Err(M4ArchError::Unsupported(
+                    "energy performance preference".to_string(),
+                ));
+            }
+            if policies
+                .iter()
+                .any(|policy| !policy.energy_preferences.contains(preference))
+            {
+                return Err(M4ArchError::Unsupported(format!(
+                    "energy performance preference '{}'",
+                    preference
+                )));
+            }
+        }
+        if tuning.turbo.is_some() && self.turbo_switch().is_none() {
+            return Err(M4ArchError::Unsupported("turbo boost control".to_string()));
+        }
+        Ok(())
+    }
+}
+
+impl CpuControl for SysfsCpu {
+    fn status(&self) -> Result<CpuStatus> {
+        let dirs = self.policy_dirs()?;
+        let driver = match dirs.first() {
+            Some((_, dir)) => read_value(&dir.join("scaling_driver"))?,
+            None => return Err(M4ArchError::Unsupported("cpufreq".to_string())),
+        };
+        let policies = dirs
+            .iter()
+            .map(|(id, dir)| Self::read_policy(*id, dir))
+            .collect::<Result<_>>()?;
+        let turbo = match self.turbo_switch() {
+            Some(TurboSwitch::NoTurbo(path)) => Some(read_value::<u8>(&path)? == 0),
+            Some(TurboSwitch::Boost(path)) => Some(read_value::<u8>(&path)? == 1),
+            None => None,
+        };
+        Ok(CpuStatus {
+            driver,
+            policies,
+            turbo,
+        })
+    }
+
+    fn apply(&self, tuning: &CpuTuning) -> Result<()> {
+        let policies = self.status()?.policies;
+        self.validate(&policies, tuning)?;
+        let dirs = self.policy_dirs()?;
+        // Governor lebih dulu: intel_pstate menolak EPP selain `performance`
+        // selama governor `performance` aktif
+        if let Some(governor) = &tuning.governor {
+            for (_, dir) in &dirs {
+                write_value(&dir.join("scaling_governor"), governor)?;
+            }
+        }
+        if let Some(preference) = &tuning.energy_preference {
+            for (_, dir) in &dirs {
+                let path = dir.join("energy_performance_preference");
+                if read_value::<String>(&path)? != *preference {
+                    write_value(&path, preference)?;
+                }
+            }
+        }
+        match (tuning.turbo, self.turbo_switch()) {
+            (Some(turbo), Some(TurboSwitch::NoTurbo(path))) => write_value(&path, u8::from(!turbo)),
+            (Some(turbo), Some(TurboSwitch::Boost(path))) => write_value(&path, u8::from(turbo)),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Profil performa firmware (jika ada) ditambah tuning CPU bawaan profilnya.
+pub struct CpuTunedProfiles {
+    firmware: Option<Box<dyn PowerProfileController>>,
+    cpu: SysfsCpu,
+}
+
+impl CpuTunedProfiles {
+    pub fn new(firmware: Option<Box<dyn PowerProfileController>>, cpu: SysfsCpu) -> Self {
+        Self { firmware, cpu }
+    }
+}
+
+impl PowerProfileControl for CpuTunedProfiles {
+    fn profiles(&self) -> Vec<PowerProfile> {
+        match &self.firmware {
+            Some(firmware) => firmware.profiles(),
+            None => PowerProfile::ALL.to_vec(),
+        }
+    }
+
+    fn get_profile(&self) -> Result<PowerProfile> {
+        match &self.firmware {
+            Some(firmware) => firmware.get_profile(),
+            None => Err(M4ArchError::Unsupported(
+                "reading the power profile from cpufreq".to_string(),
+            )),
+        }
+    }
+
+    fn set_profile(&self, profile: PowerProfile) -> Result<()> {
+        if let Some(firmware) = &self.firmware {
+            firmware.set_profile(profile)?;
+        }
+        // Setiap bagian diterapkan sendiri; bagian yang tidak didukung driver
+        // ini (misalnya EPP pada acpi-cpufreq) dilewati
+        let tuning = CpuTuning::for_profile(profile);
+        let parts = [
+            CpuTuning {
+                governor: tuning.governor,
+                ..Default::default()
+            },
+            CpuTuning {
+                energy_preference: tuning.energy_preference,
+                ..Default::default()
+            },
+            CpuTuning {
+                turbo: tuning.turbo,
+                ..Default::default()
+            },
+        ];
+        for part in parts {
+            match self.cpu.apply(&part) {
+                Ok(()) | Err(M4ArchError::Unsupported(_)) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Membaca atribut berisi daftar nama; kosong jika atribut tidak ada.
+fn read_list(path: &Path) -> Result<Vec<String>> {
+    Ok(read_optional(path)?
+        .map(|list| list.split_whitespace().map(str::to_string).collect())
+        .unwrap_or_default())
+}
+
+fn read_optional(path: &Path) -> Result<Option<String>> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(Some(content.trim().to_string())),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(map_io_error(e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sysfs::fake::FakeSysfs;
+
+    /// Dua policy `intel_pstate` dengan governor `powersave`.
+    fn setup() -> (FakeSysfs, SysfsCpu) {
+        let fake = FakeSysfs::new().unwrap();
+        for policy in ["policy0", "policy4"] {
+            let dir = format!("{CPU_DIR}/cpufreq/{policy}");
+            for (attr, value) in [
+                ("scaling_driver", "intel_pstate\n"),
+                ("scaling_governor", "powersave\n"),
+                ("scaling_available_governors", "performance powersave\n"),
+                ("energy_performance_preference", "balance_performance\n"),
+                (
+                    "energy_performance_available_preferences",
+                    "default performance balance_performance balance_power power \n",
+                ),
+            ] {
+                fake.write(format!("{dir}/{attr}"), value).unwrap();
+            }
+        }
+        fake.write(format!("{CPU_DIR}/intel_pstate/no_turbo"), "0\n")
+            .unwrap();
+        let cpu = SysfsCpu::with_root(fake.root());
+        (fake, cpu)
+    }
+
+    #[test]
+    fn test_reads_status() {
+        let (fake, cpu) = setup();
+        assert!(SysfsCpu::detect(fake.root()));
+        let status = cpu.status().unwrap();
+        assert_eq!(status.driver, "intel_pstate");
+        assert_eq!(status.turbo, Some(true));
+        let ids: Vec<_> = status.policies.iter().map(|policy| policy.id).collect();
+        assert_eq!(ids, [0, 4]);
+        let policy = &status.policies[1];
+        assert_eq!(policy.governor, "powersave");
+        assert_eq!(
+            policy.energy_preference.as_deref(),
+            Some("balance_performance")
+        );
+        assert_eq!(policy.energy_preferences.len(), 5);
+    }
+
+    #[test]
+    fn test_applies_tuning_to_every_policy() {
+        let (fake, cpu) = setup();
+        cpu.apply(&CpuTuning {
+            governor: Some("powersave".to_string()),
+            energy_preference: Some("power".to_string()),
+            turbo: Some(false),
+        })
+        .unwrap();
+        for policy in ["policy0", "policy4"] {
+            let path = format!("{CPU_DIR}/cpufreq/{policy}/energy_performance_preference");
+            assert_eq!(fake.read(path).unwrap(), "power");
+        }
+        assert_eq!(
+            fake.read(format!("{CPU_DIR}/intel_pstate/no_turbo"))
+                .unwrap(),
+            "1"
+        );
+        assert_eq!(cpu.status().unwrap().turbo, Some(false));
+    }
+
+    #[test]
+    fn test_rejects_unavailable_values() {
+        let (fake, cpu) = setup();
+        let tuning = CpuTuning {
+            governor: Some("performance".to_string()),
+            energy_preference: Some("turbo".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            cpu.apply(&tuning),
+            Err(M4ArchError::Unsupported(_))
+        ));
+        // Tidak ada yang ditulis sebelum semua nilai diperiksa
+        let governor = format!("{CPU_DIR}/cpufreq/policy0/scaling_governor");
+        assert_eq!(fake.read(&governor).unwrap(), "powersave\n");
+
+        let tuning = CpuTuning {
+            governor: Some("schedutil".to_string()),
+            ..Default::default()
+        };
+        assert!(cpu.apply(&tuning).is_err());
+    }
+
+    #[test]
+    fn test_profiles_tune_cpu() {
+        let (fake, cpu) = setup();
+        let profiles = CpuTunedProfiles::new(None, cpu);
+        assert_eq!(profiles.profiles(), PowerProfile::ALL);
+
+        profiles.set_profile(PowerProfile::Performance).unwrap();
+        let policy0 = format!("{CPU_DIR}/cpufreq/policy0");
+        assert_eq!(
+            fake.read(format!("{policy0}/scaling_governor")).unwrap(),
+            "performance"
+        );
+
+        profiles.set_profile(PowerProfile::Quiet).unwrap();
+        assert_eq!(
+            fake.read(format!("{policy0}/scaling_governor")).unwrap(),
+            "powersave"
+        );
+        assert_eq!(
+            fake.read(format!("{policy0}/energy_performance_preference"))
+                .unwrap(),
+            "power"
+        );
+        assert_eq!(
+            fake.read(format!("{CPU_DIR}/intel_pstate/no_turbo"))
+                .unwrap(),
+            "1"
+        );
+    }
+}
@@ -1,32 +1,322 @@
+use m4arch_core::battery::{BatteryInfo, ChargeControl, ChargeThresholds};
+use m4arch_core::config::KeyboardState;
+use m4arch_core::cpu::{CpuControl, CpuStatus, CpuTuning};
 use m4arch_core::error::{M4ArchError, Result};
-use m4arch_core::keyboard::{BrightnessInfo, KeyboardBrightness, KeyboardRgb, Rgb};
+use m4arch_core::fan::{FanDuty, FanInfo, FanSpeed};
+use m4arch_core::keyboard::effect::FRAME_INTERVAL;
+use m4arch_core::keyboard::{
+    BrightnessInfo, Fade, FadeFrame, KeyboardBrightness, KeyboardCapabilities, KeyboardMode,
+    KeyboardRgb, KeyboardZone, Rgb, StepDirection, Transition,
+};
+use m4arch_core::power::{PowerProfile, PowerProfileControl};
 use std::path::Path;
+use std::thread;
+use std::time::Instant;
 
+pub mod battery;
+pub mod calibration;
+pub mod cpu;
+pub mod platform_profile;
+pub mod power_supply;
 pub mod sysfs;
 pub mod systemd;
 pub mod tuxedo;
+pub mod tuxedo_io;
+pub mod tuxedo_legacy;
 pub mod udev;
+pub mod zones;
 
 /// Trait gabungan untuk controller keyboard yang mendukung Brightness dan RGB.
-pub trait KeyboardController: KeyboardBrightness + KeyboardRgb {}
-impl<T: KeyboardBrightness + KeyboardRgb> KeyboardController for T {}
+///
+/// `Send` agar controller bisa dimiliki oleh thread daemon.
+pub trait KeyboardController: KeyboardBrightness + KeyboardRgb + Send {
+    /// Kemampuan perangkat, agar front-end bisa menyembunyikan kontrol
+    /// yang tidak didukung.
+    fn capabilities(&self) -> KeyboardCapabilities;
+
+    /// Brightness terakhir yang diubah langsung oleh hardware (hotkey),
+    /// jika driver melaporkannya lewat `brightness_hw_changed`.
+    fn brightness_hw_changed(&self) -> Result<Option<u8>> {
+        Ok(None)
+    }
+
+    /// Mode bawaan firmware yang didukung; kosong jika tidak ada.
+    fn modes(&self) -> Vec<KeyboardMode> {
+        Vec::new()
+    }
+
+    fn get_mode(&self) -> Result<KeyboardMode> {
+        Err(M4ArchError::Unsupported("hardware modes".to_string()))
+    }
+
+    fn set_mode(&self, _mode: KeyboardMode) -> Result<()> {
+        Err(M4ArchError::Unsupported("hardware modes".to_string()))
+    }
+}
+
+/// Trait gabungan untuk controller kipas.
+pub trait FanController: FanSpeed + FanDuty + Send {}
+
+impl<T: FanSpeed + FanDuty + Send> FanController for T {}
+
+/// Membuka controller kipas `tuxedo_io`.
+pub fn get_fans() -> Result<Box<dyn FanController>> {
+    let fans =
+        tuxedo_io::TuxedoIo::open(Path::new(tuxedo_io::DEVICE)).map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => {
+                M4ArchError::Unsupported("fan control (is tuxedo_io loaded?)".to_string())
+            }
+            _ => sysfs::map_io_error(e),
+        })?;
+    Ok(Box::new(fans))
+}
+
+/// Trait gabungan untuk backend profil performa.
+pub trait PowerProfileController: PowerProfileControl + Send {}
+
+impl<T: PowerProfileControl + Send> PowerProfileController for T {}
+
+/// Mendeteksi backend profil performa.
+pub fn get_power_profiles() -> Result<Box<dyn PowerProfileController>> {
+    get_power_profiles_at(Path::new(sysfs::DEFAULT_ROOT), Path::new(tuxedo_io::DEVICE))
+}
+
+/// Seperti `get_power_profiles()`, tetapi dengan root sysfs dan perangkat
+/// `tuxedo_io` tertentu.
+///
+/// `platform_profile` diutamakan karena bisa dibaca kembali dan ikut
+/// diperbarui saat profil diganti lewat hotkey atau power-profiles-daemon.
+/// Jika ada cpufreq, setiap profil juga membawa tuning CPU-nya.
+pub fn get_power_profiles_at(
+    root: &Path,
+    device: &Path,
+) -> Result<Box<dyn PowerProfileController>> {
+    let firmware: std::io::Result<Box<dyn PowerProfileController>> =
+        if platform_profile::PlatformProfile::detect(root) {
+            Ok(Box::new(platform_profile::PlatformProfile::with_root(root)))
+        } else {
+            tuxedo_io::TuxedoIo::open(device).map(|io| Box::new(io) as _)
+        };
+    compose_power_profiles(firmware, get_cpu_at(root))
+}
+
+/// Menggabungkan backend firmware dengan cpufreq. `tuxedo_io` yang tidak
+/// boleh dibuka hanya menjadi error jika cpufreq juga tidak ada.
+fn compose_power_profiles(
+    firmware: std::io::Result<Box<dyn PowerProfileController>>,
+    cpu: Option<cpu::SysfsCpu>,
+) -> Result<Box<dyn PowerProfileController>> {
+    let denied = matches!(&firmware, Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied);
+    match (firmware.ok(), cpu) {
+        (firmware, Some(cpu)) => Ok(Box::new(cpu::CpuTunedProfiles::new(firmware, cpu))),
+        (Some(firmware), None) => Ok(firmware),
+        (None, None) if denied => Err(M4ArchError::PermissionDenied),
+        (None, None) => Err(M4ArchError::Unsupported(
+            "power profiles (no platform_profile, tuxedo_io or cpufreq)".to_string(),
+        )),
+    }
+}
+
+/// Trait gabungan untuk ambang pengisian baterai.
+pub trait ChargeController: ChargeControl + Send {}
+
+impl<T: ChargeControl + Send> ChargeController for T {}
+
+/// Mendeteksi mekanisme ambang pengisian baterai.
+pub fn get_charge() -> Result<Box<dyn ChargeController>> {
+    get_charge_at(Path::new(sysfs::DEFAULT_ROOT)).ok_or_else(|| {
+        M4ArchError::Unsupported("charge thresholds (no charge_control attributes)".to_string())
+    })
+}
+
+/// Seperti `get_charge()`, tetapi di bawah root sysfs tertentu. Atribut
+/// kernel diutamakan karena menerima ambang apa pun.
+pub fn get_charge_at(root: &Path) -> Option<Box<dyn ChargeController>> {
+    if let Some(kernel) = battery::KernelThresholds::detect(root) {
+        return Some(Box::new(kernel));
+    }
+    battery::UniwillChargingProfile::detect(root)
+        .map(|uniwill| Box::new(uniwill) as Box<dyn ChargeController>)
+}
+
+/// Trait gabungan untuk tuning CPU.
+pub trait CpuController: CpuControl + Send {}
+
+impl<T: CpuControl + Send> CpuController for T {}
+
+/// Mendeteksi cpufreq.
+pub fn get_cpu() -> Result<Box<dyn CpuController>> {
+    get_cpu_at(Path::new(sysfs::DEFAULT_ROOT))
+        .map(|cpu| Box::new(cpu) as Box<dyn CpuController>)
+        .ok_or_else(|| M4ArchError::Unsupported("CPU tuning (no cpufreq)".to_string()))
+}
+
+/// Seperti `get_cpu()`, tetapi di bawah root sysfs tertentu.
+pub fn get_cpu_at(root: &Path) -> Option<cpu::SysfsCpu> {
+    cpu::SysfsCpu::detect(root).then(|| cpu::SysfsCpu::with_root(root))
+}
 
 /// Mendeteksi dan mengembalikan driver keyboard yang sesuai.
 pub fn get_keyboard() -> Option<Box<dyn KeyboardController>> {
-    // 1. Cek driver spesifik Tuxedo terlebih dahulu (Prioritas Utama)
-    if Path::new("/sys/devices/platform/tuxedo_keyboard/leds/rgb:kbd_backlight/brightness").exists()
-    {
-        return Some(Box::new(tuxedo::TuxedoKeyboard));
+    get_keyboard_at(Path::new(sysfs::DEFAULT_ROOT))
+}
+
+/// Seperti `get_keyboard()`, tetapi mencari di bawah root sysfs tertentu.
+pub fn get_keyboard_at(root: &Path) -> Option<Box<dyn KeyboardController>> {
+    // 1. Cek driver spesifik Tuxedo terlebih dahulu (Prioritas Utama).
+    // Atribut lama mendukung mode firmware, jadi diutamakan jika ada.
+    if tuxedo_legacy::TuxedoLegacyKeyboard::detect(root) {
+        return Some(Box::new(tuxedo_legacy::TuxedoLegacyKeyboard::with_root(
+            root,
+        )));
+    }
+    if tuxedo::TuxedoKeyboard::detect(root) {
+        let zones = tuxedo::TuxedoKeyboard::discover_zones(root)
+            .into_iter()
+            .map(|zone| Box::new(zone) as Box<dyn KeyboardController>)
+            .collect();
+        return zones::ZonedKeyboard::combine(zones);
     }
 
-    // 2. Cek driver generik Sysfs / LED Class (Fallback)
-    // Path ini biasanya symlink, jadi lebih aman dicek setelah driver spesifik
-    if Path::new("/sys/class/leds/rgb:kbd_backlight/brightness").exists() {
-        return Some(Box::new(sysfs::keyboard::SysfsKeyboard));
+    // 2. Cek semua LED keyboard di LED Class (Fallback)
+    // Path ini biasanya symlink, jadi lebih aman dicek setelah driver spesifik.
+    // Discovery mengurutkan LED RGB lebih dulu, jadi ambil kandidat pertama
+    // (beserta zona lainnya); jika kosong berarti tidak ada driver yang cocok.
+    let found = sysfs::discovery::discover_keyboards(root).ok()?;
+    let first = found.first()?;
+    if zones::zone_index(&first.name).is_none() {
+        return found.into_iter().next().map(|keyboard| keyboard.controller);
     }
 
-    // 3. Tidak ditemukan driver yang cocok
-    None
+    let mut zoned: Vec<_> = found
+        .into_iter()
+        .filter_map(|keyboard| Some((zones::zone_index(&keyboard.name)?, keyboard.controller)))
+        .collect();
+    zoned.sort_by_key(|(index, _)| *index);
+    zones::ZonedKeyboard::combine(zoned.into_iter().map(|(_, zone)| zone).collect())
+}
+
+/// Menerapkan state tersimpan ke sebuah controller.
+///
+/// Warna diterapkan lebih dulu agar backlight tidak sempat menyala dengan
+/// warna bawaan firmware. Warna untuk zona yang sudah tidak ada diabaikan.
+/// Mode firmware diterapkan sebelum warna, karena mode `Static` yang
+/// menampilkannya.
+pub fn apply_state(keyboard: &dyn KeyboardController, state: &KeyboardState) -> Result<()> {
+    match state.mode {
+        Some(mode) if !keyboard.modes().is_empty() => keyboard.set_mode(mode)?,
+        _ if state.effect.is_some() => {
+            ensure_static_mode(keyboard)?;
+        }
+        _ => {}
+    }
+    if keyboard.capabilities().rgb {
+        if let Some(rgb) = state.rgb {
+            keyboard.set_rgb(rgb)?;
+        }
+        let count = keyboard.zones().len();
+        for (zone, rgb) in state.zone_colors() {
+            if zone < count {
+                keyboard.set_zone_rgb(zone, rgb)?;
+            }
+        }
+    }
+    if let Some(level) = state.brightness_for(keyboard.get_max_brightness()?) {
+        keyboard.set_brightness(level)?;
+    }
+    Ok(())
+}
+
+/// Membaca state keyboard saat ini, misalnya untuk disimpan sebagai profil.
+pub fn capture_state(keyboard: &dyn KeyboardController) -> Result<KeyboardState> {
+    let mut state = KeyboardState::default();
+    state.record_brightness(keyboard.brightness_info()?);
+    if keyboard.capabilities().rgb {
+        let zones = keyboard.zones();
+        state.record_rgb(keyboard.get_rgb()?);
+        if zones.len() > 1 {
+            for zone in zones {
+                state.record_zone_rgb(zone.index, keyboard.get_zone_rgb(zone.index)?);
+            }
+        }
+    }
+    if !keyboard.modes().is_empty() {
+        state.record_mode(keyboard.get_mode()?);
+    }
+    Ok(state)
+}
+
+/// Mengembalikan keyboard ke mode `Static` agar warna statis dan efek
+/// perangkat lunak terlihat. Mengembalikan `true` jika mode diganti.
+pub fn ensure_static_mode(keyboard: &dyn KeyboardController) -> Result<bool> {
+    if keyboard.modes().is_empty() || keyboard.get_mode()? == KeyboardMode::Static {
+        return Ok(false);
+    }
+    keyboard.set_mode(KeyboardMode::Static)?;
+    Ok(true)
+}
+
+/// Merencanakan transisi dari state hardware saat ini menuju `target`.
+///
+/// Hanya nilai yang disebut `target` yang ikut dianimasikan; zona tanpa warna
+/// tujuan dibiarkan.
+pub fn plan_fade(
+    keyboard: &dyn KeyboardController,
+    target: &KeyboardState,
+    transition: Transition,
+) -> Result<Fade> {
+    let max = keyboard.get_max_brightness()?;
+    let mut fade = Fade::new(transition, max);
+    if let Some(level) = target.brightness_for(max) {
+        fade = fade.brightness(keyboard.get_brightness()?, level);
+    }
+    if keyboard.capabilities().rgb {
+        let zone_colors = target.zone_colors();
+        for zone in keyboard.zones() {
+            let color = zone_colors
+                .iter()
+                .find(|(index, _)| *index == zone.index)
+                .map(|(_, rgb)| *rgb)
+                .or(target.rgb);
+            if let Some(to) = color {
+                fade = fade.color(zone.index, keyboard.get_zone_rgb(zone.index)?, to);
+            }
+        }
+    }
+    Ok(fade)
+}
+
+/// Menulis satu frame transisi ke hardware.
+pub fn write_fade_frame(keyboard: &dyn KeyboardController, frame: &FadeFrame) -> Result<()> {
+    for &(zone, rgb) in &frame.colors {
+        keyboard.set_zone_rgb(zone, rgb)?;
+    }
+    if let Some(level) = frame.brightness {
+        keyboard.set_brightness(level)?;
+    }
+    Ok(())
+}
+
+/// Seperti `apply_state()`, tetapi berpindah secara halus selama durasi
+/// `transition`. Memblokir sampai transisi selesai.
+pub fn fade_state(
+    keyboard: &dyn KeyboardController,
+    target: &KeyboardState,
+    transition: Transition,
+) -> Result<()> {
+    let fade = plan_fade(keyboard, target, transition)?;
+    let started = Instant::now();
+    while !fade.is_finished(started.elapsed()) {
+        write_fade_frame(keyboard, &fade.frame(started.elapsed()))?;
+        thread::sleep(FRAME_INTERVAL);
+    }
+    // Frame terakhir bisa terlewat; tulis nilai akhirnya persis
+    apply_state(keyboard, target)
+}
+
+/// Mengembalikan semua LED keyboard yang ditemukan di LED class.
+pub fn list_keyboards() -> Result<Vec<sysfs::discovery::DiscoveredKeyboard>> {
+    sysfs::discovery::discover_keyboards(Path::new(sysfs::DEFAULT_ROOT))
 }
 
 // === Facade Functions (API Publik untuk CLI/GUI) ===
@@ -60,6 +350,14 @@ pub fn decrease_brightness(step: u8) -> Result<()> {
     get_controller()?.decrease_brightness(step)
 }
 
+pub fn set_brightness_percent(percent: u8) -> Result<()> {
+    get_controller()?.set_brightness_percent(percent)
+}
+
+pub fn step_brightness(direction: StepDirection, steps: u8) -> Result<()> {
+    get_controller()?.step_brightness(direction, steps)
+}
+
 pub fn brightness_info() -> Result<BrightnessInfo> {
     get_controller()?.brightness_info()
 }
@@ -70,5 +368,324 @@ pub fn get_rgb_color() -> Result<(u8, u8, u8)> {
 }
 
 pub fn set_rgb_color(r: u8, g: u8, b: u8) -> Result<()> {
-    get_controller()?.set_rgb(Rgb { r, g, b })
+    let keyboard = get_controller()?;
+    ensure_static_mode(keyboard.as_ref())?;
+    keyboard.set_rgb(Rgb { r, g, b })
+}
+
+pub fn get_capabilities() -> Result<KeyboardCapabilities> {
+    Ok(get_controller()?.capabilities())
+}
+
+pub fn get_zones() -> Result<Vec<KeyboardZone>> {
+    Ok(get_controller()?.zones())
+}
+
+pub fn get_zone_rgb_color(zone: usize) -> Result<(u8, u8, u8)> {
+    let rgb = get_controller()?.get_zone_rgb(zone)?;
+    Ok((rgb.r, rgb.g, rgb.b))
+}
+
+pub fn set_zone_rgb_color(zone: usize, r: u8, g: u8, b: u8) -> Result<()> {
+    let keyboard = get_controller()?;
+    ensure_static_mode(keyboard.as_ref())?;
+    keyboard.set_zone_rgb(zone, Rgb { r, g, b })
+}
+
+pub fn keyboard_modes() -> Result<Vec<KeyboardMode>> {
+    Ok(get_controller()?.modes())
+}
+
+pub fn get_keyboard_mode() -> Result<KeyboardMode> {
+    get_controller()?.get_mode()
+}
+
+pub fn set_keyboard_mode(mode: KeyboardMode) -> Result<()> {
+    get_controller()?.set_mode(mode)
+}
+
+pub fn current_state() -> Result<KeyboardState> {
+    capture_state(get_controller()?.as_ref())
+}
+
+pub fn restore_state(state: &KeyboardState) -> Result<()> {
+    apply_state(get_controller()?.as_ref(), state)
+}
+
+pub fn fade_to_state(state: &KeyboardState, transition: Transition) -> Result<()> {
+    fade_state(get_controller()?.as_ref(), state, transition)
+}
+
+pub fn fan_info() -> Result<Vec<FanInfo>> {
+    let fans = get_fans()?;
+    fans.fans()
+        .into_iter()
+        .map(|fan| fans.fan_info(fan))
+        .collect()
+}
+
+pub fn set_fans_auto() -> Result<()> {
+    get_fans()?.set_fans_auto()
+}
+
+pub fn list_power_profiles() -> Result<Vec<PowerProfile>> {
+    Ok(get_power_profiles()?.profiles())
+}
+
+pub fn get_power_profile() -> Result<PowerProfile> {
+    get_power_profiles()?.get_profile()
+}
+
+pub fn set_power_profile(profile: PowerProfile) -> Result<()> {
+    get_power_profiles()?.set_profile(profile)
+}
+
+pub fn get_charge_thresholds() -> Result<ChargeThresholds> {
+    get_charge()?.get_thresholds()
+}
+
+pub fn set_charge_thresholds(thresholds: ChargeThresholds) -> Result<()> {
+    get_charge()?.set_thresholds(thresholds)
+}
+
+pub fn battery_info() -> Result<Vec<BatteryInfo>> {
+    battery::read_batteries(Path::new(sysfs::DEFAULT_ROOT))
+}
+
+pub fn cpu_status() -> Result<CpuStatus> {
+    get_cpu()?.status()
+}
+
+pub fn tune_cpu(tuning: &CpuTuning) -> Result<()> {
+    get_cpu()?.apply(tuning)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sysfs::fake::{FakeLed, FakeSysfs};
+
+    #[test]
+    fn test_detects_nothing_on_empty_tree() {
+        let fake = FakeSysfs::new().unwrap();
+        assert!(get_keyboard_at(fake.root()).is_none());
+    }
+
+    #[test]
+    fn test_prefers_tuxedo_over_led_class() {
+        let fake = FakeSysfs::new().unwrap();
+        fake.add_led(
+            tuxedo::TuxedoKeyboard::LED_DIR,
+            &FakeLed::rgb(10, 255, (0, 0, 0)),
+        )
+        .unwrap();
+        fake.add_led(
+            sysfs::keyboard::SysfsKeyboard::LED_DIR,
+            &FakeLed::rgb(20, 255, (0, 0, 0)),
+        )
+        .unwrap();
+
+        let kbd = get_keyboard_at(fake.root()).unwrap();
+        assert_eq!(kbd.get_brightness().unwrap(), 10);
+    }
+
+    #[test]
+    fn test_prefers_tuxedo_legacy_attributes() {
+        let fake = FakeSysfs::new().unwrap();
+        fake.add_led(
+            tuxedo::TuxedoKeyboard::LED_DIR,
+            &FakeLed::rgb(10, 255, (0, 0, 0)),
+        )
+        .unwrap();
+        let dir = tuxedo_legacy::TuxedoLegacyKeyboard::DEVICE_DIR;
+        for (attr, value) in [
+            ("brightness", "30"),
+            ("mode", "0"),
+            ("color_left", "ff0000"),
+        ] {
+            fake.write(format!("{dir}/{attr}"), value).unwrap();
+        }
+
+        let kbd = get_keyboard_at(fake.root()).unwrap();
+        assert_eq!(kbd.get_brightness().unwrap(), 30);
+        assert_eq!(kbd.get_mode().unwrap(), KeyboardMode::Static);
+    }
+
+    #[test]
+    fn test_firmware_mode_is_captured_and_restored() {
+        let fake = FakeSysfs::new().unwrap();
+        let dir = tuxedo_legacy::TuxedoLegacyKeyboard::DEVICE_DIR;
+        for (attr, value) in [
+            ("brightness", "30"),
+            ("mode", "7"),
+            ("color_left", "ff0000"),
+        ] {
+            fake.write(format!("{dir}/{attr}"), value).unwrap();
+        }
+
+        let kbd = get_keyboard_at(fake.root()).unwrap();
+        let state = capture_state(kbd.as_ref()).unwrap();
+        assert_eq!(state.mode, Some(KeyboardMode::Wave));
+
+        kbd.set_mode(KeyboardMode::Static).unwrap();
+        apply_state(kbd.as_ref(), &state).unwrap();
+        assert_eq!(kbd.get_mode().unwrap(), KeyboardMode::Wave);
+
+        assert!(ensure_static_mode(kbd.as_ref()).unwrap());
+        assert_eq!(kbd.get_mode().unwrap(), KeyboardMode::Static);
+        assert!(!ensure_static_mode(kbd.as_ref()).unwrap());
+    }
+
+    #[test]
+    fn test_falls_back_to_led_class() {
+        let fake = FakeSysfs::new().unwrap();
+        fake.add_led(
+            sysfs::keyboard::SysfsKeyboard::LED_DIR,
+            &FakeLed::rgb(20, 255, (0, 0, 0)),
+        )
+        .unwrap();
+
+        let kbd = get_keyboard_at(fake.root()).unwrap();
+        assert_eq!(kbd.get_brightness().unwrap(), 20);
+    }
+
+    #[test]
+    fn test_falls_back_to_differently_named_led() {
+        let fake = FakeSysfs::new().unwrap();
+        fake.add_led("class/leds/white:kbd_backlight", &FakeLed::mono(1, 2))
+            .unwrap();
+        fake.add_led(
+            "class/leds/rgb:kbd_backlight_1",
+            &FakeLed::rgb(7, 255, (0, 0, 0)),
+        )
+        .unwrap();
+
+        let kbd = get_keyboard_at(fake.root()).unwrap();
+        assert_eq!(kbd.get_brightness().unwrap(), 7);
+    }
+
+    #[test]
+    fn test_groups_tuxedo_zones() {
+        let fake = FakeSysfs::new().unwrap();
+        for zone in [
+            "rgb:kbd_backlight",
+            "rgb:kbd_backlight_1",
+            "rgb:kbd_backlight_2",
+        ] {
+            let dir = format!("{}/{}", tuxedo::TuxedoKeyboard::LEDS_DIR, zone);
+            fake.add_led(dir, &FakeLed::rgb(0, 255, (0, 0, 0))).unwrap();
+        }
+
+        let kbd = get_keyboard_at(fake.root()).unwrap();
+        assert_eq!(kbd.zones().len(), 3);
+        kbd.set_zone_rgb(2, Rgb { r: 0, g: 0, b: 255 }).unwrap();
+        let path = format!(
+            "{}/rgb:kbd_backlight_2/multi_intensity",
+            tuxedo::TuxedoKeyboard::LEDS_DIR
+        );
+        assert_eq!(fake.read(path).unwrap(), "0 0 255");
+    }
+
+    #[test]
+    fn test_groups_led_class_zones() {
+        let fake = FakeSysfs::new().unwrap();
+        fake.add_led(
+            "class/leds/rgb:kbd_backlight_1",
+            &FakeLed::rgb(0, 255, (1, 1, 1)),
+        )
+        .unwrap();
+        fake.add_led(
+            "class/leds/rgb:kbd_backlight",
+            &FakeLed::rgb(0, 255, (0, 0, 0)),
+        )
+        .unwrap();
+
+        let kbd = get_keyboard_at(fake.root()).unwrap();
+        assert_eq!(kbd.zones().len(), 2);
+        assert_eq!(kbd.get_zone_rgb(1).unwrap(), Rgb { r: 1, g: 1, b: 1 });
+    }
+
+    #[test]
+    fn test_prefers_platform_profile() {
+        let fake = FakeSysfs::new().unwrap();
+        let missing = fake.path("tuxedo_io");
+        assert!(matches!(
+            get_power_profiles_at(fake.root(), &missing),
+            Err(M4ArchError::Unsupported(_))
+        ));
+
+        let dir = platform_profile::ACPI_DIR;
+        fake.write(format!("{dir}/platform_profile_choices"), "quiet balanced")
+            .unwrap();
+        fake.write(format!("{dir}/platform_profile"), "quiet")
+            .unwrap();
+        let profiles = get_power_profiles_at(fake.root(), &missing).unwrap();
+        assert_eq!(profiles.get_profile().unwrap(), PowerProfile::Quiet);
+    }
+
+    #[test]
+    fn test_denied_tuxedo_io_falls_back_to_cpufreq() {
+        let denied = || Err(std::io::Error::from(std::io::ErrorKind::PermissionDenied));
+        assert!(matches!(
+            compose_power_profiles(denied(), None),
+            Err(M4ArchError::PermissionDenied)
+        ));
+
+        let fake = FakeSysfs::new().unwrap();
+        let cpu = cpu::SysfsCpu::with_root(fake.root());
+        let profiles = compose_power_profiles(denied(), Some(cpu)).unwrap();
+        assert_eq!(profiles.profiles(), PowerProfile::ALL.to_vec());
+    }
+
+    #[test]
+    fn test_apply_state() {
+        let fake = FakeSysfs::new().unwrap();
+        let leds = sysfs::discovery::LEDS_DIR;
+        for zone in ["rgb:kbd_backlight", "rgb:kbd_backlight_1"] {
+            fake.add_led(format!("{leds}/{zone}"), &FakeLed::rgb(255, 255, (0, 0, 0)))
+                .unwrap();
+        }
+        let kbd = get_keyboard_at(fake.root()).unwrap();
+
+        let mut state = KeyboardState::default();
+        state.record_rgb(Rgb { r: 1, g: 2, b: 3 });
+        state.record_zone_rgb(1, Rgb { r: 4, g: 5, b: 6 });
+        state.record_zone_rgb(5, Rgb { r: 7, g: 8, b: 9 });
+        state.record_brightness(BrightnessInfo {
+            current: 2,
+            max: 4,
+            percent: 50,
+            is_on: true,
+        });
+        apply_state(kbd.as_ref(), &state).unwrap();
+
+        let read = |attr: &str| {
+            fake.read(format!("{leds}/rgb:kbd_backlight/{attr}"))
+                .unwrap()
+        };
+        assert_eq!(read("multi_intensity"), "1 2 3");
+        assert_eq!(read("brightness"), "128");
+        let zone1 = fake
+            .read(format!("{leds}/rgb:kbd_backlight_1/multi_intensity"))
+            .unwrap();
+        assert_eq!(zone1, "4 5 6");
+
+        let mut target = KeyboardState::default();
+        target.record_zone_rgb(1, Rgb { r: 0, g: 0, b: 0 });
+        target.record_brightness(BrightnessInfo::new(255, 255));
+        let fade = plan_fade(kbd.as_ref(), &target, Transition::from_millis(10)).unwrap();
+        let start = fade.frame(std::time::Duration::ZERO);
+        assert_eq!(start.brightness, Some(128));
+        assert_eq!(start.colors, [(1, Rgb { r: 4, g: 5, b: 6 })]);
+
+        let captured = capture_state(kbd.as_ref()).unwrap();
+        assert_eq!(
+            captured.brightness.as_ref().map(|info| info.current),
+            Some(128)
+        );
+        assert_eq!(
+            captured.zone_colors(),
+            [(0, Rgb { r: 1, g: 2, b: 3 }), (1, Rgb { r: 4, g: 5, b: 6 })]
+        );
+    }
 }
@@ -0,0 +1,127 @@
+//! Profil performa lewat ACPI `platform_profile` kernel.
+//!
+//! `platform_profile_choices` berisi nama-nama yang didukung firmware
+//! (misalnya `low-power balanced performance`), dan `platform_profile`
+//! berisi profil aktif. Beberapa nama kernel dipetakan ke satu
+//! `PowerProfile`; nama pertama yang tersedia yang dipakai saat menulis.
+use crate::sysfs::{map_io_error, write_value};
+use m4arch_core::error::{M4ArchError, Result};
+use m4arch_core::power::{PowerProfile, PowerProfileControl};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Direktori atribut relatif terhadap root sysfs.
+pub const ACPI_DIR: &str = "firmware/acpi";
+
+/// Nama kernel untuk setiap profil, dari yang paling cocok.
+fn kernel_names(profile: PowerProfile) -> &'static [&'static str] {
+    match profile {
+        PowerProfile::Quiet => &["quiet", "low-power", "cool"],
+        PowerProfile::Balanced => &["balanced"],
+        PowerProfile::Performance => &["performance", "balanced-performance"],
+    }
+}
+
+pub struct PlatformProfile {
+    dir: PathBuf,
+}
+
+impl PlatformProfile {
+    pub fn with_root(root: impl AsRef<Path>) -> Self {
+        Self {
+            dir: root.as_ref().join(ACPI_DIR),
+        }
+    }
+
+    /// Apakah kernel memublikasikan `platform_profile`.
+    pub fn detect(root: &Path) -> bool {
+        let dir = root.join(ACPI_DIR);
+        dir.join("platform_profile").exists() && dir.join("platform_profile_choices").exists()
+    }
+
+    fn choices(&self) -> Result<Vec<String>> {
+        let content =
+            fs::read_to_string(self.dir.join("platform_profile_choices")).map_err(map_io_error)?;
+        Ok(content.split_whitespace().map(str::to_string).collect())
+    }
+
+    /// Nama kernel yang tersedia untuk `profile`.
+    fn kernel_name(&self, profile: PowerProfile) -> Result<Option<&'static str>> {
+        let choices = self.choices()?;
+        Ok(kernel_names(profile)
+            .iter()
+            .copied()
+            .find(|name| choices.iter().any(|choice| choice == name)))
+    }
+}
+
+impl PowerProfileControl for PlatformProfile {
+    fn profiles(&self) -> Vec<PowerProfile> {
+        PowerProfile::ALL
+            .into_iter()
+            .filter(|profile| matches!(self.kernel_name(*profile), Ok(Some(_))))
+            .collect()
+    }
+
+    fn get_profile(&self) -> Result<PowerProfile> {
+        let content =
+            fs::read_to_string(self.dir.join("platform_profile")).map_err(map_io_error)?;
+        let name = content.trim();
+        PowerProfile::ALL
+            .into_iter()
+            .find(|profile| kernel_names(*profile).contains(&name))
+            .ok_or_else(|| M4ArchError::Unsupported(format!("platform profile '{}'", name)))
+    }
+
+    fn set_profile(&self, profile: PowerProfile) -> Result<()> {
+        let name = self
+            .kernel_name(profile)?
+            .ok_or_else(|| M4ArchError::Unsupported(format!("{} power profile", profile)))?;
+        write_value(&self.dir.join("platform_profile"), name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sysfs::fake::FakeSysfs;
+
+    fn setup(choices: &str, current: &str) -> (FakeSysfs, PlatformProfile) {
+        let fake = FakeSysfs::new().unwrap();
+        fake.write(format!("{ACPI_DIR}/platform_profile_choices"), choices)
+            .unwrap();
+        fake.write(format!("{ACPI_DIR}/platform_profile"), current)
+            .unwrap();
+        let profile = PlatformProfile::with_root(fake.root());
+        (fake, profile)
+    }
+
+    #[test]
+    fn test_maps_kernel_names() {
+        let (fake, platform) = setup("low-power balanced performance\n", "balanced\n");
+        assert!(PlatformProfile::detect(fake.root()));
+        assert_eq!(platform.profiles(), PowerProfile::ALL);
+        assert_eq!(platform.get_profile().unwrap(), PowerProfile::Balanced);
+
+        platform.set_profile(PowerProfile::Quiet).unwrap();
+        let path = format!("{ACPI_DIR}/platform_profile");
+        assert_eq!(fake.read(&path).unwrap(), "low-power");
+        assert_eq!(platform.get_profile().unwrap(), PowerProfile::Quiet);
+
+        fake.write(&path, "custom").unwrap();
+        assert!(platform.get_profile().is_err());
+    }
+
+    #[test]
+    fn test_missing_choice_is_unsupported() {
+        let (_fake, platform) = setup("balanced performance", "performance");
+        assert_eq!(
+            platform.profiles(),
+            [PowerProfile::Balanced, PowerProfile::Performance]
+        );
+        assert!(matches!(
+            platform.set_profile(PowerProfile::Quiet),
+            Err(M4ArchError::Unsupported(_))
+        ));
+    }
+}
@@ -0,0 +1,197 @@
+//! Membaca sumber daya dan sisa baterai dari kelas `power_supply` kernel.
+//!
+//! Adaptor AC (`type` = `Mains` atau `USB`) melaporkan `online`, baterai
+//! melaporkan `capacity`. Baterai perangkat lain (mouse, keyboard nirkabel)
+//! ditandai `scope` = `Device` dan diabaikan.
+use crate::sysfs::{map_io_error, read_value};
+use m4arch_core::config::{PowerSource, PowerStatus};
+use m4arch_core::error::Result;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Direktori kelas power_supply relatif terhadap root sysfs.
+pub const POWER_SUPPLY_DIR: &str = "class/power_supply";
+
+/// Sumber daya milik sistem beserta `type`-nya, terurut menurut nama.
+///
+/// Entri ber-`scope` `Device` dilewati; `type` kosong jika tidak terbaca.
+fn system_supplies(root: &Path) -> Result<Vec<(PathBuf, String)>> {
+    let entries = match fs::read_dir(root.join(POWER_SUPPLY_DIR)) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(map_io_error(e)),
+    };
+    let mut supplies = Vec::new();
+    for entry in entries {
+        let dir = entry?.path();
+        let attr = |name: &str| {
+            fs::read_to_string(dir.join(name))
+                .map(|value| value.trim().to_string())
+                .unwrap_or_default()
+        };
+        if attr("scope") != "Device" {
+            let kind = attr("type");
+            supplies.push((dir, kind));
+        }
+    }
+    supplies.sort();
+    Ok(supplies)
+}
+
+/// Direktori baterai sistem (bukan baterai mouse atau keyboard nirkabel),
+/// terurut menurut nama.
+pub fn system_batteries(root: &Path) -> Result<Vec<PathBuf>> {
+    Ok(system_supplies(root)?
+        .into_iter()
+        .filter(|(_, kind)| kind == "Battery")
+        .map(|(dir, _)| dir)
+        .collect())
+}
+
+/// Membaca keadaan daya di bawah root sysfs.
+///
+/// `None` jika tidak ada adaptor maupun baterai, misalnya di desktop.
+pub fn read_status(root: &Path) -> Result<Option<PowerStatus>> {
+    let mut adapters = 0;
+    let mut online = false;
+    let mut capacities = Vec::new();
+    let mut discharging = false;
+    for (dir, kind) in system_supplies(root)? {
+        match kind.as_str() {
+            "Battery" => {
+                if let Ok(capacity) = read_value::<u8>(&dir.join("capacity")) {
+                    capacities.push(capacity.min(100));
+                }
+                let status = fs::read_to_string(dir.join("status")).unwrap_or_default();
+                discharging |= status.trim() == "Discharging";
+            }
+            // UCSI melaporkan 2 untuk "online" lewat USB-C; adaptor yang
+            // `online`-nya tidak terbaca dilewati seperti perangkat lain
+            _ => {
+                if let Ok(value) = read_value::<u8>(&dir.join("online")) {
+                    adapters += 1;
+                    online |= value != 0;
+                }
+            }
+        }
+    }
+
+    if adapters == 0 && capacities.is_empty() {
+        return Ok(None);
+    }
+    // Tanpa adaptor yang terdaftar, status baterai satu-satunya petunjuk
+    let on_battery = if adapters > 0 { !online } else { discharging };
+    let capacity = (!capacities.is_empty()).then(|| {
+        let total: u32 = capacities.iter().map(|&c| u32::from(c)).sum();
+        (total / capacities.len() as u32) as u8
+    });
+    Ok(Some(PowerStatus {
+        source: if on_battery {
+            PowerSource::Battery
+        } else {
+            PowerSource::Ac
+        },
+        capacity,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sysfs::fake::FakeSysfs;
+
+    fn supply(fake: &FakeSysfs, name: &str, attributes: &[(&str, &str)]) {
+        for (attribute, value) in attributes {
+            fake.write(format!("{POWER_SUPPLY_DIR}/{name}/{attribute}"), value)
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_reads_ac_and_battery() {
+        let fake = FakeSysfs::new().unwrap();
+        assert_eq!(read_status(fake.root()).unwrap(), None);
+
+        supply(&fake, "ADP1", &[("type", "Mains"), ("online", "1")]);
+        supply(
+            &fake,
+            "BAT0",
+            &[
+                ("type", "Battery"),
+                ("capacity", "42"),
+                ("status", "Charging"),
+            ],
+        );
+        // Baterai mouse tidak dihitung
+        supply(
+            &fake,
+            "hid-mouse-battery",
+            &[("type", "Battery"), ("scope", "Device"), ("capacity", "5")],
+        );
+        let status = read_status(fake.root()).unwrap().unwrap();
+        assert_eq!(status.source, PowerSource::Ac);
+        assert_eq!(status.capacity, Some(42));
+
+        fake.write(format!("{POWER_SUPPLY_DIR}/ADP1/online"), "0")
+            .unwrap();
+        let status = read_status(fake.root()).unwrap().unwrap();
+        assert_eq!(status.source, PowerSource::Battery);
+    }
+
+    #[test]
+    fn test_ucsi_online_counts_as_ac() {
+        let fake = FakeSysfs::new().unwrap();
+        supply(
+            &fake,
+            "ucsi-source-psy-USBC000:001",
+            &[("type", "USB"), ("online", "2")],
+        );
+        supply(
+            &fake,
+            "BAT0",
+            &[
+                ("type", "Battery"),
+                ("capacity", "60"),
+                ("status", "Charging"),
+            ],
+        );
+        let status = read_status(fake.root()).unwrap().unwrap();
+        assert_eq!(status.source, PowerSource::Ac);
+    }
+
+    #[test]
+    fn test_unreadable_adapter_is_skipped() {
+        let fake = FakeSysfs::new().unwrap();
+        supply(&fake, "ADP1", &[("type", "Mains"), ("online", "")]);
+        supply(
+            &fake,
+            "BAT0",
+            &[
+                ("type", "Battery"),
+                ("capacity", "30"),
+                ("status", "Discharging"),
+            ],
+        );
+        let status = read_status(fake.root()).unwrap().unwrap();
+        assert_eq!(status.source, PowerSource::Battery);
+        assert_eq!(status.capacity, Some(30));
+    }
+
+    #[test]
+    fn test_battery_without_adapter_uses_status() {
+        let fake = FakeSysfs::new().unwrap();
+        supply(
+            &fake,
+            "BAT0",
+            &[
+                ("type", "Battery"),
+                ("capacity", "80"),
+                ("status", "Discharging"),
+            ],
+        );
+        let status = read_status(fake.root()).unwrap().unwrap();
+        assert_eq!(status.source, PowerSource::Battery);
+        assert_eq!(status.capacity, Some(80));
+    }
+}
@@ -0,0 +1,165 @@
+//! Penemuan (discovery) LED keyboard melalui LED class kernel.
+//!
+//! Menelusuri `<root>/class/leds` dan mengklasifikasikan setiap LED keyboard
+//! berdasarkan atribut yang dipublikasikannya, sehingga binary yang sama bisa
+//! berjalan di model Axioo/Clevo lain yang nama LED-nya berbeda.
+use super::keyboard::SysfsKeyboard;
+use crate::KeyboardController;
+use m4arch_core::error::{M4ArchError, Result};
+use m4arch_core::keyboard::KeyboardCapabilities;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Direktori LED class relatif terhadap root sysfs.
+pub const LEDS_DIR: &str = "class/leds";
+
+/// Satu LED keyboard yang ditemukan beserta driver-nya.
+pub struct DiscoveredKeyboard {
+    /// Nama LED di LED class, misalnya `rgb:kbd_backlight_1`
+    pub name: String,
+
+    /// Path ke direktori LED
+    pub path: PathBuf,
+
+    /// Kemampuan yang terdeteksi dari atribut sysfs
+    pub capabilities: KeyboardCapabilities,
+
+    /// Driver untuk mengontrol LED ini
+    pub controller: Box<dyn KeyboardController>,
+}
+
+/// Apakah nama LED merupakan backlight keyboard.
+///
+/// Mengenali `*::kbd_backlight` (vendor generik), `rgb:kbd_backlight*`
+/// (termasuk zona `_1`, `_2`, ...) dan `white:kbd_backlight`.
+pub fn is_keyboard_led(name: &str) -> bool {
+    name.ends_with("::kbd_backlight")
+        || name.starts_with("rgb:kbd_backlight")
+        || name == "white:kbd_backlight"
+}
+
+/// Mencari semua LED keyboard di bawah root sysfs.
+///
+/// LED RGB diurutkan lebih dulu, lalu berdasarkan nama. LED tanpa atribut
+/// `brightness` diabaikan. Jika LED class tidak ada, hasilnya kosong.
+pub fn discover_keyboards(root: &Path) -> Result<Vec<DiscoveredKeyboard>> {
+    let entries = match fs::read_dir(root.join(LEDS_DIR)) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(M4ArchError::Io(e)),
+    };
+
+    let mut found = Vec::new();
+    for entry in entries {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if !is_keyboard_led(&name) {
+            continue;
+        }
+
+        let keyboard = SysfsKeyboard::from_led_dir(entry.path());
+        let capabilities = keyboard.capabilities();
+        if !capabilities.brightness {
+            continue;
+        }
+
+        found.push(DiscoveredKeyboard {
+            name,
+            path: entry.path(),
+            capabilities,
+            controller: Box::new(keyboard),
+        });
+    }
+
+    found.sort_by(|a, b| {
+        b.capabilities
+            .rgb
+            .cmp(&a.capabilities.rgb)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    Ok(found)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sysfs::fake::{FakeLed, FakeSysfs};
+
+    fn names(found: &[DiscoveredKeyboard]) -> Vec<&str> {
+        found.iter().map(|k| k.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_is_keyboard_led() {
+        assert!(is_keyboard_led("rgb:kbd_backlight"));
+        assert!(is_keyboard_led("rgb:kbd_backlight_2"));
+        assert!(is_keyboard_led("white:kbd_backlight"));
+        assert!(is_keyboard_led("asus::kbd_backlight"));
+        assert!(!is_keyboard_led("input3::capslock"));
+        assert!(!is_keyboard_led("phy0-led"));
+    }
+
+    #[test]
+    fn test_missing_led_class_is_empty() {
+        let fake = FakeSysfs::new().unwrap();
+        assert!(discover_keyboards(fake.root()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_classifies_and_sorts_leds() {
+        let fake = FakeSysfs::new().unwrap();
+        let leds = |name: &str| format!("{LEDS_DIR}/{name}");
+        fake.add_led(leds("white:kbd_backlight"), &FakeLed::mono(1, 2))
+            .unwrap();
+        fake.add_led(
+            leds("rgb:kbd_backlight_1"),
+            &FakeLed::rgb(0, 255, (0, 0, 0)),
+        )
+        .unwrap();
+        fake.add_led(leds("rgb:kbd_backlight"), &FakeLed::rgb(0, 255, (0, 0, 0)))
+            .unwrap();
+        fake.write(leds("rgb:kbd_backlight/multi_index"), "red green blue")
+            .unwrap();
+        fake.add_led(leds("input3::capslock"), &FakeLed::mono(0, 1))
+            .unwrap();
+
+        let found = discover_keyboards(fake.root()).unwrap();
+        assert_eq!(
+            names(&found),
+            [
+                "rgb:kbd_backlight",
+                "rgb:kbd_backlight_1",
+                "white:kbd_backlight"
+            ]
+        );
+        assert!(found[0].capabilities.channel_index);
+        assert!(!found[1].capabilities.channel_index);
+        assert!(!found[2].capabilities.rgb);
+        assert_eq!(found[2].controller.get_max_brightness().unwrap(), 2);
+    }
+
+    #[test]
+    fn test_follows_symlinked_devices() {
+        let fake = FakeSysfs::new().unwrap();
+        let device = "devices/platform/clevo/leds/clevo::kbd_backlight";
+        fake.add_led(device, &FakeLed::mono(3, 5)).unwrap();
+        fake.symlink(format!("{LEDS_DIR}/clevo::kbd_backlight"), device)
+            .unwrap();
+
+        let found = discover_keyboards(fake.root()).unwrap();
+        assert_eq!(names(&found), ["clevo::kbd_backlight"]);
+        assert_eq!(found[0].controller.get_brightness().unwrap(), 3);
+    }
+
+    #[test]
+    fn test_skips_leds_without_brightness() {
+        let fake = FakeSysfs::new().unwrap();
+        fake.write(
+            format!("{LEDS_DIR}/rgb:kbd_backlight/max_brightness"),
+            "255",
+        )
+        .unwrap();
+        assert!(discover_keyboards(fake.root()).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,106 @@
+//! Pohon sysfs palsu di direktori sementara untuk pengujian.
+//!
+//! Hanya tersedia saat `cfg(test)` atau dengan fitur `fake-sysfs`.
+//!
+//! ```ignore
+//! let fake = FakeSysfs::new()?;
+//! fake.add_led(TuxedoKeyboard::LED_DIR, &FakeLed::rgb(128, 255, (255, 0, 0)))?;
+//! let kbd = TuxedoKeyboard::with_root(fake.root());
+//! ```
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+/// Isi atribut untuk satu perangkat LED palsu.
+#[derive(Debug, Clone)]
+pub struct FakeLed {
+    pub brightness: u8,
+    pub max_brightness: u8,
+    /// Isi `multi_intensity`; `None` untuk LED monokrom.
+    pub multi_intensity: Option<String>,
+}
+
+impl FakeLed {
+    /// LED monokrom tanpa `multi_intensity`.
+    pub fn mono(brightness: u8, max_brightness: u8) -> Self {
+        Self {
+            brightness,
+            max_brightness,
+            multi_intensity: None,
+        }
+    }
+
+    /// LED RGB dengan `multi_intensity` berformat `"r g b"`.
+    pub fn rgb(brightness: u8, max_brightness: u8, (r, g, b): (u8, u8, u8)) -> Self {
+        Self {
+            brightness,
+            max_brightness,
+            multi_intensity: Some(format!("{} {} {}", r, g, b)),
+        }
+    }
+}
+
+/// Root sysfs palsu yang otomatis dihapus saat di-drop.
+pub struct FakeSysfs {
+    dir: TempDir,
+}
+
+impl FakeSysfs {
+    pub fn new() -> io::Result<Self> {
+        Ok(Self {
+            dir: tempfile::Builder::new().prefix("m4arch-sysfs").tempdir()?,
+        })
+    }
+
+    /// Root yang bisa diberikan ke `with_root()` milik driver.
+    pub fn root(&self) -> &Path {
+        self.dir.path()
+    }
+
+    /// Path absolut untuk path relatif terhadap root.
+    pub fn path(&self, rel: impl AsRef<Path>) -> PathBuf {
+        self.dir.path().join(rel)
+    }
+
+    /// Membuat direktori LED beserta atributnya, mengembalikan path absolutnya.
+    pub fn add_led(&self, rel_dir: impl AsRef<Path>, led: &FakeLed) -> io::Result<PathBuf> {
+        let dir = self.path(rel_dir);
+        fs::create_dir_all(&dir)?;
+        fs::write(dir.join("brightness"), led.brightness.to_string())?;
+        fs::write(dir.join("max_brightness"), led.max_brightness.to_string())?;
+        if let Some(intensity) = &led.multi_intensity {
+            fs::write(dir.join("multi_intensity"), intensity)?;
+        }
+        Ok(dir)
+    }
+
+    /// Menulis (atau menimpa) sebuah atribut, membuat direktori induk bila perlu.
+    pub fn write(&self, rel: impl AsRef<Path>, contents: impl AsRef<[u8]>) -> io::Result<()> {
+        let path = self.path(rel);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, contents)
+    }
+
+    /// Membaca isi atribut apa adanya.
+    pub fn read(&self, rel: impl AsRef<Path>) -> io::Result<String> {
+        fs::read_to_string(self.path(rel))
+    }
+
+    /// Membuat symlink `link` yang menunjuk ke `target` (keduanya relatif
+    /// terhadap root), seperti entri di `/sys/class/leds`.
+    pub fn symlink(&self, link: impl AsRef<Path>, target: impl AsRef<Path>) -> io::Result<()> {
+        let link = self.path(link);
+        if let Some(parent) = link.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        std::os::unix::fs::symlink(self.path(target), link)
+    }
+
+    /// Menghapus sebuah atribut untuk mensimulasikan file yang hilang.
+    pub fn remove(&self, rel: impl AsRef<Path>) -> io::Result<()> {
+        fs::remove_file(self.path(rel))
+    }
+}
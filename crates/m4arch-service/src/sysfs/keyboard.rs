@@ -1,55 +1,189 @@
-use std::fs;
+use std::path::{Path, PathBuf};
 
 use m4arch_core::error::{M4ArchError, Result};
 use m4arch_core::keyboard::{
     brightness::KeyboardBrightness,
+    controller::KeyboardCapabilities,
     rgb::{KeyboardRgb, Rgb},
 };
 
-const COLOR_PATH: &str = "/sys/class/leds/rgb:kbd_backlight/multi_intensity";
-const BRIGHTNESS_PATH: &str = "/sys/class/leds/rgb:kbd_backlight/brightness";
-const MAX_BRIGHTNESS_PATH: &str = "/sys/class/leds/rgb:kbd_backlight/max_brightness";
+use super::multicolor::LayoutCache;
+use super::{led_capabilities, read_hw_changed, read_value, write_value, DEFAULT_ROOT};
+use crate::KeyboardController;
 
-pub struct SysfsKeyboard;
+/// Driver generik untuk LED class (`/sys/class/leds`).
+///
+/// Bisa menunjuk ke LED apa pun, baik RGB maupun monokrom; lihat
+/// `sysfs::discovery` untuk menemukan semua LED keyboard.
+pub struct SysfsKeyboard {
+    led_dir: PathBuf,
+    rgb: LayoutCache,
+}
+
+impl SysfsKeyboard {
+    /// Direktori LED relatif terhadap root sysfs.
+    pub const LED_DIR: &'static str = "class/leds/rgb:kbd_backlight";
+
+    pub fn new() -> Self {
+        Self::with_root(DEFAULT_ROOT)
+    }
+
+    /// Membuat driver dengan root sysfs kustom (misalnya `FakeSysfs`).
+    pub fn with_root(root: impl AsRef<Path>) -> Self {
+        Self::from_led_dir(root.as_ref().join(Self::LED_DIR))
+    }
+
+    /// Membuat driver untuk direktori LED tertentu.
+    pub fn from_led_dir(led_dir: impl Into<PathBuf>) -> Self {
+        let led_dir = led_dir.into();
+        Self {
+            rgb: LayoutCache::new(&led_dir),
+            led_dir,
+        }
+    }
+
+    /// Apakah LED class tersedia di bawah root yang diberikan.
+    pub fn detect(root: &Path) -> bool {
+        root.join(Self::LED_DIR).join("brightness").exists()
+    }
+
+    /// Direktori LED yang dikontrol driver ini.
+    pub fn led_dir(&self) -> &Path {
+        &self.led_dir
+    }
+
+    fn ensure_rgb(&self) -> Result<()> {
+        if self.led_dir.join("multi_intensity").exists() {
+            Ok(())
+        } else {
+            Err(M4ArchError::Unsupported(
+                "monochrome backlight has no RGB control".to_string(),
+            ))
+        }
+    }
+}
+
+impl Default for SysfsKeyboard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl KeyboardController for SysfsKeyboard {
+    fn capabilities(&self) -> KeyboardCapabilities {
+        led_capabilities(&self.led_dir)
+    }
+
+    fn brightness_hw_changed(&self) -> Result<Option<u8>> {
+        read_hw_changed(&self.led_dir)
+    }
+}
 
 impl KeyboardBrightness for SysfsKeyboard {
     fn get_brightness(&self) -> Result<u8> {
-        let content = fs::read_to_string(BRIGHTNESS_PATH)?;
-        content
-            .trim()
-            .parse()
-            .map_err(|_| M4ArchError::InvalidValue)
+        read_value(&self.led_dir.join("brightness"))
     }
 
     fn get_max_brightness(&self) -> Result<u8> {
-        let content = fs::read_to_string(MAX_BRIGHTNESS_PATH)?;
-        content
-            .trim()
-            .parse()
-            .map_err(|_| M4ArchError::InvalidValue)
+        read_value(&self.led_dir.join("max_brightness"))
     }
 
     fn set_brightness(&self, level: u8) -> Result<()> {
-        fs::write(BRIGHTNESS_PATH, level.to_string())?;
-        Ok(())
+        write_value(&self.led_dir.join("brightness"), level)
     }
 }
 
 impl KeyboardRgb for SysfsKeyboard {
     fn get_rgb(&self) -> Result<Rgb> {
-        let content = fs::read_to_string(COLOR_PATH)?;
-        let parts: Vec<&str> = content.trim().split_whitespace().collect();
-        if parts.len() < 3 {
-            return Err(M4ArchError::InvalidValue);
-        }
-        let r = parts[0].parse().map_err(|_| M4ArchError::InvalidValue)?;
-        let g = parts[1].parse().map_err(|_| M4ArchError::InvalidValue)?;
-        let b = parts[2].parse().map_err(|_| M4ArchError::InvalidValue)?;
-        Ok(Rgb { r, g, b })
+        self.ensure_rgb()?;
+        self.rgb.read_rgb()
     }
 
     fn set_rgb(&self, rgb: Rgb) -> Result<()> {
-        fs::write(COLOR_PATH, format!("{} {} {}", rgb.r, rgb.g, rgb.b))?;
-        Ok(())
+        self.ensure_rgb()?;
+        self.rgb.write_rgb(rgb)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sysfs::fake::{FakeLed, FakeSysfs};
+
+    fn setup(led: FakeLed) -> (FakeSysfs, SysfsKeyboard) {
+        let fake = FakeSysfs::new().unwrap();
+        fake.add_led(SysfsKeyboard::LED_DIR, &led).unwrap();
+        let kbd = SysfsKeyboard::with_root(fake.root());
+        (fake, kbd)
+    }
+
+    #[test]
+    fn test_read_and_write_brightness() {
+        let (fake, kbd) = setup(FakeLed::rgb(10, 255, (0, 0, 0)));
+        assert_eq!(kbd.get_brightness().unwrap(), 10);
+        assert_eq!(kbd.get_max_brightness().unwrap(), 255);
+
+        kbd.set_brightness(200).unwrap();
+        let path = format!("{}/brightness", SysfsKeyboard::LED_DIR);
+        assert_eq!(fake.read(path).unwrap(), "200");
+    }
+
+    #[test]
+    fn test_read_and_write_rgb() {
+        let (fake, kbd) = setup(FakeLed::rgb(10, 255, (1, 2, 3)));
+        assert_eq!(kbd.get_rgb().unwrap(), Rgb { r: 1, g: 2, b: 3 });
+
+        kbd.set_rgb(Rgb {
+            r: 255,
+            g: 0,
+            b: 128,
+        })
+        .unwrap();
+        let path = format!("{}/multi_intensity", SysfsKeyboard::LED_DIR);
+        assert_eq!(fake.read(path).unwrap(), "255 0 128");
+    }
+
+    #[test]
+    fn test_brightness_hw_changed() {
+        let (fake, kbd) = setup(FakeLed::mono(1, 3));
+        assert_eq!(kbd.brightness_hw_changed().unwrap(), None);
+
+        let path = format!("{}/brightness_hw_changed", SysfsKeyboard::LED_DIR);
+        fake.write(path, "2\n").unwrap();
+        assert_eq!(kbd.brightness_hw_changed().unwrap(), Some(2));
+    }
+
+    #[test]
+    fn test_detect() {
+        let fake = FakeSysfs::new().unwrap();
+        assert!(!SysfsKeyboard::detect(fake.root()));
+        fake.add_led(SysfsKeyboard::LED_DIR, &FakeLed::mono(0, 3))
+            .unwrap();
+        assert!(SysfsKeyboard::detect(fake.root()));
+    }
+
+    #[test]
+    fn test_monochrome_rgb_is_unsupported() {
+        let (_fake, kbd) = setup(FakeLed::mono(1, 3));
+        assert!(!kbd.capabilities().rgb);
+        assert!(matches!(kbd.get_rgb(), Err(M4ArchError::Unsupported(_))));
+        assert!(matches!(
+            kbd.set_rgb(Rgb { r: 1, g: 1, b: 1 }),
+            Err(M4ArchError::Unsupported(_))
+        ));
+    }
+
+    #[test]
+    fn test_malformed_values_are_invalid() {
+        let (fake, kbd) = setup(FakeLed::rgb(1, 255, (0, 0, 0)));
+        let dir = SysfsKeyboard::LED_DIR;
+        fake.write(format!("{dir}/brightness"), "abc").unwrap();
+        fake.write(format!("{dir}/multi_intensity"), "255 0")
+            .unwrap();
+        assert!(matches!(
+            kbd.get_brightness(),
+            Err(M4ArchError::InvalidValue)
+        ));
+        assert!(matches!(kbd.get_rgb(), Err(M4ArchError::InvalidValue)));
     }
 }
@@ -1 +1,76 @@
+//! Utilitas bersama untuk membaca dan menulis atribut sysfs.
+//!
+//! Semua driver menyelesaikan path-nya relatif terhadap sebuah root sysfs
+//! (default `/sys`) sehingga bisa diarahkan ke pohon palsu saat pengujian.
+use m4arch_core::error::{M4ArchError, Result};
+use m4arch_core::keyboard::KeyboardCapabilities;
+use std::fmt::Display;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+
+pub mod discovery;
+#[cfg(any(test, feature = "fake-sysfs"))]
+pub mod fake;
 pub mod keyboard;
+pub mod multicolor;
+
+/// Root sysfs yang dipakai di sistem sungguhan.
+pub const DEFAULT_ROOT: &str = "/sys";
+
+/// Kemampuan sebuah direktori LED berdasarkan atribut yang tersedia.
+pub(crate) fn led_capabilities(led_dir: &Path) -> KeyboardCapabilities {
+    let has = |attr: &str| led_dir.join(attr).exists();
+    KeyboardCapabilities {
+        brightness: has("brightness"),
+        max_brightness: has("max_brightness"),
+        rgb: has("multi_intensity"),
+        channel_index: has("multi_index"),
+    }
+}
+
+/// Membaca atribut sysfs dan mem-parse isinya.
+pub(crate) fn read_value<T: FromStr>(path: &Path) -> Result<T> {
+    let content = fs::read_to_string(path).map_err(map_io_error)?;
+    content
+        .trim()
+        .parse()
+        .map_err(|_| M4ArchError::InvalidValue)
+}
+
+/// Menulis sebuah nilai ke atribut sysfs.
+pub(crate) fn write_value(path: &Path, value: impl Display) -> Result<()> {
+    fs::write(path, value.to_string()).map_err(map_io_error)
+}
+
+/// Membaca `brightness_hw_changed`: brightness terakhir yang diubah oleh
+/// hardware (misalnya hotkey Fn) tanpa lewat software.
+///
+/// `None` jika atribut tidak ada atau kernel belum mencatat perubahan
+/// (kernel membalas `ENODATA`).
+pub(crate) fn read_hw_changed(led_dir: &Path) -> Result<Option<u8>> {
+    const ENODATA: i32 = 61;
+
+    match fs::read_to_string(led_dir.join("brightness_hw_changed")) {
+        Ok(content) => content
+            .trim()
+            .parse()
+            .map(Some)
+            .map_err(|_| M4ArchError::InvalidValue),
+        Err(e) if e.kind() == io::ErrorKind::NotFound || e.raw_os_error() == Some(ENODATA) => {
+            Ok(None)
+        }
+        Err(e) => Err(map_io_error(e)),
+    }
+}
+
+/// Akses ditolak oleh kernel diterjemahkan menjadi `PermissionDenied`
+/// agar pesan error ke pengguna lebih jelas.
+pub(crate) fn map_io_error(e: io::Error) -> M4ArchError {
+    if e.kind() == io::ErrorKind::PermissionDenied {
+        M4ArchError::PermissionDenied
+    } else {
+        M4ArchError::Io(e)
+    }
+}
@@ -0,0 +1,284 @@
+//! Konversi `Rgb` ke atribut multicolor LED class kernel.
+//!
+//! Kernel memublikasikan urutan channel di `multi_index` (misalnya
+//! `"green red blue"`) dan batas nilai tiap channel sama dengan
+//! `max_brightness`. Modul ini memetakan `Rgb` (0–255 per channel) ke urutan
+//! dan rentang yang sebenarnya dipakai perangkat.
+use super::{map_io_error, read_value, write_value};
+use m4arch_core::error::{M4ArchError, Result};
+use m4arch_core::keyboard::Rgb;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Channel {
+    Red,
+    Green,
+    Blue,
+}
+
+/// Batas `max_brightness` yang masih masuk akal untuk LED multicolor; nilai di
+/// atasnya dianggap atribut yang rusak.
+const MAX_LIMIT: u32 = u16::MAX as u32;
+
+/// Tata letak `multi_intensity` sebuah LED: urutan channel dan nilai maksimum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct MultiColor {
+    order: [Channel; 3],
+    max: u32,
+}
+
+impl MultiColor {
+    /// Dipakai jika LED tidak memiliki `multi_index`/`max_brightness`.
+    const DEFAULT: Self = Self {
+        order: [Channel::Red, Channel::Green, Channel::Blue],
+        max: 255,
+    };
+
+    fn load(led_dir: &Path) -> Result<Self> {
+        let order = match fs::read_to_string(led_dir.join("multi_index")) {
+            Ok(content) => parse_index(&content)?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Self::DEFAULT.order,
+            Err(e) => return Err(map_io_error(e)),
+        };
+
+        let max_path = led_dir.join("max_brightness");
+        let max = if max_path.exists() {
+            read_value(&max_path)?
+        } else {
+            Self::DEFAULT.max
+        };
+        if max == 0 || max > MAX_LIMIT {
+            return Err(M4ArchError::InvalidValue);
+        }
+
+        Ok(Self { order, max })
+    }
+
+    fn encode(&self, rgb: Rgb) -> String {
+        self.order
+            .iter()
+            .map(|channel| {
+                let value = u64::from(pick(rgb, *channel));
+                ((value * u64::from(self.max) + 127) / 255).to_string()
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn decode(&self, content: &str) -> Result<Rgb> {
+        let values: Vec<u64> = content
+            .split_whitespace()
+            .map(|part| part.parse().map_err(|_| M4ArchError::InvalidValue))
+            .collect::<Result<_>>()?;
+        if values.len() != self.order.len() {
+            return Err(M4ArchError::InvalidValue);
+        }
+
+        let max = u64::from(self.max);
+        let mut rgb = Rgb { r: 0, g: 0, b: 0 };
+        for (channel, value) in self.order.iter().zip(values) {
+            let scaled = ((value.min(max) * 255 + max / 2) / max) as u8;
+            match channel {
+                Channel::Red => rgb.r = scaled,
+                Channel::Green => rgb.g = scaled,
+                Channel::Blue => rgb.b = scaled,
+            }
+        }
+        Ok(rgb)
+    }
+}
+
+fn pick(rgb: Rgb, channel: Channel) -> u8 {
+    match channel {
+        Channel::Red => rgb.r,
+        Channel::Green => rgb.g,
+        Channel::Blue => rgb.b,
+    }
+}
+
+/// Mem-parse `multi_index`; harus berisi tepat red, green dan blue.
+fn parse_index(content: &str) -> Result<[Channel; 3]> {
+    let unexpected = || M4ArchError::UnexpectedChannels(content.trim().to_string());
+
+    let channels: Vec<Channel> = content
+        .split_whitespace()
+        .map(|name| match name {
+            "red" => Ok(Channel::Red),
+            "green" => Ok(Channel::Green),
+            "blue" => Ok(Channel::Blue),
+            _ => Err(unexpected()),
+        })
+        .collect::<Result<_>>()?;
+
+    let order: [Channel; 3] = channels.try_into().map_err(|_| unexpected())?;
+    let complete = [Channel::Red, Channel::Green, Channel::Blue]
+        .iter()
+        .all(|channel| order.contains(channel));
+    if complete {
+        Ok(order)
+    } else {
+        Err(unexpected())
+    }
+}
+
+/// Tata letak multicolor sebuah direktori LED yang disimpan oleh driver.
+///
+/// Dimuat saat driver dibuat; jika LED belum siap saat itu, dimuat ulang pada
+/// akses berikutnya sampai berhasil, lalu tidak pernah dibaca lagi.
+pub(crate) struct LayoutCache {
+    led_dir: PathBuf,
+    layout: OnceLock<MultiColor>,
+}
+
+impl LayoutCache {
+    pub(crate) fn new(led_dir: &Path) -> Self {
+        let cache = Self {
+            led_dir: led_dir.to_path_buf(),
+            layout: OnceLock::new(),
+        };
+        // Kegagalan di sini diulang (dan dilaporkan) saat warna diakses.
+        let _ = cache.layout();
+        cache
+    }
+
+    fn layout(&self) -> Result<MultiColor> {
+        if let Some(layout) = self.layout.get() {
+            return Ok(*layout);
+        }
+        let layout = MultiColor::load(&self.led_dir)?;
+        Ok(*self.layout.get_or_init(|| layout))
+    }
+
+    /// Membaca warna dari `multi_intensity`.
+    pub(crate) fn read_rgb(&self) -> Result<Rgb> {
+        let layout = self.layout()?;
+        let content =
+            fs::read_to_string(self.led_dir.join("multi_intensity")).map_err(map_io_error)?;
+        layout.decode(&content)
+    }
+
+    /// Menulis warna ke `multi_intensity`.
+    pub(crate) fn write_rgb(&self, rgb: Rgb) -> Result<()> {
+        let layout = self.layout()?;
+        write_value(&self.led_dir.join("multi_intensity"), layout.encode(rgb))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sysfs::fake::{FakeLed, FakeSysfs};
+
+    const ORANGE: Rgb = Rgb {
+        r: 255,
+        g: 128,
+        b: 0,
+    };
+
+    fn led(fake: &FakeSysfs, max: u8, index: Option<&str>) -> std::path::PathBuf {
+        let dir = fake
+            .add_led("led", &FakeLed::rgb(0, max, (0, 0, 0)))
+            .unwrap();
+        if let Some(index) = index {
+            fake.write("led/multi_index", index).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn test_default_layout_without_multi_index() {
+        let fake = FakeSysfs::new().unwrap();
+        let dir = led(&fake, 255, None);
+        LayoutCache::new(&dir).write_rgb(ORANGE).unwrap();
+        assert_eq!(fake.read("led/multi_intensity").unwrap(), "255 128 0");
+        assert_eq!(LayoutCache::new(&dir).read_rgb().unwrap(), ORANGE);
+    }
+
+    #[test]
+    fn test_honours_channel_order() {
+        let fake = FakeSysfs::new().unwrap();
+        let dir = led(&fake, 255, Some("green blue red\n"));
+        LayoutCache::new(&dir).write_rgb(ORANGE).unwrap();
+        assert_eq!(fake.read("led/multi_intensity").unwrap(), "128 0 255");
+        assert_eq!(LayoutCache::new(&dir).read_rgb().unwrap(), ORANGE);
+    }
+
+    #[test]
+    fn test_scales_to_max_brightness() {
+        let fake = FakeSysfs::new().unwrap();
+        let dir = led(&fake, 50, Some("red green blue"));
+        LayoutCache::new(&dir).write_rgb(ORANGE).unwrap();
+        assert_eq!(fake.read("led/multi_intensity").unwrap(), "50 25 0");
+
+        fake.write("led/multi_intensity", "25 50 10").unwrap();
+        assert_eq!(
+            LayoutCache::new(&dir).read_rgb().unwrap(),
+            Rgb {
+                r: 128,
+                g: 255,
+                b: 51
+            }
+        );
+    }
+
+    #[test]
+    fn test_rejects_unexpected_channels() {
+        for index in ["red green", "red green blue white", "red red blue", "rgb"] {
+            let fake = FakeSysfs::new().unwrap();
+            let dir = led(&fake, 255, Some(index));
+            let err = LayoutCache::new(&dir).write_rgb(ORANGE).unwrap_err();
+            assert!(
+                matches!(&err, M4ArchError::UnexpectedChannels(found) if found == index),
+                "{index}: {err}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_rejects_absurd_max_brightness() {
+        let fake = FakeSysfs::new().unwrap();
+        let dir = led(&fake, 255, None);
+        fake.write("led/max_brightness", "4294967295\n").unwrap();
+        assert!(matches!(
+            LayoutCache::new(&dir).write_rgb(ORANGE),
+            Err(M4ArchError::InvalidValue)
+        ));
+    }
+
+    #[test]
+    fn test_large_max_brightness_does_not_overflow() {
+        let fake = FakeSysfs::new().unwrap();
+        let dir = led(&fake, 255, None);
+        fake.write("led/max_brightness", "65535\n").unwrap();
+        let cache = LayoutCache::new(&dir);
+        cache.write_rgb(ORANGE).unwrap();
+        assert_eq!(fake.read("led/multi_intensity").unwrap(), "65535 32896 0");
+        fake.write("led/multi_intensity", "4294967295 32896 0")
+            .unwrap();
+        assert_eq!(cache.read_rgb().unwrap(), ORANGE);
+    }
+
+    #[test]
+    fn test_layout_is_cached() {
+        let fake = FakeSysfs::new().unwrap();
+        let dir = led(&fake, 255, Some("green blue red"));
+        let cache = LayoutCache::new(&dir);
+        fake.write("led/multi_index", "red green blue").unwrap();
+        cache.write_rgb(ORANGE).unwrap();
+        assert_eq!(fake.read("led/multi_intensity").unwrap(), "128 0 255");
+    }
+
+    #[test]
+    fn test_rejects_wrong_value_count() {
+        let fake = FakeSysfs::new().unwrap();
+        let dir = led(&fake, 255, Some("red green blue"));
+        fake.write("led/multi_intensity", "1 2").unwrap();
+        assert!(matches!(
+            LayoutCache::new(&dir).read_rgb(),
+            Err(M4ArchError::InvalidValue)
+        ));
+    }
+}
@@ -1,58 +1,187 @@
-use m4arch_core::error::{M4ArchError, Result};
-use m4arch_core::keyboard::{KeyboardBrightness, KeyboardRgb, Rgb};
+use crate::sysfs::multicolor::LayoutCache;
+use crate::sysfs::{led_capabilities, read_hw_changed, read_value, write_value, DEFAULT_ROOT};
+use crate::zones::zone_index;
+use crate::KeyboardController;
+use m4arch_core::error::Result;
+use m4arch_core::keyboard::{KeyboardBrightness, KeyboardCapabilities, KeyboardRgb, Rgb};
 use std::fs;
+use std::path::{Path, PathBuf};
 
-pub struct TuxedoKeyboard;
+/// Driver untuk LED yang dipublikasikan oleh modul `tuxedo_keyboard`.
+pub struct TuxedoKeyboard {
+    led_dir: PathBuf,
+    rgb: LayoutCache,
+}
 
 impl TuxedoKeyboard {
-    // Path sysfs spesifik untuk driver Tuxedo
-    const BRIGHTNESS_PATH: &'static str =
-        "/sys/devices/platform/tuxedo_keyboard/leds/rgb:kbd_backlight/brightness";
-    const MAX_BRIGHTNESS_PATH: &'static str =
-        "/sys/devices/platform/tuxedo_keyboard/leds/rgb:kbd_backlight/max_brightness";
-    const RGB_PATH: &'static str =
-        "/sys/devices/platform/tuxedo_keyboard/leds/rgb:kbd_backlight/multi_intensity";
+    // Path sysfs spesifik untuk driver Tuxedo, relatif terhadap root sysfs
+    pub const LEDS_DIR: &'static str = "devices/platform/tuxedo_keyboard/leds";
+    pub const LED_DIR: &'static str = "devices/platform/tuxedo_keyboard/leds/rgb:kbd_backlight";
+
+    pub fn new() -> Self {
+        Self::with_root(DEFAULT_ROOT)
+    }
+
+    /// Membuat driver dengan root sysfs kustom (misalnya `FakeSysfs`).
+    pub fn with_root(root: impl AsRef<Path>) -> Self {
+        Self::from_led_dir(root.as_ref().join(Self::LED_DIR))
+    }
+
+    /// Membuat driver untuk satu LED zona tertentu.
+    pub fn from_led_dir(led_dir: impl Into<PathBuf>) -> Self {
+        let led_dir = led_dir.into();
+        Self {
+            rgb: LayoutCache::new(&led_dir),
+            led_dir,
+        }
+    }
+
+    /// Apakah LED Tuxedo tersedia di bawah root yang diberikan.
+    pub fn detect(root: &Path) -> bool {
+        root.join(Self::LED_DIR).join("brightness").exists()
+    }
+
+    /// Mengembalikan satu driver per zona (`rgb:kbd_backlight`,
+    /// `rgb:kbd_backlight_1`, ...), terurut berdasarkan indeks zona.
+    pub fn discover_zones(root: &Path) -> Vec<Self> {
+        let Ok(entries) = fs::read_dir(root.join(Self::LEDS_DIR)) else {
+            return Vec::new();
+        };
+
+        let mut zones: Vec<(usize, PathBuf)> = entries
+            .flatten()
+            .filter_map(|entry| {
+                let index = zone_index(&entry.file_name().to_string_lossy())?;
+                let path = entry.path();
+                path.join("brightness").exists().then_some((index, path))
+            })
+            .collect();
+        zones.sort_by_key(|(index, _)| *index);
+        zones
+            .into_iter()
+            .map(|(_, path)| Self::from_led_dir(path))
+            .collect()
+    }
+}
+
+impl Default for TuxedoKeyboard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl KeyboardController for TuxedoKeyboard {
+    fn capabilities(&self) -> KeyboardCapabilities {
+        led_capabilities(&self.led_dir)
+    }
+
+    fn brightness_hw_changed(&self) -> Result<Option<u8>> {
+        read_hw_changed(&self.led_dir)
+    }
 }
 
 impl KeyboardBrightness for TuxedoKeyboard {
     fn get_brightness(&self) -> Result<u8> {
-        let content = fs::read_to_string(Self::BRIGHTNESS_PATH)?;
-        content
-            .trim()
-            .parse()
-            .map_err(|_| M4ArchError::InvalidValue)
+        read_value(&self.led_dir.join("brightness"))
     }
 
     fn get_max_brightness(&self) -> Result<u8> {
-        let content = fs::read_to_string(Self::MAX_BRIGHTNESS_PATH)?;
-        content
-            .trim()
-            .parse()
-            .map_err(|_| M4ArchError::InvalidValue)
+        read_value(&self.led_dir.join("max_brightness"))
     }
 
     fn set_brightness(&self, level: u8) -> Result<()> {
-        fs::write(Self::BRIGHTNESS_PATH, level.to_string())?;
-        Ok(())
+        write_value(&self.led_dir.join("brightness"), level)
     }
 }
 
 impl KeyboardRgb for TuxedoKeyboard {
     fn get_rgb(&self) -> Result<Rgb> {
-        let content = fs::read_to_string(Self::RGB_PATH)?;
-        let parts: Vec<&str> = content.trim().split_whitespace().collect();
-        if parts.len() < 3 {
-            return Err(M4ArchError::InvalidValue);
-        }
-        let r = parts[0].parse().map_err(|_| M4ArchError::InvalidValue)?;
-        let g = parts[1].parse().map_err(|_| M4ArchError::InvalidValue)?;
-        let b = parts[2].parse().map_err(|_| M4ArchError::InvalidValue)?;
-        Ok(Rgb { r, g, b })
+        self.rgb.read_rgb()
     }
 
     fn set_rgb(&self, rgb: Rgb) -> Result<()> {
-        let value = format!("{} {} {}", rgb.r, rgb.g, rgb.b);
-        fs::write(Self::RGB_PATH, value)?;
-        Ok(())
+        self.rgb.write_rgb(rgb)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sysfs::fake::{FakeLed, FakeSysfs};
+    use m4arch_core::error::M4ArchError;
+
+    fn setup(led: FakeLed) -> (FakeSysfs, TuxedoKeyboard) {
+        let fake = FakeSysfs::new().unwrap();
+        fake.add_led(TuxedoKeyboard::LED_DIR, &led).unwrap();
+        let kbd = TuxedoKeyboard::with_root(fake.root());
+        (fake, kbd)
+    }
+
+    #[test]
+    fn test_read_and_write_brightness() {
+        let (fake, kbd) = setup(FakeLed::rgb(64, 255, (255, 255, 255)));
+        let info = kbd.brightness_info().unwrap();
+        assert_eq!(info.current, 64);
+        assert_eq!(info.max, 255);
+        assert_eq!(info.percent, 25);
+
+        kbd.increase_brightness(250).unwrap();
+        let path = format!("{}/brightness", TuxedoKeyboard::LED_DIR);
+        assert_eq!(fake.read(path).unwrap(), "255");
+    }
+
+    #[test]
+    fn test_read_and_write_rgb() {
+        let (fake, kbd) = setup(FakeLed::rgb(64, 255, (10, 20, 30)));
+        assert_eq!(
+            kbd.get_rgb().unwrap(),
+            Rgb {
+                r: 10,
+                g: 20,
+                b: 30
+            }
+        );
+
+        kbd.set_rgb(Rgb { r: 0, g: 255, b: 0 }).unwrap();
+        let path = format!("{}/multi_intensity", TuxedoKeyboard::LED_DIR);
+        assert_eq!(fake.read(path).unwrap(), "0 255 0");
+    }
+
+    #[test]
+    fn test_missing_device_is_io_error() {
+        let fake = FakeSysfs::new().unwrap();
+        let kbd = TuxedoKeyboard::with_root(fake.root());
+        assert!(!TuxedoKeyboard::detect(fake.root()));
+        assert!(matches!(kbd.get_brightness(), Err(M4ArchError::Io(_))));
+        assert!(matches!(kbd.set_brightness(1), Err(M4ArchError::Io(_))));
+    }
+
+    #[test]
+    fn test_discover_zones() {
+        let fake = FakeSysfs::new().unwrap();
+        for (name, value) in [
+            ("rgb:kbd_backlight_2", 2),
+            ("rgb:kbd_backlight", 0),
+            ("rgb:kbd_backlight_1", 1),
+        ] {
+            let dir = format!("{}/{}", TuxedoKeyboard::LEDS_DIR, name);
+            fake.add_led(dir, &FakeLed::rgb(value, 255, (0, 0, 0)))
+                .unwrap();
+        }
+
+        let zones = TuxedoKeyboard::discover_zones(fake.root());
+        let levels: Vec<u8> = zones.iter().map(|z| z.get_brightness().unwrap()).collect();
+        assert_eq!(levels, [0, 1, 2]);
+    }
+
+    #[test]
+    fn test_out_of_range_value_is_invalid() {
+        let (fake, kbd) = setup(FakeLed::rgb(0, 255, (0, 0, 0)));
+        let path = format!("{}/max_brightness", TuxedoKeyboard::LED_DIR);
+        fake.write(path, "1024\n").unwrap();
+        assert!(matches!(
+            kbd.get_max_brightness(),
+            Err(M4ArchError::InvalidValue)
+        ));
     }
 }
@@ -0,0 +1,468 @@
+//! Driver kipas dan profil performa lewat perangkat karakter `/dev/tuxedo_io`.
+//!
+//! Modul kernel `tuxedo_io` tidak memakai sysfs; semua akses ke EC dilakukan
+//! dengan ioctl yang membawa satu `int32_t`. Ada dua keluarga EC dengan
+//! perintah berbeda, dideteksi lewat `R_HWCHECK_CL`/`R_HWCHECK_UW`:
+//!
+//! - Clevo: satu register per kipas berisi duty (0–255) di byte 0 dan suhu
+//!   di byte 1; duty semua kipas ditulis sekaligus, satu byte per kipas.
+//! - Uniwill: duty (0–200) dan suhu dibaca dari register terpisah, dan
+//!   setiap kipas ditulis sendiri-sendiri.
+//!
+//! Profil performa hanya bisa ditulis; EC tidak melaporkan profil aktif.
+//!
+//! Akses ioctl dibungkus `IoctlDevice` agar driver bisa diuji dengan
+//! `fake::FakeIoctl`.
+use crate::sysfs::map_io_error;
+use m4arch_core::error::{M4ArchError, Result};
+use m4arch_core::fan::{Fan, FanDuty, FanSpeed};
+use m4arch_core::power::{PowerProfile, PowerProfileControl};
+use std::cell::Cell;
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::os::fd::AsRawFd;
+use std::path::Path;
+
+/// Lokasi perangkat di sistem sungguhan.
+pub const DEVICE: &str = "/dev/tuxedo_io";
+
+/// Nomor request ioctl dari `tuxedo_io_ioctl.h`.
+///
+/// Header mendeklarasikan argumennya sebagai `int32_t*`, jadi ukuran yang
+/// dikodekan adalah ukuran pointer, bukan ukuran `int32_t`.
+pub mod request {
+    const MAGIC: u64 = 0xec;
+    const MAGIC_READ_CL: u64 = MAGIC + 1;
+    const MAGIC_WRITE_CL: u64 = MAGIC + 2;
+    const MAGIC_READ_UW: u64 = MAGIC + 3;
+    const MAGIC_WRITE_UW: u64 = MAGIC + 4;
+
+    const WRITE: u64 = 1;
+    const READ: u64 = 2;
+    const SIZE: u64 = std::mem::size_of::<*mut i32>() as u64;
+
+    /// `_IOC(dir, type, nr, size)` dari `<asm-generic/ioctl.h>`.
+    const fn ioc(dir: u64, magic: u64, nr: u64) -> u64 {
+        (dir << 30) | (SIZE << 16) | (magic << 8) | nr
+    }
+
+    pub const R_HWCHECK_CL: u64 = ioc(READ, MAGIC, 0x05);
+    pub const R_HWCHECK_UW: u64 = ioc(READ, MAGIC, 0x06);
+
+    pub const R_CL_FANINFO1: u64 = ioc(READ, MAGIC_READ_CL, 0x10);
+    pub const R_CL_FANINFO2: u64 = ioc(READ, MAGIC_READ_CL, 0x11);
+    pub const R_CL_FANINFO3: u64 = ioc(READ, MAGIC_READ_CL, 0x12);
+    pub const W_CL_FANSPEED: u64 = ioc(WRITE, MAGIC_WRITE_CL, 0x10);
+    pub const W_CL_FANAUTO: u64 = ioc(WRITE, MAGIC_WRITE_CL, 0x11);
+    pub const W_CL_PERF_PROFILE: u64 = ioc(WRITE, MAGIC_WRITE_CL, 0x15);
+
+    pub const R_UW_FANSPEED: u64 = ioc(READ, MAGIC_READ_UW, 0x10);
+    pub const R_UW_FANSPEED2: u64 = ioc(READ, MAGIC_READ_UW, 0x11);
+    pub const R_UW_FAN_TEMP: u64 = ioc(READ, MAGIC_READ_UW, 0x12);
+    pub const R_UW_FAN_TEMP2: u64 = ioc(READ, MAGIC_READ_UW, 0x13);
+    pub const W_UW_FANSPEED: u64 = ioc(WRITE, MAGIC_WRITE_UW, 0x10);
+    pub const W_UW_FANSPEED2: u64 = ioc(WRITE, MAGIC_WRITE_UW, 0x11);
+    pub const W_UW_FANAUTO: u64 = ioc(WRITE, MAGIC_WRITE_UW, 0x14);
+    pub const W_UW_PERF_PROF: u64 = ioc(WRITE, MAGIC_WRITE_UW, 0x18);
+}
+
+/// Duty mentah maksimum pada EC Clevo.
+const CLEVO_MAX_DUTY: u32 = 0xff;
+
+/// Duty mentah maksimum pada EC Uniwill.
+const UNIWILL_MAX_DUTY: u32 = 0xc8;
+
+/// Register info Clevo untuk kipas CPU, GPU dan GPU kedua, sesuai urutan
+/// byte pada `W_CL_FANSPEED`.
+const CLEVO_FANINFO: [u64; 3] = [
+    request::R_CL_FANINFO1,
+    request::R_CL_FANINFO2,
+    request::R_CL_FANINFO3,
+];
+
+/// Perangkat yang menerima ioctl `tuxedo_io`.
+pub trait IoctlDevice: Send {
+    fn read(&self, request: u64) -> io::Result<i32>;
+    fn write(&self, request: u64, value: i32) -> io::Result<()>;
+}
+
+/// `/dev/tuxedo_io` yang sesungguhnya.
+pub struct DeviceFile {
+    file: File,
+}
+
+impl DeviceFile {
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let file = OpenOptions::new().read(true).write(true).open(path)?;
+        Ok(Self { file })
+    }
+
+    fn ioctl(&self, request: u64, value: &mut i32) -> io::Result<()> {
+        // SAFETY: setiap request tuxedo_io membaca atau menulis tepat satu
+        // int32_t lewat pointer yang diberikan, dan `value` hidup selama panggilan
+        let result = unsafe {
+            libc::ioctl(
+                self.file.as_raw_fd(),
+                request as libc::Ioctl,
+                value as *mut i32,
+            )
+        };
+        if result < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+}
+
+impl IoctlDevice for DeviceFile {
+    fn read(&self, request: u64) -> io::Result<i32> {
+        let mut value = 0;
+        self.ioctl(request, &mut value)?;
+        Ok(value)
+    }
+
+    fn write(&self, request: u64, value: i32) -> io::Result<()> {
+        let mut value = value;
+        self.ioctl(request, &mut value)
+    }
+}
+
+/// Keluarga EC yang dilayani `tuxedo_io`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Interface {
+    Clevo,
+    Uniwill,
+}
+
+pub struct TuxedoIo<D = DeviceFile> {
+    device: D,
+    interface: Interface,
+    /// Profil performa terakhir yang ditulis lewat driver ini
+    profile: Cell<Option<PowerProfile>>,
+}
+
+impl TuxedoIo {
+    /// Membuka `path` dan mendeteksi keluarga EC-nya.
+    pub fn open(path: &Path) -> io::Result<Self> {
+        Self::with_device(DeviceFile::open(path)?).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::Unsupported,
+                "tuxedo_io reports neither a Clevo nor a Uniwill interface",
+            )
+        })
+    }
+}
+
+impl<D: IoctlDevice> TuxedoIo<D> {
+    /// Membuat driver di atas `device`; `None` jika tidak ada antarmuka
+    /// kipas yang dikenali.
+    pub fn with_device(device: D) -> Option<Self> {
+        let present = |request| device.read(request).is_ok_and(|value| value == 1);
+        let interface = if present(request::R_HWCHECK_CL) {
+            Interface::Clevo
+        } else if present(request::R_HWCHECK_UW) {
+            Interface::Uniwill
+        } else {
+            return None;
+        };
+        Some(Self {
+            device,
+            interface,
+            profile: Cell::new(None),
+        })
+    }
+
+    fn read(&self, request: u64) -> Result<i32> {
+        self.device.read(request).map_err(map_io_error)
+    }
+
+    fn write(&self, request: u64, value: i32) -> Result<()> {
+        self.device.write(request, value).map_err(map_io_error)
+    }
+
+    /// Duty mentah (byte 0) semua kipas Clevo.
+    fn clevo_duties(&self) -> Result<[u8; 3]> {
+        let mut duties = [0; 3];
+        for (duty, request) in duties.iter_mut().zip(CLEVO_FANINFO) {
+            *duty = self.read(request)?.to_le_bytes()[0];
+        }
+        Ok(duties)
+    }
+}
+
+impl<D: IoctlDevice> FanSpeed for TuxedoIo<D> {
+    fn fans(&self) -> Vec<Fan> {
+        vec![Fan::Cpu, Fan::Gpu]
+    }
+
+    fn get_fan_speed(&self, fan: Fan) -> Result<u8> {
+        match self.interface {
+            Interface::Clevo => {
+                let info = self.read(CLEVO_FANINFO[fan_index(fan)])?;
+                Ok(to_percent(u32::from(info.to_le_bytes()[0]), CLEVO_MAX_DUTY))
+            }
+            Interface::Uniwill => {
+                let request = match fan {
+                    Fan::Cpu => request::R_UW_FANSPEED,
+                    Fan::Gpu => request::R_UW_FANSPEED2,
+                };
+                let raw = u32::try_from(self.read(request)?).unwrap_or(0);
+                Ok(to_percent(raw, UNIWILL_MAX_DUTY))
+            }
+        }
+    }
+
+    fn get_temperature(&self, fan: Fan) -> Result<u8> {
+        match self.interface {
+            Interface::Clevo => {
+                let info = self.read(CLEVO_FANINFO[fan_index(fan)])?;
+                Ok(info.to_le_bytes()[1])
+            }
+            Interface::Uniwill => {
+                let request = match fan {
+                    Fan::Cpu => request::R_UW_FAN_TEMP,
+                    Fan::Gpu => request::R_UW_FAN_TEMP2,
+                };
+                Ok(self.read(request)?.clamp(0, 255) as u8)
+            }
+        }
+    }
+}
+
+impl<D: IoctlDevice> FanDuty for TuxedoIo<D> {
+    fn set_fan_duty(&self, fan: Fan, percent: u8) -> Result<()> {
+        if percent > 100 {
+            return Err(M4ArchError::InvalidValue);
+        }
+        match self.interface {
+            Interface::Clevo => {
+                // Kipas lain ditulis ulang dengan duty-nya saat ini
+                let mut duties = self.clevo_duties()?;
+                duties[fan_index(fan)] = from_percent(percent, CLEVO_MAX_DUTY) as u8;
+                let [cpu, gpu, gpu2] = duties;
+                let value = i32::from_le_bytes([cpu, gpu, gpu2, 0]);
+                self.write(request::W_CL_FANSPEED, value)
+            }
+            Interface::Uniwill => {
+                let request = match fan {
+                    Fan::Cpu => request::W_UW_FANSPEED,
+                    Fan::Gpu => request::W_UW_FANSPEED2,
+                };
+                self.write(request, from_percent(percent, UNIWILL_MAX_DUTY) as i32)
+            }
+        }
+    }
+
+    fn set_fans_auto(&self) -> Result<()> {
+        match self.interface {
+            // Satu bit per kipas
+            Interface::Clevo => self.write(request::W_CL_FANAUTO, 0xf),
+            Interface::Uniwill => self.write(request::W_UW_FANAUTO, 0),
+        }
+    }
+}
+
+impl<D: IoctlDevice> PowerProfileControl for TuxedoIo<D> {
+    fn profiles(&self) -> Vec<PowerProfile> {
+        PowerProfile::ALL.to_vec()
+    }
+
+    fn get_profile(&self) -> Result<PowerProfile> {
+        self.profile.get().ok_or_else(|| {
+            M4ArchError::Unsupported("reading the power profile from tuxedo_io".to_string())
+        })
+    }
+
+    fn set_profile(&self, profile: PowerProfile) -> Result<()> {
+        // Nilai profil mengikuti TUXEDO Control Center. Pada Clevo, 0x00
+        // "quiet", 0x01 "power saving", 0x02 "performance" dan 0x03
+        // "entertainment" (seimbang). "Power saving" tidak dipakai karena
+        // `Quiet` sudah mencakup batas daya rendah.
+        match self.interface {
+            Interface::Clevo => {
+                let value = match profile {
+                    PowerProfile::Quiet => 0x00,
+                    PowerProfile::Balanced => 0x03,
+                    PowerProfile::Performance => 0x02,
+                };
+                self.write(request::W_CL_PERF_PROFILE, value)?;
+            }
+            Interface::Uniwill => {
+                let value = match profile {
+                    PowerProfile::Quiet => 0x01,
+                    PowerProfile::Balanced => 0x02,
+                    PowerProfile::Performance => 0x03,
+                };
+                self.write(request::W_UW_PERF_PROF, value)?;
+            }
+        }
+        self.profile.set(Some(profile));
+        Ok(())
+    }
+}
+
+fn fan_index(fan: Fan) -> usize {
+    match fan {
+        Fan::Cpu => 0,
+        Fan::Gpu => 1,
+    }
+}
+
+fn to_percent(raw: u32, max: u32) -> u8 {
+    ((raw.min(max) * 100 + max / 2) / max) as u8
+}
+
+fn from_percent(percent: u8, max: u32) -> u32 {
+    (u32::from(percent.min(100)) * max + 50) / 100
+}
+
+/// Perangkat ioctl palsu untuk pengujian.
+///
+/// Hanya tersedia saat `cfg(test)` atau dengan fitur `fake-sysfs`.
+#[cfg(any(test, feature = "fake-sysfs"))]
+pub mod fake {
+    use super::{request, IoctlDevice};
+    use std::collections::HashMap;
+    use std::io;
+    use std::sync::{Arc, Mutex, PoisonError};
+
+    /// Setiap request adalah register yang bisa dibaca dan ditulis; request
+    /// yang belum pernah diisi gagal seperti ioctl yang tidak dikenal.
+    ///
+    /// Salinan berbagi register yang sama, jadi pengujian bisa menyimpan satu
+    /// salinan setelah yang lain diserahkan ke driver.
+    #[derive(Debug, Clone, Default)]
+    pub struct FakeIoctl {
+        registers: Arc<Mutex<HashMap<u64, i32>>>,
+    }
+
+    impl FakeIoctl {
+        /// EC Clevo dengan semua register kipas bernilai 0.
+        pub fn clevo() -> Self {
+            let fake = Self::default();
+            fake.set(request::R_HWCHECK_CL, 1);
+            for register in super::CLEVO_FANINFO {
+                fake.set(register, 0);
+            }
+            fake
+        }
+
+        /// EC Uniwill dengan semua register kipas bernilai 0.
+        pub fn uniwill() -> Self {
+            let fake = Self::default();
+            fake.set(request::R_HWCHECK_CL, 0);
+            fake.set(request::R_HWCHECK_UW, 1);
+            for register in [
+                request::R_UW_FANSPEED,
+                request::R_UW_FANSPEED2,
+                request::R_UW_FAN_TEMP,
+                request::R_UW_FAN_TEMP2,
+            ] {
+                fake.set(register, 0);
+            }
+            fake
+        }
+
+        pub fn set(&self, request: u64, value: i32) {
+            self.registers().insert(request, value);
+        }
+
+        /// Nilai terakhir yang dibaca atau ditulis lewat `request`.
+        pub fn get(&self, request: u64) -> Option<i32> {
+            self.registers().get(&request).copied()
+        }
+
+        /// Melupakan nilai yang ditulis lewat `request`.
+        pub fn clear(&self, request: u64) {
+            self.registers().remove(&request);
+        }
+
+        fn registers(&self) -> std::sync::MutexGuard<'_, HashMap<u64, i32>> {
+            self.registers
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+        }
+    }
+
+    impl IoctlDevice for FakeIoctl {
+        fn read(&self, request: u64) -> io::Result<i32> {
+            self.get(request)
+                .ok_or_else(|| io::Error::from_raw_os_error(libc::ENOTTY))
+        }
+
+        fn write(&self, request: u64, value: i32) -> io::Result<()> {
+            self.set(request, value);
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fake::FakeIoctl;
+    use super::*;
+
+    #[test]
+    fn test_request_numbers_match_header() {
+        // Nilai dari `_IOR(0xed, 0x10, int32_t*)` dan `_IOW(0xf0, 0x14, int32_t*)`
+        // pada x86_64
+        assert_eq!(request::R_CL_FANINFO1, 0x8008_ed10);
+        assert_eq!(request::W_UW_FANAUTO, 0x4008_f014);
+    }
+
+    #[test]
+    fn test_detects_nothing_without_interface() {
+        assert!(TuxedoIo::with_device(FakeIoctl::default()).is_none());
+    }
+
+    #[test]
+    fn test_clevo_fans() {
+        let fake = FakeIoctl::clevo();
+        let fans = TuxedoIo::with_device(fake.clone()).unwrap();
+        // CPU: duty 0x80, 61 °C; GPU: duty 0x33, 48 °C
+        fake.set(request::R_CL_FANINFO1, 0x3d80);
+        fake.set(request::R_CL_FANINFO2, 0x3033);
+
+        let info = fans.fan_info(Fan::Cpu).unwrap();
+        assert_eq!((info.speed, info.temperature), (50, 61));
+        assert_eq!(fans.get_temperature(Fan::Gpu).unwrap(), 48);
+
+        // Kipas lain tetap pada duty-nya
+        fans.set_fan_duty(Fan::Gpu, 100).unwrap();
+        assert_eq!(fake.get(request::W_CL_FANSPEED), Some(0xff80));
+        fans.set_fans_auto().unwrap();
+        assert_eq!(fake.get(request::W_CL_FANAUTO), Some(0xf));
+    }
+
+    #[test]
+    fn test_uniwill_fans() {
+        let fake = FakeIoctl::uniwill();
+        let fans = TuxedoIo::with_device(fake.clone()).unwrap();
+        fake.set(request::R_UW_FANSPEED2, 0x64);
+        fake.set(request::R_UW_FAN_TEMP, 72);
+
+        assert_eq!(fans.get_fan_speed(Fan::Gpu).unwrap(), 50);
+        assert_eq!(fans.get_temperature(Fan::Cpu).unwrap(), 72);
+
+        fans.set_fan_duty(Fan::Cpu, 40).unwrap();
+        assert_eq!(fake.get(request::W_UW_FANSPEED), Some(80));
+        assert!(fans.set_fan_duty(Fan::Cpu, 101).is_err());
+        fans.set_fans_auto().unwrap();
+        assert_eq!(fake.get(request::W_UW_FANAUTO), Some(0));
+    }
+
+    #[test]
+    fn test_power_profiles() {
+        let fake = FakeIoctl::clevo();
+        let io = TuxedoIo::with_device(fake.clone()).unwrap();
+        assert!(io.get_profile().is_err());
+        io.set_profile(PowerProfile::Balanced).unwrap();
+        assert_eq!(fake.get(request::W_CL_PERF_PROFILE), Some(0x03));
+        assert_eq!(io.get_profile().unwrap(), PowerProfile::Balanced);
+
+        let fake = FakeIoctl::uniwill();
+        let io = TuxedoIo::with_device(fake.clone()).unwrap();
+        io.set_profile(PowerProfile::Quiet).unwrap();
+        assert_eq!(fake.get(request::W_UW_PERF_PROF), Some(0x01));
+    }
+}
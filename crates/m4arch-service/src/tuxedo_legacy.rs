@@ -0,0 +1,233 @@
+//! Driver untuk antarmuka lama `tuxedo_keyboard`.
+//!
+//! Versi lama modul ini tidak memakai LED class, melainkan atribut langsung
+//! di bawah perangkat platform: `brightness`, `mode`, dan `color_left`,
+//! `color_center`, `color_right` (serta `color_extra` pada sebagian model).
+//! Warna ditulis sebagai `0xRRGGBB` dan dibaca kembali sebagai `rrggbb`.
+use crate::sysfs::{map_io_error, read_value, write_value, DEFAULT_ROOT};
+use crate::KeyboardController;
+use m4arch_core::error::{M4ArchError, Result};
+use m4arch_core::keyboard::{
+    KeyboardBrightness, KeyboardCapabilities, KeyboardMode, KeyboardRgb, KeyboardZone, Rgb,
+};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Atribut warna per zona, terurut berdasarkan indeks zona.
+const COLOR_ATTRS: [&str; 4] = ["color_left", "color_center", "color_right", "color_extra"];
+
+pub struct TuxedoLegacyKeyboard {
+    device_dir: PathBuf,
+    /// Atribut warna yang tersedia di perangkat ini
+    zones: Vec<&'static str>,
+}
+
+impl TuxedoLegacyKeyboard {
+    pub const DEVICE_DIR: &'static str = "devices/platform/tuxedo_keyboard";
+
+    /// Firmware tidak memublikasikan `max_brightness`; batasnya selalu 255.
+    pub const MAX_BRIGHTNESS: u8 = 255;
+
+    pub fn new() -> Self {
+        Self::with_root(DEFAULT_ROOT)
+    }
+
+    /// Membuat driver dengan root sysfs kustom (misalnya `FakeSysfs`).
+    pub fn with_root(root: impl AsRef<Path>) -> Self {
+        let device_dir = root.as_ref().join(Self::DEVICE_DIR);
+        let zones = COLOR_ATTRS
+            .into_iter()
+            .filter(|attr| device_dir.join(attr).exists())
+            .collect();
+        Self { device_dir, zones }
+    }
+
+    /// Apakah atribut lama (`mode` dan minimal satu warna) tersedia.
+    pub fn detect(root: &Path) -> bool {
+        let dir = root.join(Self::DEVICE_DIR);
+        dir.join("mode").exists() && dir.join(COLOR_ATTRS[0]).exists()
+    }
+
+    fn color_path(&self, zone: usize) -> Result<PathBuf> {
+        self.zones
+            .get(zone)
+            .map(|attr| self.device_dir.join(attr))
+            .ok_or(M4ArchError::InvalidZone(zone))
+    }
+}
+
+impl Default for TuxedoLegacyKeyboard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl KeyboardController for TuxedoLegacyKeyboard {
+    fn capabilities(&self) -> KeyboardCapabilities {
+        KeyboardCapabilities {
+            brightness: true,
+            max_brightness: true,
+            rgb: !self.zones.is_empty(),
+            channel_index: false,
+        }
+    }
+
+    fn modes(&self) -> Vec<KeyboardMode> {
+        KeyboardMode::ALL.to_vec()
+    }
+
+    fn get_mode(&self) -> Result<KeyboardMode> {
+        KeyboardMode::from_index(read_value(&self.device_dir.join("mode"))?)
+    }
+
+    fn set_mode(&self, mode: KeyboardMode) -> Result<()> {
+        write_value(&self.device_dir.join("mode"), mode.index())
+    }
+}
+
+impl KeyboardBrightness for TuxedoLegacyKeyboard {
+    fn get_brightness(&self) -> Result<u8> {
+        read_value(&self.device_dir.join("brightness"))
+    }
+
+    fn get_max_brightness(&self) -> Result<u8> {
+        Ok(Self::MAX_BRIGHTNESS)
+    }
+
+    fn set_brightness(&self, level: u8) -> Result<()> {
+        write_value(&self.device_dir.join("brightness"), level)
+    }
+}
+
+impl KeyboardRgb for TuxedoLegacyKeyboard {
+    fn get_rgb(&self) -> Result<Rgb> {
+        self.get_zone_rgb(0)
+    }
+
+    fn set_rgb(&self, rgb: Rgb) -> Result<()> {
+        (0..self.zones.len()).try_for_each(|zone| self.set_zone_rgb(zone, rgb))
+    }
+
+    fn zones(&self) -> Vec<KeyboardZone> {
+        (0..self.zones.len())
+            .map(|index| KeyboardZone::standard(index, self.zones.len()))
+            .collect()
+    }
+
+    fn get_zone_rgb(&self, zone: usize) -> Result<Rgb> {
+        let content = fs::read_to_string(self.color_path(zone)?).map_err(map_io_error)?;
+        parse_color(&content)
+    }
+
+    fn set_zone_rgb(&self, zone: usize, rgb: Rgb) -> Result<()> {
+        let value = format!("0x{:02x}{:02x}{:02x}", rgb.r, rgb.g, rgb.b);
+        write_value(&self.color_path(zone)?, value)
+    }
+}
+
+/// Mem-parse warna `rrggbb`, dengan atau tanpa prefix `0x`.
+fn parse_color(content: &str) -> Result<Rgb> {
+    let hex = content.trim();
+    let hex = hex.strip_prefix("0x").unwrap_or(hex);
+    let value = u32::from_str_radix(hex, 16).map_err(|_| M4ArchError::InvalidValue)?;
+    if value > 0xff_ffff {
+        return Err(M4ArchError::InvalidValue);
+    }
+    let [_, r, g, b] = value.to_be_bytes();
+    Ok(Rgb { r, g, b })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sysfs::fake::FakeSysfs;
+
+    fn attr(name: &str) -> String {
+        format!("{}/{}", TuxedoLegacyKeyboard::DEVICE_DIR, name)
+    }
+
+    fn setup() -> (FakeSysfs, TuxedoLegacyKeyboard) {
+        let fake = FakeSysfs::new().unwrap();
+        for (name, value) in [
+            ("brightness", "50\n"),
+            ("mode", "0\n"),
+            ("color_left", "ff0000\n"),
+            ("color_center", "00ff00\n"),
+            ("color_right", "0000ff\n"),
+        ] {
+            fake.write(attr(name), value).unwrap();
+        }
+        let kbd = TuxedoLegacyKeyboard::with_root(fake.root());
+        (fake, kbd)
+    }
+
+    #[test]
+    fn test_detect() {
+        let fake = FakeSysfs::new().unwrap();
+        assert!(!TuxedoLegacyKeyboard::detect(fake.root()));
+        fake.write(attr("brightness"), "0").unwrap();
+        assert!(!TuxedoLegacyKeyboard::detect(fake.root()));
+        fake.write(attr("mode"), "0").unwrap();
+        fake.write(attr("color_left"), "000000").unwrap();
+        assert!(TuxedoLegacyKeyboard::detect(fake.root()));
+    }
+
+    #[test]
+    fn test_zone_colors() {
+        let (fake, kbd) = setup();
+        let names: Vec<String> = kbd.zones().into_iter().map(|z| z.name).collect();
+        assert_eq!(names, ["left", "center", "right"]);
+        assert_eq!(kbd.get_zone_rgb(1).unwrap(), Rgb { r: 0, g: 255, b: 0 });
+
+        kbd.set_zone_rgb(2, Rgb { r: 1, g: 2, b: 3 }).unwrap();
+        assert_eq!(fake.read(attr("color_right")).unwrap(), "0x010203");
+        kbd.set_rgb(Rgb {
+            r: 255,
+            g: 128,
+            b: 0,
+        })
+        .unwrap();
+        assert_eq!(fake.read(attr("color_left")).unwrap(), "0xff8000");
+        assert_eq!(
+            kbd.get_rgb().unwrap(),
+            Rgb {
+                r: 255,
+                g: 128,
+                b: 0
+            }
+        );
+
+        assert!(matches!(
+            kbd.get_zone_rgb(3),
+            Err(M4ArchError::InvalidZone(3))
+        ));
+    }
+
+    #[test]
+    fn test_modes_and_brightness() {
+        let (fake, kbd) = setup();
+        assert_eq!(kbd.modes().len(), 8);
+        assert_eq!(kbd.get_mode().unwrap(), KeyboardMode::Static);
+        kbd.set_mode(KeyboardMode::Wave).unwrap();
+        assert_eq!(fake.read(attr("mode")).unwrap(), "7");
+
+        let info = kbd.brightness_info().unwrap();
+        assert_eq!((info.current, info.max), (50, 255));
+        kbd.set_brightness(0).unwrap();
+        assert_eq!(fake.read(attr("brightness")).unwrap(), "0");
+    }
+
+    #[test]
+    fn test_parse_color() {
+        assert_eq!(
+            parse_color("0x0a0b0c").unwrap(),
+            Rgb {
+                r: 10,
+                g: 11,
+                b: 12
+            }
+        );
+        assert!(parse_color("1000000").is_err());
+        assert!(parse_color("red").is_err());
+    }
+}
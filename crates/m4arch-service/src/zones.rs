@@ -0,0 +1,162 @@
+//! Penggabungan beberapa LED menjadi satu keyboard multi-zona.
+//!
+//! Chassis Clevo dengan `tuxedo_keyboard` sering memublikasikan
+//! `rgb:kbd_backlight`, `rgb:kbd_backlight_1`, `rgb:kbd_backlight_2` untuk
+//! zona kiri/tengah/kanan. `ZonedKeyboard` menyatukannya di balik satu
+//! `KeyboardController`.
+use crate::KeyboardController;
+use m4arch_core::error::{M4ArchError, Result};
+use m4arch_core::keyboard::{
+    KeyboardBrightness, KeyboardCapabilities, KeyboardRgb, KeyboardZone, Rgb,
+};
+
+/// Prefix nama LED RGB yang bisa memiliki beberapa zona.
+const ZONE_LED_PREFIX: &str = "rgb:kbd_backlight";
+
+/// Mengembalikan indeks zona dari nama LED.
+///
+/// `rgb:kbd_backlight` adalah zona 0, `rgb:kbd_backlight_N` adalah zona N.
+pub fn zone_index(led_name: &str) -> Option<usize> {
+    let suffix = led_name.strip_prefix(ZONE_LED_PREFIX)?;
+    if suffix.is_empty() {
+        return Some(0);
+    }
+    suffix.strip_prefix('_')?.parse().ok()
+}
+
+/// Keyboard yang terdiri dari beberapa LED, satu per zona.
+pub struct ZonedKeyboard {
+    zones: Vec<Box<dyn KeyboardController>>,
+}
+
+impl ZonedKeyboard {
+    /// Menggabungkan controller per zona (sudah terurut berdasarkan indeks).
+    pub fn new(zones: Vec<Box<dyn KeyboardController>>) -> Self {
+        Self { zones }
+    }
+
+    /// Mengembalikan satu controller: langsung jika hanya ada satu zona,
+    /// atau `ZonedKeyboard` jika lebih dari satu.
+    pub fn combine(
+        mut zones: Vec<Box<dyn KeyboardController>>,
+    ) -> Option<Box<dyn KeyboardController>> {
+        match zones.len() {
+            0 => None,
+            1 => zones.pop(),
+            _ => Some(Box::new(Self::new(zones))),
+        }
+    }
+
+    fn zone(&self, zone: usize) -> Result<&dyn KeyboardController> {
+        self.zones
+            .get(zone)
+            .map(|z| z.as_ref())
+            .ok_or(M4ArchError::InvalidZone(zone))
+    }
+}
+
+impl KeyboardController for ZonedKeyboard {
+    fn capabilities(&self) -> KeyboardCapabilities {
+        self.zones
+            .first()
+            .map(|zone| zone.capabilities())
+            .unwrap_or_default()
+    }
+
+    fn brightness_hw_changed(&self) -> Result<Option<u8>> {
+        self.zone(0)?.brightness_hw_changed()
+    }
+}
+
+impl KeyboardBrightness for ZonedKeyboard {
+    // Zona pertama dijadikan acuan; semua zona diubah bersamaan
+    fn get_brightness(&self) -> Result<u8> {
+        self.zone(0)?.get_brightness()
+    }
+
+    fn get_max_brightness(&self) -> Result<u8> {
+        self.zone(0)?.get_max_brightness()
+    }
+
+    fn set_brightness(&self, level: u8) -> Result<()> {
+        self.zones
+            .iter()
+            .try_for_each(|zone| zone.set_brightness(level))
+    }
+}
+
+impl KeyboardRgb for ZonedKeyboard {
+    fn get_rgb(&self) -> Result<Rgb> {
+        self.get_zone_rgb(0)
+    }
+
+    fn set_rgb(&self, rgb: Rgb) -> Result<()> {
+        self.zones.iter().try_for_each(|zone| zone.set_rgb(rgb))
+    }
+
+    fn zones(&self) -> Vec<KeyboardZone> {
+        (0..self.zones.len())
+            .map(|index| KeyboardZone::standard(index, self.zones.len()))
+            .collect()
+    }
+
+    fn get_zone_rgb(&self, zone: usize) -> Result<Rgb> {
+        self.zone(zone)?.get_rgb()
+    }
+
+    fn set_zone_rgb(&self, zone: usize, rgb: Rgb) -> Result<()> {
+        self.zone(zone)?.set_rgb(rgb)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sysfs::fake::{FakeLed, FakeSysfs};
+    use crate::sysfs::keyboard::SysfsKeyboard;
+
+    #[test]
+    fn test_zone_index() {
+        assert_eq!(zone_index("rgb:kbd_backlight"), Some(0));
+        assert_eq!(zone_index("rgb:kbd_backlight_2"), Some(2));
+        assert_eq!(zone_index("rgb:kbd_backlightx"), None);
+        assert_eq!(zone_index("white:kbd_backlight"), None);
+    }
+
+    #[test]
+    fn test_zoned_keyboard() {
+        let fake = FakeSysfs::new().unwrap();
+        let zones = (0..3)
+            .map(|i| {
+                let dir = fake
+                    .add_led(format!("zone{i}"), &FakeLed::rgb(5, 255, (i, i, i)))
+                    .unwrap();
+                Box::new(SysfsKeyboard::from_led_dir(dir)) as Box<dyn KeyboardController>
+            })
+            .collect();
+        let kbd = ZonedKeyboard::new(zones);
+
+        let names: Vec<String> = kbd.zones().into_iter().map(|z| z.name).collect();
+        assert_eq!(names, ["left", "center", "right"]);
+        assert_eq!(kbd.get_zone_rgb(2).unwrap(), Rgb { r: 2, g: 2, b: 2 });
+
+        kbd.set_zone_rgb(1, Rgb { r: 9, g: 8, b: 7 }).unwrap();
+        assert_eq!(fake.read("zone1/multi_intensity").unwrap(), "9 8 7");
+        assert_eq!(fake.read("zone0/multi_intensity").unwrap(), "0 0 0");
+
+        kbd.set_rgb(Rgb { r: 1, g: 1, b: 1 }).unwrap();
+        kbd.set_brightness(200).unwrap();
+        for i in 0..3 {
+            assert_eq!(
+                fake.read(format!("zone{i}/multi_intensity")).unwrap(),
+                "1 1 1"
+            );
+            assert_eq!(fake.read(format!("zone{i}/brightness")).unwrap(), "200");
+        }
+
+        assert!(matches!(
+            kbd.set_zone_rgb(3, Rgb { r: 0, g: 0, b: 0 }),
+            Err(M4ArchError::InvalidZone(3))
+        ));
+    }
+}
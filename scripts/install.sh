@@ -5,6 +5,8 @@
 # Skrip ini akan:
 # 1. Menyalin aturan udev untuk memberikan izin akses keyboard tanpa sudo.
 # 2. Memuat ulang aturan udev agar langsung aktif.
+# 3. Membuat grup 'm4arch' yang anggotanya boleh mengatur kipas, profil
+#    daya, tuning CPU dan ambang pengisian baterai lewat m4arch-daemon.
 
 set -e
 
@@ -19,5 +21,10 @@ echo "Memuat ulang aturan udev..."
 sudo udevadm control --reload-rules
 sudo udevadm trigger
 
+echo "Membuat grup m4arch..."
+sudo groupadd -rf m4arch
+
 echo "Instalasi selesai. Izin keyboard seharusnya sudah aktif."
-echo "Pastikan user Anda adalah anggota grup 'users'."
\ No newline at end of file
+echo "Pastikan user Anda adalah anggota grup 'users'."
+echo "Tambahkan user ke grup 'm4arch' (sudo usermod -aG m4arch \$USER) untuk"
+echo "mengatur kipas, daya, CPU dan baterai, lalu login ulang dan mulai ulang m4arch-daemon."
\ No newline at end of file